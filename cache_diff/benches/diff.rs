@@ -0,0 +1,122 @@
+//! Measures diffing small, medium, and large structs with and without differences
+//!
+//! Run with `cargo bench --package cache_diff --bench diff`. To check a performance motivated change, save a
+//! baseline before it with `-- --save-baseline main` and compare against it after with
+//! `-- --baseline main`. Add `--features bullet_stream` to include the `bullet_stream` styled case.
+
+use cache_diff::{CacheDiff, DiffStyle};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+#[derive(Clone, CacheDiff)]
+struct Small {
+    version: String,
+}
+
+macro_rules! medium {
+    ($name:ident $(, $style:path)?) => {
+        #[derive(Clone, CacheDiff)]
+        $(#[cache_diff(style = $style)])?
+        struct $name {
+            version: String,
+            distro: String,
+            distro_version: String,
+            arch: String,
+            bundler_version: String,
+            node_version: String,
+            yarn_version: String,
+            cache_usage_count: u32,
+        }
+
+        impl $name {
+            fn new(version: &str) -> Self {
+                Self {
+                    version: version.to_string(),
+                    distro: "ubuntu".to_string(),
+                    distro_version: "24.04".to_string(),
+                    arch: "amd64".to_string(),
+                    bundler_version: "2.5.6".to_string(),
+                    node_version: "22.1.0".to_string(),
+                    yarn_version: "1.22.22".to_string(),
+                    cache_usage_count: 1,
+                }
+            }
+        }
+    };
+}
+
+medium!(Medium, DiffStyle::Backticks);
+#[cfg(feature = "bullet_stream")]
+medium!(BulletStream, DiffStyle::BulletStream);
+
+macro_rules! large {
+    ($($field:ident),*) => {
+        #[derive(Clone, CacheDiff)]
+        struct Large {
+            $($field: String),*
+        }
+
+        impl Large {
+            fn new(value: &str) -> Self {
+                Self {
+                    $($field: value.to_string()),*
+                }
+            }
+        }
+    };
+}
+
+large!(
+    f01, f02, f03, f04, f05, f06, f07, f08, f09, f10, f11, f12, f13, f14, f15, f16, f17, f18, f19,
+    f20, f21, f22, f23, f24, f25, f26, f27, f28, f29, f30, f31, f32
+);
+
+/// Benchmark one struct size both when the cache is kept and when it is invalidated
+fn bench_pair<T: CacheDiff>(c: &mut Criterion, name: &str, now: &T, unchanged: &T, changed: &T) {
+    let mut group = c.benchmark_group(name);
+    for (case, old) in [("unchanged", unchanged), ("changed", changed)] {
+        group.bench_with_input(BenchmarkId::from_parameter(case), old, |b, old| {
+            b.iter(|| black_box(now).diff(black_box(old)))
+        });
+    }
+    group.finish();
+}
+
+fn diff(c: &mut Criterion) {
+    let small = Small {
+        version: "3.4.0".to_string(),
+    };
+    bench_pair(
+        c,
+        "small",
+        &small,
+        &small.clone(),
+        &Small {
+            version: "3.3.0".to_string(),
+        },
+    );
+    bench_pair(
+        c,
+        "medium",
+        &Medium::new("3.4.0"),
+        &Medium::new("3.4.0"),
+        &Medium::new("3.3.0"),
+    );
+    #[cfg(feature = "bullet_stream")]
+    bench_pair(
+        c,
+        "bullet_stream",
+        &BulletStream::new("3.4.0"),
+        &BulletStream::new("3.4.0"),
+        &BulletStream::new("3.3.0"),
+    );
+    bench_pair(
+        c,
+        "large",
+        &Large::new("a"),
+        &Large::new("a"),
+        &Large::new("b"),
+    );
+}
+
+criterion_group!(benches, diff);
+criterion_main!(benches);
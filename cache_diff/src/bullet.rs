@@ -0,0 +1,36 @@
+//! Print differences with [`bullet_stream`](https://github.com/heroku-buildpacks/bullet_stream)
+
+use crate::Diff;
+use bullet_stream::state::SubBullet;
+use bullet_stream::Print;
+use std::io::Write;
+
+/// Write each difference as a sub-bullet on an existing `bullet_stream` output
+///
+/// ```rust,no_run
+/// use bullet_stream::Print;
+/// use cache_diff::CacheDiff;
+///
+/// #[derive(CacheDiff)]
+/// struct Metadata {
+///     version: String,
+/// }
+/// let now = Metadata { version: "3.4.0".to_string() };
+///
+/// let mut bullet = Print::new(std::io::stdout())
+///     .h2("Ruby")
+///     .bullet("Ruby version");
+/// if let Some(diff) = now.changes(&Metadata { version: "3.3.0".to_string() }) {
+///     bullet = cache_diff::bullet::print_diff(bullet.sub_bullet("Clearing cache"), &diff);
+/// }
+/// bullet.done();
+/// ```
+pub fn print_diff<W>(mut bullet: Print<SubBullet<W>>, diff: &Diff) -> Print<SubBullet<W>>
+where
+    W: Write + Send + Sync + 'static,
+{
+    for line in diff.lines() {
+        bullet = bullet.sub_bullet(line);
+    }
+    bullet
+}
@@ -0,0 +1,98 @@
+//! Arguments for a container's `#[cache_diff(custom = <function>)]`
+
+use std::fmt::Debug;
+
+/// The old and current values passed to a `#[cache_diff(custom = <function>)]` by name
+///
+/// A custom function can take `(old: &T, now: &T)` or a single `DiffArgs<T>`. Positional arguments
+/// are easy to swap by accident, which silently inverts the messages, named fields are not:
+///
+/// ```rust
+/// use cache_diff::{CacheDiff, DiffArgs};
+///
+/// #[derive(CacheDiff)]
+/// #[cache_diff(custom = diff_os)]
+/// struct Metadata {
+///     version: String,
+///     #[cache_diff(ignore = "custom")]
+///     distro_name: String,
+///     #[cache_diff(ignore = "custom")]
+///     distro_version: String,
+/// }
+///
+/// fn diff_os(DiffArgs { old, now }: DiffArgs<Metadata>) -> Vec<String> {
+///     if old.distro_name != now.distro_name || old.distro_version != now.distro_version {
+///         vec![format!(
+///             "OS (`{} {}` to `{} {}`)",
+///             old.distro_name, old.distro_version, now.distro_name, now.distro_version
+///         )]
+///     } else {
+///         Vec::new()
+///     }
+/// }
+///
+/// let metadata = |distro_version: &str| Metadata {
+///     version: "3.4.0".to_string(),
+///     distro_name: "ubuntu".to_string(),
+///     distro_version: distro_version.to_string(),
+/// };
+/// let old = metadata("22.04");
+/// let now = metadata("24.04");
+/// assert_eq!(now.diff(&old), vec!["OS (`ubuntu 22.04` to `ubuntu 24.04`)".to_string()]);
+/// ```
+pub struct DiffArgs<'a, T: ?Sized> {
+    /// The value from the previous build
+    pub old: &'a T,
+    /// The value from the current build
+    pub now: &'a T,
+}
+
+impl<T: ?Sized> Clone for DiffArgs<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: ?Sized> Copy for DiffArgs<'_, T> {}
+
+impl<T: Debug + ?Sized> Debug for DiffArgs<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DiffArgs")
+            .field("old", &self.old)
+            .field("now", &self.now)
+            .finish()
+    }
+}
+
+/// Marks a custom function that takes `(old: &T, now: &T)`
+#[doc(hidden)]
+pub struct Positional;
+
+/// Marks a custom function that takes `DiffArgs<T>`
+#[doc(hidden)]
+pub struct Named;
+
+/// Functions accepted by `#[cache_diff(custom = <function>)]`, the marker picks the signature
+///
+/// Generated code calls it as `(old, now)` regardless of which signature the function has.
+#[doc(hidden)]
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` cannot be used as `#[cache_diff(custom = <function>)]`",
+    label = "wrong signature",
+    note = "use a function `fn(old: &{T}, now: &{T}) -> Vec<String>` or `fn(cache_diff::DiffArgs<{T}>) -> Vec<String>`"
+)]
+pub trait CustomDiff<T: ?Sized, Marker> {
+    fn call_custom(&self, old: &T, now: &T) -> Vec<String>;
+}
+
+impl<T: ?Sized, F: Fn(&T, &T) -> Vec<String>> CustomDiff<T, Positional> for F {
+    fn call_custom(&self, old: &T, now: &T) -> Vec<String> {
+        self(old, now)
+    }
+}
+
+impl<T: ?Sized, F: Fn(DiffArgs<'_, T>) -> Vec<String>> CustomDiff<T, Named> for F {
+    fn call_custom(&self, old: &T, now: &T) -> Vec<String> {
+        self(DiffArgs { old, now })
+    }
+}
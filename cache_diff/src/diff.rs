@@ -0,0 +1,128 @@
+//! A non-empty collection of differences returned by [CacheDiff::changes](crate::CacheDiff::changes)
+
+use crate::{DiffStyle, Difference, Renderer, Template};
+use std::fmt::Display;
+
+/// One or more differences between two cache structs
+///
+/// Returned by [CacheDiff::changes](crate::CacheDiff::changes) when the cache should be invalidated.
+/// It displays as one difference per line:
+///
+/// ```rust
+/// use cache_diff::CacheDiff;
+///
+/// #[derive(CacheDiff)]
+/// struct Metadata {
+///     version: String,
+/// }
+/// let now = Metadata { version: "3.4.0".to_string() };
+///
+/// if let Some(diff) = now.changes(&Metadata { version: "3.3.0".to_string() }) {
+///     assert_eq!(diff.len(), 1);
+///     assert_eq!(diff.to_string(), "- version (`3.3.0` to `3.4.0`)");
+/// } else {
+///     panic!("Expected a difference");
+/// }
+/// ```
+///
+/// With the `serde` feature, it serializes as a list of [Difference]-s. The style and template
+/// are not serialized, a deserialized diff uses the defaults.
+///
+/// With the `smallvec` feature, up to four differences are stored inline instead of on the heap.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(from = "Vec<Difference>", into = "Vec<Difference>")
+)]
+pub struct Diff {
+    differences: DifferenceList,
+    style: DiffStyle,
+    template: Template,
+}
+
+/// Most diffs have one or two differences, the `smallvec` feature keeps them off the heap
+#[cfg(feature = "smallvec")]
+type DifferenceList = smallvec::SmallVec<[Difference; 4]>;
+#[cfg(not(feature = "smallvec"))]
+type DifferenceList = Vec<Difference>;
+
+impl Diff {
+    pub fn is_empty(&self) -> bool {
+        self.differences.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.differences.len()
+    }
+
+    /// Display values with the given style instead of the default
+    pub fn with_style(mut self, style: DiffStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Render changed fields with the given template instead of the installed one
+    pub fn with_template(mut self, template: Template) -> Self {
+        self.template = template;
+        self
+    }
+
+    /// Each difference rendered with the style and template of the struct that produced it
+    pub fn lines(&self) -> Vec<String> {
+        self.differences
+            .iter()
+            .map(|difference| {
+                difference.render_styled(
+                    &self.template,
+                    |value| self.style.fmt_value(&value),
+                    |name| self.style.fmt_name(name),
+                )
+            })
+            .collect()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Difference> {
+        self.differences.iter()
+    }
+}
+
+impl From<Vec<Difference>> for Diff {
+    fn from(differences: Vec<Difference>) -> Self {
+        Self {
+            differences: differences.into_iter().collect(),
+            style: DiffStyle::default(),
+            template: Template::current(),
+        }
+    }
+}
+
+impl From<Diff> for Vec<Difference> {
+    fn from(diff: Diff) -> Self {
+        diff.differences.into_iter().collect()
+    }
+}
+
+impl Display for Diff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&Renderer::new().render(self.lines()))
+    }
+}
+
+impl IntoIterator for Diff {
+    type Item = Difference;
+    type IntoIter = std::vec::IntoIter<Difference>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Vec::from(self).into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Diff {
+    type Item = &'a Difference;
+    type IntoIter = std::slice::Iter<'a, Difference>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.differences.iter()
+    }
+}
@@ -0,0 +1,48 @@
+//! The result of diffing against metadata that may not exist
+
+/// Returned by [CacheDiff::diff_or_new](crate::CacheDiff::diff_or_new)
+///
+/// ```rust
+/// use cache_diff::{CacheDiff, DiffOrNew};
+///
+/// #[derive(CacheDiff)]
+/// struct Metadata {
+///     version: String,
+/// }
+/// let now = Metadata { version: "3.4.0".to_string() };
+/// let old = Metadata { version: "3.3.0".to_string() };
+///
+/// assert_eq!(now.diff_or_new(None), DiffOrNew::New);
+/// assert_eq!(
+///     now.diff_or_new(Some(&old)),
+///     DiffOrNew::Diff(vec!["version (`3.3.0` to `3.4.0`)".to_string()])
+/// );
+/// assert!(now.diff_or_new(None).invalidates());
+/// assert!(!now.diff_or_new(Some(&now)).invalidates());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffOrNew {
+    /// There is no previous cache to compare against
+    New,
+    /// The differences against the previous cache, empty when it can be kept
+    Diff(Vec<String>),
+}
+
+impl DiffOrNew {
+    /// Returns `true` when there is nothing to keep, either because there is no previous cache or
+    /// because it differs
+    pub fn invalidates(&self) -> bool {
+        match self {
+            DiffOrNew::New => true,
+            DiffOrNew::Diff(differences) => !differences.is_empty(),
+        }
+    }
+
+    /// The differences, `None` when there is no previous cache
+    pub fn differences(&self) -> Option<&[String]> {
+        match self {
+            DiffOrNew::New => None,
+            DiffOrNew::Diff(differences) => Some(differences),
+        }
+    }
+}
@@ -0,0 +1,419 @@
+//! Structured representation of a single difference between two cache structs
+
+use crate::{DiffStyle, Template};
+use std::borrow::Cow;
+use std::fmt::Display;
+
+/// One reason the cache should be invalidated
+///
+/// Derived implementations produce a difference per changed field containing the field's name,
+/// and the old and new values. Differences returned from a container's `custom = <function>` or
+/// from a manual [CacheDiff::diff](crate::CacheDiff::diff) implementation are free form messages.
+///
+/// ```rust
+/// use cache_diff::Difference;
+///
+/// let difference = Difference::changed("version", "3.3.0", "3.4.0").with_category("Runtime");
+/// assert_eq!(difference.name(), Some("version"));
+/// assert_eq!(difference.category(), Some("Runtime"));
+/// assert_eq!(difference.to_string(), "version (`3.3.0` to `3.4.0`)");
+///
+/// let difference = Difference::message("Cache count (201) exceeded limit 200");
+/// assert_eq!(difference.name(), None);
+/// assert_eq!(difference.to_string(), "Cache count (201) exceeded limit 200");
+/// ```
+///
+/// Differences from a derived implementation also carry the field's Rust identifier, which stays the
+/// same when the field is renamed for display:
+///
+/// ```rust
+/// use cache_diff::CacheDiff;
+///
+/// #[derive(CacheDiff)]
+/// struct Metadata {
+///     #[cache_diff(rename = "OS version")]
+///     os_version: String,
+/// }
+/// let diff = Metadata { os_version: "24.04".to_string() }
+///     .diff_structured(&Metadata { os_version: "22.04".to_string() });
+///
+/// assert_eq!(diff[0].identifier(), Some("os_version"));
+/// assert_eq!(diff[0].name(), Some("OS version"));
+/// ```
+///
+/// With the `serde` feature, differences can be serialized, for example to persist the reason for
+/// the last rebuild in a layer's metadata.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Difference {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    kind: Kind,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    category: Option<Cow<'static, str>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    code: Option<Cow<'static, str>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    identifier: Option<Cow<'static, str>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    labels: Option<(Cow<'static, str>, Cow<'static, str>)>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    parents: Vec<Cow<'static, str>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    template: Option<Cow<'static, str>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(untagged))]
+enum Kind {
+    Changed {
+        name: Cow<'static, str>,
+        old: String,
+        now: String,
+    },
+    Message {
+        message: String,
+    },
+}
+
+impl Difference {
+    /// A named value changed from `old` to `now`
+    ///
+    /// A `&'static str` name, such as one generated by the derive macro, is stored without allocating.
+    pub fn changed(
+        name: impl Into<Cow<'static, str>>,
+        old: impl Into<String>,
+        now: impl Into<String>,
+    ) -> Self {
+        Self {
+            kind: Kind::Changed {
+                name: name.into(),
+                old: old.into(),
+                now: now.into(),
+            },
+            category: None,
+            code: None,
+            identifier: None,
+            labels: None,
+            parents: Vec::new(),
+            template: None,
+        }
+    }
+
+    /// A free form description of a difference
+    pub fn message(message: impl Into<String>) -> Self {
+        Self {
+            kind: Kind::Message {
+                message: message.into(),
+            },
+            category: None,
+            code: None,
+            identifier: None,
+            labels: None,
+            parents: Vec::new(),
+            template: None,
+        }
+    }
+
+    /// Group the difference under a heading i.e. `"Operating system"`
+    pub fn with_category(mut self, category: impl Into<Cow<'static, str>>) -> Self {
+        self.category = Some(category.into());
+        self
+    }
+
+    /// A stable machine readable code for the difference i.e. `"ruby_version"`
+    pub fn with_code(mut self, code: impl Into<Cow<'static, str>>) -> Self {
+        self.code = Some(code.into());
+        self
+    }
+
+    /// The Rust identifier of the field that changed i.e. `os_version`
+    ///
+    /// Derived implementations set it for every changed field, so machine consumers can key on it
+    /// while [Difference::name] holds the renamed label shown to users i.e. `OS version`.
+    pub fn with_identifier(mut self, identifier: impl Into<Cow<'static, str>>) -> Self {
+        self.identifier = Some(identifier.into());
+        self
+    }
+
+    /// Words placed before the old and new values i.e. `("was", "now")`
+    ///
+    /// A changed value with labels is rendered as `{name} ({old label} {old}, {new label} {new})`
+    /// instead of with the template, they have no effect on messages:
+    ///
+    /// ```rust
+    /// use cache_diff::Difference;
+    ///
+    /// let difference = Difference::changed("stack", "heroku-22", "heroku-24").with_labels("was", "now");
+    /// assert_eq!(difference.to_string(), "stack (was `heroku-22`, now `heroku-24`)");
+    /// ```
+    ///
+    /// When deriving, set them with `#[cache_diff(labels = "was/now")]` on the field.
+    pub fn with_labels(
+        mut self,
+        old: impl Into<Cow<'static, str>>,
+        now: impl Into<Cow<'static, str>>,
+    ) -> Self {
+        self.labels = Some((old.into(), now.into()));
+        self
+    }
+
+    /// A [Template] for just this difference i.e. `"{name} changed from {old} to {new}"`
+    ///
+    /// It replaces the template passed to [Difference::render], [Difference::with_labels] still take
+    /// priority. A template that does not parse is ignored:
+    ///
+    /// ```rust
+    /// use cache_diff::Difference;
+    ///
+    /// let difference = Difference::changed("stack", "heroku-22", "heroku-24")
+    ///     .with_template("{name} changed from {old} to {new}");
+    /// assert_eq!(difference.to_string(), "stack changed from `heroku-22` to `heroku-24`");
+    /// ```
+    ///
+    /// When deriving, set it with `#[cache_diff(fmt = "<template>")]` on the field.
+    pub fn with_template(mut self, template: impl Into<Cow<'static, str>>) -> Self {
+        self.template = Some(template.into());
+        self
+    }
+
+    /// Place the difference under a parent i.e. the field holding a nested struct
+    ///
+    /// Parents are added from the inside out, each call goes in front of the previous ones:
+    ///
+    /// ```rust
+    /// use cache_diff::Difference;
+    ///
+    /// let difference = Difference::changed("version", "3.3.0", "3.4.0")
+    ///     .with_identifier("version")
+    ///     .with_parent("ruby")
+    ///     .with_parent("runtime");
+    /// assert_eq!(difference.path(), vec!["runtime", "ruby", "version"]);
+    /// ```
+    ///
+    /// When deriving, differences of a `#[cache_diff(nested)]` field get the field's identifier as a parent.
+    pub fn with_parent(mut self, parent: impl Into<Cow<'static, str>>) -> Self {
+        self.parents.insert(0, parent.into());
+        self
+    }
+
+    /// The name of the changed value, `None` for messages
+    pub fn name(&self) -> Option<&str> {
+        match &self.kind {
+            Kind::Changed { name, .. } => Some(name),
+            Kind::Message { .. } => None,
+        }
+    }
+
+    /// The previous value without styling, `None` for messages
+    pub fn old(&self) -> Option<&str> {
+        match &self.kind {
+            Kind::Changed { old, .. } => Some(old),
+            Kind::Message { .. } => None,
+        }
+    }
+
+    /// The current value without styling, `None` for messages
+    pub fn now(&self) -> Option<&str> {
+        match &self.kind {
+            Kind::Changed { now, .. } => Some(now),
+            Kind::Message { .. } => None,
+        }
+    }
+
+    pub fn category(&self) -> Option<&str> {
+        self.category.as_deref()
+    }
+
+    pub fn code(&self) -> Option<&str> {
+        self.code.as_deref()
+    }
+
+    /// The Rust identifier of the changed field, see [Difference::with_identifier]
+    pub fn identifier(&self) -> Option<&str> {
+        self.identifier.as_deref()
+    }
+
+    /// The parents from [Difference::with_parent], outermost first
+    pub fn parents(&self) -> Vec<&str> {
+        self.parents.iter().map(AsRef::as_ref).collect()
+    }
+
+    /// The parents from [Difference::with_parent] followed by the identifier, code, or name of the
+    /// difference, whichever is set first
+    ///
+    /// Messages without an identifier or code only contain their parents.
+    pub fn path(&self) -> Vec<&str> {
+        let leaf = self
+            .identifier
+            .as_deref()
+            .or(self.code.as_deref())
+            .or(self.name());
+        self.parents.iter().map(AsRef::as_ref).chain(leaf).collect()
+    }
+
+    /// The template of this difference, see [Difference::with_template]
+    pub fn template(&self) -> Option<&str> {
+        self.template.as_deref()
+    }
+
+    /// The old and new value labels, see [Difference::with_labels]
+    pub fn labels(&self) -> Option<(&str, &str)> {
+        self.labels
+            .as_ref()
+            .map(|(old, now)| (old.as_ref(), now.as_ref()))
+    }
+
+    /// Key-value pairs describing the difference for structured exports, absent values are skipped
+    #[cfg(any(feature = "toml", feature = "serde_json"))]
+    pub(crate) fn entries(&self) -> Vec<(&'static str, &str)> {
+        let mut entries = match &self.kind {
+            Kind::Changed { name, old, now } => {
+                vec![("name", name.as_ref()), ("old", old), ("now", now)]
+            }
+            Kind::Message { message } => vec![("message", message.as_str())],
+        };
+        entries.extend(
+            self.identifier
+                .as_deref()
+                .map(|identifier| ("identifier", identifier)),
+        );
+        entries.extend(self.code.as_deref().map(|code| ("code", code)));
+        entries.extend(
+            self.category
+                .as_deref()
+                .map(|category| ("category", category)),
+        );
+        entries
+    }
+
+    /// Render into a human readable string using the template, styling values with the given function
+    ///
+    /// Derived implementations use this with [CacheDiff::template](crate::CacheDiff::template) and
+    /// [CacheDiff::fmt_value](crate::CacheDiff::fmt_value) to produce the output of
+    /// [CacheDiff::diff](crate::CacheDiff::diff).
+    pub fn render(&self, template: &Template, fmt_value: impl Fn(&str) -> String) -> String {
+        self.render_styled(template, fmt_value, |name| name.to_string())
+    }
+
+    /// Like [Difference::render] but also styles the field's name with `fmt_name`
+    ///
+    /// Derived implementations pass [CacheDiff::fmt_name](crate::CacheDiff::fmt_name), which only
+    /// changes the name with [DiffStyle::OwoColors].
+    pub fn render_styled(
+        &self,
+        template: &Template,
+        fmt_value: impl Fn(&str) -> String,
+        fmt_name: impl Fn(&str) -> String,
+    ) -> String {
+        let mut out = String::new();
+        self.write_styled(&mut out, template, fmt_value, fmt_name)
+            .expect("Writing to a String cannot fail");
+        out
+    }
+
+    /// Like [Difference::render] but writes directly into `w` instead of allocating a String
+    pub fn write_to<W: std::fmt::Write + ?Sized>(
+        &self,
+        w: &mut W,
+        template: &Template,
+        fmt_value: impl Fn(&str) -> String,
+    ) -> std::fmt::Result {
+        self.write_styled(w, template, fmt_value, |name| name.to_string())
+    }
+
+    /// Like [Difference::render_styled] but writes directly into `w` instead of allocating a String
+    pub fn write_styled<W: std::fmt::Write + ?Sized>(
+        &self,
+        w: &mut W,
+        template: &Template,
+        fmt_value: impl Fn(&str) -> String,
+        fmt_name: impl Fn(&str) -> String,
+    ) -> std::fmt::Result {
+        match (&self.kind, &self.labels) {
+            (Kind::Changed { name, old, now }, Some((old_label, now_label))) => write!(
+                w,
+                "{} ({old_label} {}, {now_label} {})",
+                fmt_name(name),
+                fmt_value(old),
+                fmt_value(now)
+            ),
+            (Kind::Changed { name, old, now }, None) => {
+                let own = self
+                    .template
+                    .as_deref()
+                    .and_then(|own| Template::parse(own).ok());
+                own.as_ref().unwrap_or(template).write_to(
+                    w,
+                    &fmt_name(name),
+                    &fmt_value(old),
+                    &fmt_value(now),
+                    self.code.as_deref().unwrap_or_default(),
+                )
+            }
+            (Kind::Message { message }, _) => w.write_str(message),
+        }
+    }
+}
+
+impl Display for Difference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let style = DiffStyle::default();
+        self.write_styled(
+            f,
+            &Template::current(),
+            |value| style.fmt_value(&value),
+            |name| style.fmt_name(name),
+        )
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_serde_round_trip() {
+        let differences = vec![
+            Difference::changed("version", "3.3.0", "3.4.0")
+                .with_code("version")
+                .with_category("Runtime"),
+            Difference::changed("stack", "heroku-22", "heroku-24").with_labels("was", "now"),
+            Difference::changed("version", "3.3.0", "3.4.0").with_parent("ruby"),
+            Difference::changed("version", "3.3.0", "3.4.0")
+                .with_template("{name}: {old} => {new}"),
+            Difference::message("Cache count (201) exceeded limit 200"),
+        ];
+
+        #[derive(serde::Serialize, serde::Deserialize)]
+        struct Layer {
+            differences: Vec<Difference>,
+        }
+        let toml = toml::to_string(&Layer {
+            differences: differences.clone(),
+        })
+        .unwrap();
+        assert_eq!(
+            toml::from_str::<Layer>(&toml).unwrap().differences,
+            differences
+        );
+    }
+}
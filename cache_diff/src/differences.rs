@@ -0,0 +1,162 @@
+//! The differences returned by [CacheDiff::differences](crate::CacheDiff::differences)
+
+use crate::{CacheDiff, Difference, Renderer};
+use std::fmt::Display;
+use std::ops::Deref;
+
+/// Every difference between two cache structs, as messages and as structured [Difference]-s
+///
+/// Returned by [CacheDiff::differences](crate::CacheDiff::differences). It dereferences to the
+/// `Vec<String>` that [CacheDiff::diff](crate::CacheDiff::diff) returns, so existing code keeps
+/// working while the structured differences stay available:
+///
+/// ```rust
+/// use cache_diff::CacheDiff;
+///
+/// #[derive(CacheDiff)]
+/// struct Metadata {
+///     version: String,
+///     distro: String,
+/// }
+/// let now = Metadata { version: "3.4.0".to_string(), distro: "Ubuntu".to_string() };
+/// let differences = now.differences(&Metadata { version: "3.3.0".to_string(), distro: "Ubuntu".to_string() });
+///
+/// assert_eq!(differences.len(), 1);
+/// assert_eq!(differences.join(", "), "version (`3.3.0` to `3.4.0`)");
+/// assert_eq!(
+///     differences.iter_structured().map(|d| d.name()).collect::<Vec<_>>(),
+///     vec![Some("version")]
+/// );
+/// assert_eq!(differences.render(), "- version (`3.3.0` to `3.4.0`)");
+/// ```
+///
+/// An empty value means the cache should be retained.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Differences {
+    lines: Vec<String>,
+    structured: Vec<Difference>,
+}
+
+impl Differences {
+    /// Render each structured difference with the style and template of `cache`
+    pub(crate) fn new<T: CacheDiff + ?Sized>(cache: &T, structured: Vec<Difference>) -> Self {
+        Self {
+            lines: crate::render_differences(cache, &structured),
+            structured,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.structured.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.structured.len()
+    }
+
+    /// The differences in the same order as the messages
+    pub fn iter_structured(&self) -> std::slice::Iter<'_, Difference> {
+        self.structured.iter()
+    }
+
+    /// One bullet point per difference, see [Renderer]
+    ///
+    /// An empty string when there are no differences.
+    pub fn render(&self) -> String {
+        Renderer::new().render(&self.lines)
+    }
+
+    /// The messages, as returned by [CacheDiff::diff](crate::CacheDiff::diff)
+    pub fn into_lines(self) -> Vec<String> {
+        self.lines
+    }
+
+    /// The structured differences, as returned by [CacheDiff::diff_structured](crate::CacheDiff::diff_structured)
+    pub fn into_structured(self) -> Vec<Difference> {
+        self.structured
+    }
+}
+
+impl Deref for Differences {
+    type Target = Vec<String>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.lines
+    }
+}
+
+impl From<Differences> for Vec<String> {
+    fn from(differences: Differences) -> Self {
+        differences.lines
+    }
+}
+
+impl<S: AsRef<str>> PartialEq<Vec<S>> for Differences {
+    fn eq(&self, other: &Vec<S>) -> bool {
+        self.lines.len() == other.len()
+            && self
+                .lines
+                .iter()
+                .zip(other)
+                .all(|(line, other)| line == other.as_ref())
+    }
+}
+
+impl Display for Differences {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.render())
+    }
+}
+
+impl IntoIterator for Differences {
+    type Item = String;
+    type IntoIter = std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.lines.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Differences {
+    type Item = &'a String;
+    type IntoIter = std::slice::Iter<'a, String>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.lines.iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct Manual;
+
+    impl CacheDiff for Manual {
+        fn diff(&self, _old: &Self) -> Vec<String> {
+            vec!["Cache count (201) exceeded limit 200".to_string()]
+        }
+    }
+
+    #[test]
+    fn test_manual_implementation() {
+        let differences = Manual.differences(&Manual);
+        assert_eq!(differences, vec!["Cache count (201) exceeded limit 200"]);
+        assert_eq!(
+            differences.iter_structured().cloned().collect::<Vec<_>>(),
+            vec![Difference::message("Cache count (201) exceeded limit 200")]
+        );
+        assert_eq!(
+            differences.to_string(),
+            "- Cache count (201) exceeded limit 200"
+        );
+    }
+
+    #[test]
+    fn test_empty() {
+        let differences = Differences::default();
+        assert!(differences.is_empty());
+        assert_eq!(differences.render(), "");
+        assert_eq!(Vec::<String>::from(differences), Vec::<String>::new());
+    }
+}
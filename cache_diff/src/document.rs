@@ -0,0 +1,80 @@
+//! Walk two documents key by key, shared by the `toml` and `json` modules
+
+use crate::Difference;
+
+/// Displayed in place of a value for a key that is only present in one document
+const MISSING: &str = "(not set)";
+
+/// A value of a document format that can be compared key by key
+pub(crate) trait Document: PartialEq {
+    /// The keys in document order when the value is a table, `None` for every other value
+    fn keys(&self) -> Option<Vec<&str>>;
+
+    /// The value of a key when the value is a table
+    fn get(&self, key: &str) -> Option<&Self>;
+
+    /// The name of the value's type in the format i.e. `string` or `table`
+    fn type_name(&self) -> &'static str;
+
+    /// Strings are shown without quotes, everything else in the format's own syntax
+    fn display(&self) -> String;
+}
+
+/// A difference for every key that differs, nested keys are named with dots i.e. `ruby.version`
+///
+/// With `schema`, keys that were added or removed and values whose type changed are described as
+/// messages with the dotted key as their code instead.
+pub(crate) fn diff_documents<V: Document>(old: &V, now: &V, schema: bool) -> Vec<Difference> {
+    let mut differences = Vec::new();
+    diff_at(
+        &mut Vec::new(),
+        Some(old),
+        Some(now),
+        schema,
+        &mut differences,
+    );
+    differences
+}
+
+fn diff_at<'a, V: Document>(
+    path: &mut Vec<&'a str>,
+    old: Option<&'a V>,
+    now: Option<&'a V>,
+    schema: bool,
+    differences: &mut Vec<Difference>,
+) {
+    if let (Some(old), Some(now)) = (old, now) {
+        if let (Some(mut keys), Some(now_keys)) = (old.keys(), now.keys()) {
+            keys.extend(now_keys.into_iter().filter(|key| old.get(key).is_none()));
+            for key in keys {
+                path.push(key);
+                diff_at(path, old.get(key), now.get(key), schema, differences);
+                path.pop();
+            }
+            return;
+        }
+    }
+    match (old, now) {
+        (old, now) if schema && old.map(V::type_name) != now.map(V::type_name) => {
+            let key = path.join(".");
+            let message = match (old, now) {
+                (None, _) => format!("Field `{key}` added"),
+                (_, None) => format!("Field `{key}` removed"),
+                (Some(old), Some(now)) => format!(
+                    "Field `{key}` changed type from {} to {}",
+                    old.type_name(),
+                    now.type_name()
+                ),
+            };
+            differences.push(Difference::message(message).with_code(key));
+        }
+        (old, now) if old != now => {
+            differences.push(Difference::changed(
+                path.join("."),
+                old.map_or_else(|| MISSING.to_string(), V::display),
+                now.map_or_else(|| MISSING.to_string(), V::display),
+            ));
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,238 @@
+//! Diff environment variables captured by two builds
+
+use crate::{CacheDiff, Difference, RedactionPolicy};
+use std::collections::BTreeMap;
+
+/// Displayed in place of a value for a variable that is only set in one snapshot
+pub(crate) const MISSING: &str = "(not set)";
+
+/// Environment variables captured by a build, to compare against the previous build's
+///
+/// Variables are compared by name in sorted order. Variables named `PATH` or ending in `_PATH`,
+/// and any marked with [EnvSnapshot::path_like], are compared element-wise so the message names
+/// the added and removed directories instead of repeating the whole value:
+///
+/// ```rust
+/// use cache_diff::{CacheDiff, EnvSnapshot, RedactionPolicy};
+///
+/// let old = EnvSnapshot::new([
+///     ("PATH", "/usr/local/bin:/usr/bin"),
+///     ("API_TOKEN", "hunter2"),
+///     ("RUBY_YJIT_ENABLE", "0"),
+/// ]);
+/// let now = EnvSnapshot::new([
+///     ("PATH", "/app/bin:/usr/bin"),
+///     ("API_TOKEN", "secret"),
+///     ("WEB_CONCURRENCY", "5"),
+/// ])
+/// .redaction(RedactionPolicy::new().pattern("*_TOKEN"));
+///
+/// assert_eq!(
+///     now.diff(&old),
+///     vec![
+///         "API_TOKEN (`[REDACTED]` to `[REDACTED]`)",
+///         "PATH added `/app/bin`, removed `/usr/local/bin`",
+///         "RUBY_YJIT_ENABLE (`0` to `(not set)`)",
+///         "WEB_CONCURRENCY (`(not set)` to `5`)",
+///     ]
+/// );
+/// ```
+///
+/// Without a [EnvSnapshot::redaction], the [RedactionPolicy] installed for the process is used.
+///
+/// With the `serde` feature, it serializes as a table of variables, for example to store it in a
+/// layer's metadata. Path-like names and the redaction policy are not serialized.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(from = "BTreeMap<String, String>", into = "BTreeMap<String, String>")
+)]
+pub struct EnvSnapshot {
+    vars: BTreeMap<String, String>,
+    path_like: Vec<String>,
+    redaction: Option<RedactionPolicy>,
+}
+
+impl EnvSnapshot {
+    pub fn new<K: Into<String>, V: Into<String>>(vars: impl IntoIterator<Item = (K, V)>) -> Self {
+        Self {
+            vars: vars
+                .into_iter()
+                .map(|(key, value)| (key.into(), value.into()))
+                .collect(),
+            ..Self::default()
+        }
+    }
+
+    /// The environment of the current process, variables that are not valid unicode are skipped
+    pub fn capture() -> Self {
+        Self::new(
+            std::env::vars_os().filter_map(|(key, value)| {
+                Some((key.into_string().ok()?, value.into_string().ok()?))
+            }),
+        )
+    }
+
+    /// Compare the variable element-wise, separated the same as `PATH`
+    pub fn path_like(mut self, name: impl Into<String>) -> Self {
+        self.path_like.push(name.into());
+        self
+    }
+
+    /// Hide the values of variables matching the policy instead of the installed one
+    pub fn redaction(mut self, policy: RedactionPolicy) -> Self {
+        self.redaction = Some(policy);
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.vars.get(name).map(String::as_str)
+    }
+
+    /// Variable names and values in sorted order
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.vars
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+    }
+
+    fn is_path_like(&self, name: &str) -> bool {
+        name == "PATH" || name.ends_with("_PATH") || self.path_like.iter().any(|n| n == name)
+    }
+
+    fn difference(
+        &self,
+        policy: Option<&RedactionPolicy>,
+        name: &str,
+        old: Option<&str>,
+        now: Option<&str>,
+    ) -> Option<Difference> {
+        let placeholder = policy
+            .filter(|policy| policy.matches(name))
+            .map(|policy| policy.placeholder.clone());
+        let difference = match (placeholder, old, now) {
+            (_, old, now) if old == now => return None,
+            (Some(placeholder), _, _) => {
+                Difference::changed(name.to_string(), placeholder.clone(), placeholder)
+            }
+            (None, Some(old), Some(now)) if self.is_path_like(name) => {
+                let old_paths = split_paths(old);
+                let now_paths = split_paths(now);
+                let added = now_paths
+                    .iter()
+                    .filter(|path| !old_paths.contains(path))
+                    .map(|path| self.fmt_value(path))
+                    .collect::<Vec<_>>();
+                let removed = old_paths
+                    .iter()
+                    .filter(|path| !now_paths.contains(path))
+                    .map(|path| self.fmt_value(path))
+                    .collect::<Vec<_>>();
+                let changes = [("added", added), ("removed", removed)]
+                    .into_iter()
+                    .filter(|(_, paths)| !paths.is_empty())
+                    .map(|(change, paths)| format!("{change} {}", paths.join(", ")))
+                    .collect::<Vec<_>>();
+                if changes.is_empty() {
+                    Difference::message(format!("{name} order changed"))
+                } else {
+                    Difference::message(format!("{name} {}", changes.join(", ")))
+                }
+            }
+            (None, old, now) => Difference::changed(
+                name.to_string(),
+                old.unwrap_or(MISSING),
+                now.unwrap_or(MISSING),
+            ),
+        };
+        Some(difference.with_identifier(name.to_string()))
+    }
+}
+
+fn split_paths(value: &str) -> Vec<String> {
+    std::env::split_paths(value)
+        .map(|path| path.display().to_string())
+        .collect()
+}
+
+impl CacheDiff for EnvSnapshot {
+    fn diff(&self, old: &Self) -> Vec<String> {
+        crate::render_differences(self, &self.diff_structured(old))
+    }
+
+    fn diff_structured(&self, old: &Self) -> Vec<Difference> {
+        let mut names = old.vars.keys().chain(self.vars.keys()).collect::<Vec<_>>();
+        names.sort();
+        names.dedup();
+        let installed = self
+            .redaction
+            .is_none()
+            .then(crate::redaction::installed)
+            .flatten();
+        let policy = self.redaction.as_ref().or(installed.as_ref());
+        names
+            .into_iter()
+            .filter_map(|name| self.difference(policy, name, old.get(name), self.get(name)))
+            .collect()
+    }
+}
+
+impl From<BTreeMap<String, String>> for EnvSnapshot {
+    fn from(vars: BTreeMap<String, String>) -> Self {
+        Self {
+            vars,
+            ..Self::default()
+        }
+    }
+}
+
+impl From<EnvSnapshot> for BTreeMap<String, String> {
+    fn from(snapshot: EnvSnapshot) -> Self {
+        snapshot.vars
+    }
+}
+
+impl<K: Into<String>, V: Into<String>> FromIterator<(K, V)> for EnvSnapshot {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(vars: I) -> Self {
+        Self::new(vars)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_path_like() {
+        let old = EnvSnapshot::new([("GEM_PATH", "/a:/b"), ("CLASSPATH", "/a:/b")]);
+        let now = EnvSnapshot::new([("GEM_PATH", "/b:/a"), ("CLASSPATH", "/a:/c")])
+            .path_like("CLASSPATH");
+        // Paths are styled with the default `DiffStyle`, which colors them with `bullet_stream`
+        assert_eq!(
+            now.diff(&old)
+                .iter()
+                .map(|line| crate::strip_ansi(line))
+                .collect::<Vec<_>>(),
+            vec![
+                "CLASSPATH added `/c`, removed `/b`",
+                "GEM_PATH order changed"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unchanged() {
+        let old = EnvSnapshot::new([("PATH", "/usr/bin")]);
+        let now =
+            EnvSnapshot::new([("PATH", "/usr/bin")]).redaction(RedactionPolicy::new().pattern("*"));
+        assert!(now.diff(&old).is_empty());
+    }
+
+    #[test]
+    fn test_paths_are_identified() {
+        let old = EnvSnapshot::new([("LANG", "C")]);
+        let now = EnvSnapshot::new([("LANG", "C.UTF-8")]);
+        assert_eq!(now.diff_structured(&old)[0].path(), vec!["LANG"]);
+    }
+}
@@ -0,0 +1,926 @@
+//! Static description of the fields a derived [CacheDiff](crate::CacheDiff) compares
+
+use crate::{CacheDiff, Difference, IgnoreSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt::{Debug, Display};
+use std::hash::{BuildHasher, Hash};
+
+/// Describes one field compared by a derived [CacheDiff](crate::CacheDiff) implementation
+///
+/// Every derived struct lists its compared fields, in order, in [CacheDiff::FIELDS](crate::CacheDiff::FIELDS).
+/// Ignored fields are not included. With the `serde` feature it can be serialized, for example
+/// to publish a table of "what invalidates this cache" from source:
+///
+/// ```rust
+/// use cache_diff::{CacheDiff, FieldInfo};
+///
+/// #[derive(CacheDiff)]
+/// struct Metadata {
+///     #[cache_diff(rename = "Ruby version", category = "Runtime")]
+///     ruby_version: String,
+///     #[cache_diff(ignore)]
+///     changed_by: String,
+/// }
+///
+/// assert_eq!(
+///     Metadata::FIELDS,
+///     &[FieldInfo {
+///         identifier: "ruby_version",
+///         name: "Ruby version",
+///         category: Some("Runtime"),
+///         code: "ruby_version",
+///     }]
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct FieldInfo {
+    /// The Rust identifier of the field i.e. `ruby_version`
+    pub identifier: &'static str,
+    /// The name shown to users i.e. `Ruby version`
+    pub name: &'static str,
+    /// The heading from `#[cache_diff(category = "...")]`
+    pub category: Option<&'static str>,
+    /// The stable code from `#[cache_diff(code = "...")]`, defaults to the identifier
+    pub code: &'static str,
+}
+
+/// Describes how a derived [CacheDiff](crate::CacheDiff) treats one field of the struct
+///
+/// Returned for every field, including ignored ones, by [CacheDiff::explain](crate::CacheDiff::explain)
+/// to debug why a field did or didn't invalidate the cache. It displays on one line:
+///
+/// ```rust
+/// use cache_diff::CacheDiff;
+///
+/// #[derive(CacheDiff)]
+/// struct Metadata {
+///     #[cache_diff(rename = "Ruby version")]
+///     version: String,
+///     #[cache_diff(ignore)]
+///     changed_by: String,
+/// }
+/// let now = Metadata { version: "3.4.0".to_string(), changed_by: "schneems".to_string() };
+///
+/// assert_eq!(
+///     now.explain().iter().map(ToString::to_string).collect::<Vec<_>>(),
+///     vec![
+///         "version: shown as `Ruby version`, compared with `!=`, displayed with `Display`",
+///         "changed_by: ignored",
+///     ]
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct FieldExplanation {
+    /// The Rust identifier of the field i.e. `ruby_version`
+    pub identifier: &'static str,
+    /// Whether and how the field is compared
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub status: FieldStatus,
+}
+
+/// Whether and how a field is compared, see [FieldExplanation]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(tag = "status", rename_all = "snake_case"))]
+pub enum FieldStatus {
+    /// Compared by the derived implementation
+    Compared {
+        /// The name shown to users i.e. `Ruby version`
+        name: &'static str,
+        /// How values are compared i.e. `` `!=` `` or `` `major_minor::compare` ``
+        compare: &'static str,
+        /// How values are displayed i.e. `` `Display` `` or `` `Path::display` ``
+        display: &'static str,
+    },
+    /// Skipped with `#[cache_diff(ignore)]`
+    Ignored,
+    /// Skipped with `#[cache_diff(ignore = "custom")]` and compared by the container's `custom = <function>`
+    Custom,
+}
+
+impl Display for FieldExplanation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let identifier = self.identifier;
+        match self.status {
+            FieldStatus::Compared {
+                name,
+                compare,
+                display,
+            } => write!(
+                f,
+                "{identifier}: shown as `{name}`, compared with {compare}, displayed with {display}"
+            ),
+            FieldStatus::Ignored => write!(f, "{identifier}: ignored"),
+            FieldStatus::Custom => write!(
+                f,
+                "{identifier}: ignored, compared by the container's `custom` function"
+            ),
+        }
+    }
+}
+
+/// How a derived implementation compares and displays one field, paired with its [FieldInfo]
+///
+/// Generated code only emits this small table, the comparison loop lives in [diff_fields] so it is
+/// compiled once instead of once per field.
+#[doc(hidden)]
+pub struct FieldDiff<T: ?Sized> {
+    /// Returns `true` when the field's values are not equal
+    pub differs: fn(&T, &T) -> bool,
+    /// Passes the field's display value to the callback, not called when the installed
+    /// [RedactionPolicy](crate::RedactionPolicy) hides it
+    ///
+    /// A callback keeps the generated closure free of generics, the value is only formatted
+    /// once by the shared loop.
+    pub display: fn(&T, &mut DisplayCallback),
+    /// Renders a change of the values as one message from `#[cache_diff(lines)]`, i.e. `cache_diff::text::unified`
+    pub lines: Option<fn(&str, &str) -> String>,
+    /// Renders the old and new values together from `display_pair = <function>`, takes priority over `display`
+    pub display_pair: Option<fn(&T, &T) -> PairDisplay>,
+    /// Words placed before the old and new values from `labels = "was/now"`
+    pub labels: Option<(&'static str, &'static str)>,
+    /// Returns the differences of a `nested` field's own [CacheDiff](crate::CacheDiff) implementation, replaces `display`
+    pub nested: Option<fn(&T, &T) -> Vec<Difference>>,
+    /// Returns the messages of a field's `custom = <function>`, replaces `display`
+    pub custom: Option<fn(&T, &T) -> Vec<String>>,
+    /// The field's template from `fmt = "..."`, replaces the struct's template
+    pub template: Option<&'static str>,
+    /// Returns `true` when the field should not be compared, from `skip_if = <function>`
+    pub skip: Option<fn(&T, &T) -> bool>,
+    /// Combines the field with others of the same `group = "..."` into one difference
+    pub group: Option<&'static str>,
+}
+
+/// The output of a `display_pair = <function>`
+#[doc(hidden)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PairDisplay {
+    /// Old and new values shown with the template like any other field
+    Values(String, String),
+    /// A full phrase shown as is
+    Phrase(String),
+}
+
+/// Values that can be returned from a `display_pair = <function>`
+#[doc(hidden)]
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` cannot be returned from `#[cache_diff(display_pair = <function>)]`",
+    label = "returns `{Self}`",
+    note = "return the rendered `(old, now)` values as a tuple of `Display` types, or a full phrase as a `String`"
+)]
+pub trait IntoPairDisplay {
+    fn into_pair_display(self) -> PairDisplay;
+}
+
+impl<A: Display, B: Display> IntoPairDisplay for (A, B) {
+    fn into_pair_display(self) -> PairDisplay {
+        PairDisplay::Values(self.0.to_string(), self.1.to_string())
+    }
+}
+
+impl IntoPairDisplay for String {
+    fn into_pair_display(self) -> PairDisplay {
+        PairDisplay::Phrase(self)
+    }
+}
+
+/// Receives a field's display value from generated code
+#[doc(hidden)]
+pub type DisplayCallback<'a> = dyn FnMut(&dyn Display) + 'a;
+
+/// Push a [Difference] for every field in the table whose values differ, skipping ignored fields
+///
+/// Nothing is allocated when no fields differ. On the first difference, capacity for every
+/// remaining field is reserved so the list is never re-allocated, and the installed
+/// [RedactionPolicy](crate::RedactionPolicy) is read once for the rest of the table.
+#[doc(hidden)]
+pub fn diff_fields<T: ?Sized>(
+    now: &T,
+    old: &T,
+    infos: &[FieldInfo],
+    fields: &[FieldDiff<T>],
+    ignore: &IgnoreSet,
+    differences: &mut Vec<Difference>,
+) {
+    let mut policy = None;
+    for (index, (info, field)) in infos.iter().zip(fields).enumerate() {
+        if let Some(group) = field.group {
+            // The whole group is compared at its first field
+            if !fields[..index]
+                .iter()
+                .any(|field| field.group == Some(group))
+            {
+                differences.extend(group_difference(
+                    now,
+                    old,
+                    group,
+                    infos,
+                    fields,
+                    ignore,
+                    &mut policy,
+                ));
+            }
+            continue;
+        }
+        if ignore.contains(info.identifier) || field.skip.is_some_and(|skip| skip(now, old)) {
+            continue;
+        }
+        if let Some(nested) = field.nested {
+            differences.extend(nested(now, old).into_iter().map(|difference| {
+                let difference = difference.with_parent(info.identifier);
+                match (info.category, difference.category()) {
+                    (Some(category), None) => difference.with_category(category),
+                    _ => difference,
+                }
+            }));
+        } else if let Some(custom) = field.custom {
+            let messages = custom(now, old);
+            if messages.is_empty() {
+                continue;
+            }
+            // A custom message can contain the field's values, a redacted field is reported once without them
+            let messages = match policy.get_or_insert_with(crate::redaction::installed) {
+                Some(policy) if policy.redacts(info.identifier, info.name) => {
+                    vec![Difference::changed(
+                        info.name,
+                        &policy.placeholder,
+                        &policy.placeholder,
+                    )]
+                }
+                _ => messages.into_iter().map(Difference::message).collect(),
+            };
+            differences.extend(messages.into_iter().map(|difference| {
+                let difference = difference
+                    .with_identifier(info.identifier)
+                    .with_code(info.code);
+                match info.category {
+                    Some(category) => difference.with_category(category),
+                    None => difference,
+                }
+            }));
+        } else if (field.differs)(now, old) {
+            if differences.len() == differences.capacity() {
+                differences.reserve(fields.len() - index);
+            }
+            let pair = match policy.get_or_insert_with(crate::redaction::installed) {
+                Some(policy) if policy.redacts(info.identifier, info.name) => {
+                    PairDisplay::Values(policy.placeholder.clone(), policy.placeholder.clone())
+                }
+                _ => match field.display_pair {
+                    Some(display_pair) => display_pair(old, now),
+                    None => PairDisplay::Values(display(field, old), display(field, now)),
+                },
+            };
+            let difference = match (pair, field.lines) {
+                (PairDisplay::Phrase(phrase), _) => Difference::message(phrase),
+                (PairDisplay::Values(old_value, now_value), Some(lines)) => Difference::message(
+                    format!("{} changed:\n{}", info.name, lines(&old_value, &now_value)),
+                ),
+                (PairDisplay::Values(old_value, now_value), None) => {
+                    Difference::changed(info.name, old_value, now_value)
+                }
+            }
+            .with_identifier(info.identifier)
+            .with_code(info.code);
+            let difference = match field.labels {
+                Some((old, now)) => difference.with_labels(old, now),
+                None => difference,
+            };
+            let difference = match field.template {
+                Some(template) => difference.with_template(template),
+                None => difference,
+            };
+            differences.push(match info.category {
+                Some(category) => difference.with_category(category),
+                None => difference,
+            });
+        }
+    }
+}
+
+/// One difference for every field of a `group = "..."`, when any of them differs
+///
+/// The old and new values are the fields' display values joined with `-` in declaration order
+/// i.e. `ubuntu-22.04`. Ignored fields are left out, skipped fields are shown but not compared.
+/// The group name is the identifier, the code lists the codes of the fields that changed
+/// i.e. `os_version` or `os_distribution,os_version`.
+fn group_difference<T: ?Sized>(
+    now: &T,
+    old: &T,
+    group: &'static str,
+    infos: &[FieldInfo],
+    fields: &[FieldDiff<T>],
+    ignore: &IgnoreSet,
+    policy: &mut Option<Option<crate::RedactionPolicy>>,
+) -> Option<Difference> {
+    let members = || {
+        infos
+            .iter()
+            .zip(fields)
+            .filter(|(info, field)| field.group == Some(group) && !ignore.contains(info.identifier))
+    };
+    let codes = members()
+        .filter(|(_, field)| {
+            !field.skip.is_some_and(|skip| skip(now, old)) && (field.differs)(now, old)
+        })
+        .map(|(info, _)| info.code)
+        .collect::<Vec<_>>();
+    if codes.is_empty() {
+        return None;
+    }
+    let policy = policy
+        .get_or_insert_with(crate::redaction::installed)
+        .as_ref();
+    let value = |cache: &T| {
+        members()
+            .map(|(info, field)| match policy {
+                Some(policy) if policy.redacts(info.identifier, info.name) => {
+                    policy.placeholder.clone()
+                }
+                _ => display(field, cache),
+            })
+            .collect::<Vec<_>>()
+            .join("-")
+    };
+    let difference = Difference::changed(group, value(old), value(now))
+        .with_identifier(group)
+        .with_code(codes.join(","));
+    Some(match members().find_map(|(info, _)| info.category) {
+        Some(category) => difference.with_category(category),
+        None => difference,
+    })
+}
+
+/// Fields compared by the derive must implement [PartialEq]
+#[doc(hidden)]
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` doesn't implement `PartialEq`, which `#[derive(CacheDiff)]` needs to compare this field",
+    label = "compared by CacheDiff",
+    note = "implement `PartialEq` for `{Self}`, or skip the field with `#[cache_diff(ignore)]`"
+)]
+pub trait FieldPartialEq: PartialEq {}
+impl<T: PartialEq + ?Sized> FieldPartialEq for T {}
+
+/// Fields displayed by the derive must implement [Display] unless they use `display = <function>`
+#[doc(hidden)]
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` doesn't implement `std::fmt::Display`, which `#[derive(CacheDiff)]` needs to show this field's value",
+    label = "displayed by CacheDiff",
+    note = "add `#[cache_diff(display = <function>)]` to the field, or skip it with `#[cache_diff(ignore)]`"
+)]
+pub trait FieldDisplay: Display {}
+impl<T: Display + ?Sized> FieldDisplay for T {}
+
+/// Used by generated code to compare a field with errors naming the requirement
+#[doc(hidden)]
+pub fn field_ne<T: FieldPartialEq + ?Sized>(now: &T, old: &T) -> bool {
+    now != old
+}
+
+/// Used by generated code to display a field with errors naming the requirement
+#[doc(hidden)]
+pub fn field_display<T: FieldDisplay>(value: &T) -> &dyn Display {
+    value
+}
+
+/// Fields marked `debug` are displayed with their [Debug] output
+#[doc(hidden)]
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` doesn't implement `std::fmt::Debug`, which `#[cache_diff(debug)]` needs to show this field's value",
+    label = "displayed with Debug by CacheDiff",
+    note = "derive `Debug` for `{Self}`, or use `#[cache_diff(display = <function>)]` instead"
+)]
+pub trait FieldDebug: Debug {}
+impl<T: Debug + ?Sized> FieldDebug for T {}
+
+/// Displays a value with its [Debug] output i.e. `{:?}`
+#[doc(hidden)]
+pub struct DebugDisplay<'a>(&'a dyn Debug);
+
+impl Display for DebugDisplay<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.0)
+    }
+}
+
+/// Used by generated code to display a `debug` field with errors naming the requirement
+#[doc(hidden)]
+pub fn field_debug<T: FieldDebug>(value: &T) -> DebugDisplay<'_> {
+    DebugDisplay(value)
+}
+
+/// Fields compared with `empty_is_none` must be strings or optional strings
+#[doc(hidden)]
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` cannot be compared with `#[cache_diff(empty_is_none)]`",
+    label = "not a `String` or `Option<String>`",
+    note = "`empty_is_none` treats `\"\"` and `None` as equal, remove it from fields that are not strings"
+)]
+pub trait EmptyIsNone {
+    fn non_empty(&self) -> Option<&str>;
+}
+
+impl EmptyIsNone for str {
+    fn non_empty(&self) -> Option<&str> {
+        Some(self).filter(|value| !value.is_empty())
+    }
+}
+
+impl EmptyIsNone for String {
+    fn non_empty(&self) -> Option<&str> {
+        self.as_str().non_empty()
+    }
+}
+
+impl<T: EmptyIsNone + ?Sized> EmptyIsNone for &T {
+    fn non_empty(&self) -> Option<&str> {
+        (**self).non_empty()
+    }
+}
+
+impl<T: EmptyIsNone> EmptyIsNone for Option<T> {
+    fn non_empty(&self) -> Option<&str> {
+        self.as_ref().and_then(EmptyIsNone::non_empty)
+    }
+}
+
+/// Used by generated code to compare `""` and `None` as equal
+#[doc(hidden)]
+pub fn empty_is_none<T: EmptyIsNone + ?Sized>(value: &T) -> Option<&str> {
+    value.non_empty()
+}
+
+/// Collections that can be filtered with `filter = <function>`
+///
+/// Elements are kept when the function returns `true`, it receives `&T` for lists and sets and
+/// `&K, &V` for maps.
+#[doc(hidden)]
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` cannot be filtered with `#[cache_diff(filter = <function>)]`",
+    label = "filtered by CacheDiff",
+    note = "use a `Vec`, set or map field with a function that returns `bool`, it receives `&T` for lists and sets, and `&K, &V` for maps"
+)]
+pub trait FilterField<F> {
+    fn filter_field(&self, keep: F) -> Self;
+}
+
+impl<T: Clone, F: Fn(&T) -> bool> FilterField<F> for Vec<T> {
+    fn filter_field(&self, keep: F) -> Self {
+        self.iter().filter(|item| keep(item)).cloned().collect()
+    }
+}
+
+impl<T: Ord + Clone, F: Fn(&T) -> bool> FilterField<F> for BTreeSet<T> {
+    fn filter_field(&self, keep: F) -> Self {
+        self.iter().filter(|item| keep(item)).cloned().collect()
+    }
+}
+
+impl<T: Eq + Hash + Clone, S: BuildHasher + Clone, F: Fn(&T) -> bool> FilterField<F>
+    for HashSet<T, S>
+{
+    fn filter_field(&self, keep: F) -> Self {
+        let mut filtered = HashSet::with_hasher(self.hasher().clone());
+        filtered.extend(self.iter().filter(|item| keep(item)).cloned());
+        filtered
+    }
+}
+
+impl<K: Ord + Clone, V: Clone, F: Fn(&K, &V) -> bool> FilterField<F> for BTreeMap<K, V> {
+    fn filter_field(&self, keep: F) -> Self {
+        self.iter()
+            .filter(|(key, value)| keep(key, value))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect()
+    }
+}
+
+impl<K: Eq + Hash + Clone, V: Clone, S: BuildHasher + Clone, F: Fn(&K, &V) -> bool> FilterField<F>
+    for HashMap<K, V, S>
+{
+    fn filter_field(&self, keep: F) -> Self {
+        let mut filtered = HashMap::with_hasher(self.hasher().clone());
+        filtered.extend(
+            self.iter()
+                .filter(|(key, value)| keep(key, value))
+                .map(|(key, value)| (key.clone(), value.clone())),
+        );
+        filtered
+    }
+}
+
+/// Used by generated code to filter a collection before it is compared and displayed
+#[doc(hidden)]
+pub fn filter_field<T: FilterField<F>, F>(value: &T, keep: F) -> T {
+    value.filter_field(keep)
+}
+
+/// A field's `custom = <function>` returns its messages
+#[doc(hidden)]
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` cannot be returned from a field's `#[cache_diff(custom = <function>)]`",
+    label = "not `Option<String>` or `Vec<String>`",
+    note = "return `None` or an empty `Vec` when the field's values are the same"
+)]
+pub trait FieldCustom {
+    fn into_messages(self) -> Vec<String>;
+}
+
+impl FieldCustom for Option<String> {
+    fn into_messages(self) -> Vec<String> {
+        self.into_iter().collect()
+    }
+}
+
+impl FieldCustom for Vec<String> {
+    fn into_messages(self) -> Vec<String> {
+        self
+    }
+}
+
+/// Used by generated code to collect the messages of a field's `custom = <function>`
+#[doc(hidden)]
+pub fn field_custom<M: FieldCustom>(messages: M) -> Vec<String> {
+    messages.into_messages()
+}
+
+/// Marks a `skip_if = <function>` that takes the field's old and new values
+#[doc(hidden)]
+pub struct SkipField;
+
+/// Marks a `skip_if = <function>` that takes the old and new struct
+#[doc(hidden)]
+pub struct SkipStruct;
+
+/// Functions accepted by `#[cache_diff(skip_if = <function>)]`, the marker picks the signature
+///
+/// Generated code passes both the structs and the field's values, the function receives the ones
+/// its signature asks for.
+#[doc(hidden)]
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` cannot be used as `#[cache_diff(skip_if = <function>)]`",
+    label = "wrong signature",
+    note = "use a function `fn(old: &{T}, now: &{T}) -> bool` of the field, or `fn(old: &{S}, now: &{S}) -> bool` of the struct"
+)]
+pub trait SkipIf<S: ?Sized, T: ?Sized, Marker> {
+    fn should_skip(&self, old: &S, now: &S, old_value: &T, now_value: &T) -> bool;
+}
+
+impl<S: ?Sized, T: ?Sized, F: Fn(&T, &T) -> bool> SkipIf<S, T, SkipField> for F {
+    fn should_skip(&self, _: &S, _: &S, old_value: &T, now_value: &T) -> bool {
+        self(old_value, now_value)
+    }
+}
+
+impl<S: ?Sized, T: ?Sized, F: Fn(&S, &S) -> bool> SkipIf<S, T, SkipStruct> for F {
+    fn should_skip(&self, old: &S, now: &S, _: &T, _: &T) -> bool {
+        self(old, now)
+    }
+}
+
+/// Fields marked `nested` must implement [CacheDiff] themselves
+#[doc(hidden)]
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` doesn't implement `CacheDiff`, which `#[cache_diff(nested)]` needs to compare this field",
+    label = "nested in CacheDiff",
+    note = "derive or implement `CacheDiff` for `{Self}`, or remove `nested` from the field"
+)]
+pub trait NestedCacheDiff: CacheDiff {}
+impl<T: CacheDiff + ?Sized> NestedCacheDiff for T {}
+
+/// Used by generated code to compare a `nested` field with errors naming the requirement
+#[doc(hidden)]
+pub fn nested_differs<T: NestedCacheDiff + ?Sized>(now: &T, old: &T) -> bool {
+    now.differs(old)
+}
+
+/// Used by generated code to include the differences of a `nested` field
+#[doc(hidden)]
+pub fn nested_diff<T: NestedCacheDiff + ?Sized>(now: &T, old: &T) -> Vec<Difference> {
+    now.diff_structured(old)
+}
+
+/// Used by generated code to display a `nested` field, its initial message on one line
+#[doc(hidden)]
+pub fn nested_display<T: NestedCacheDiff + ?Sized>(value: &T) -> String {
+    value.initial_message().join(", ")
+}
+
+/// Used by generated code, returns `true` when a `max = <limit>` field is over its limit
+#[doc(hidden)]
+pub fn exceeds_max<T: PartialOrd + ?Sized>(value: &T, limit: &T) -> bool {
+    value > limit
+}
+
+/// Used by generated code to describe a `max = <limit>` field over its limit
+#[doc(hidden)]
+pub fn max_message<T: Display + ?Sized>(name: &str, value: &T, limit: &T) -> String {
+    format!("{name} ({value}) exceeded limit {limit}")
+}
+
+fn display<T: ?Sized>(field: &FieldDiff<T>, cache: &T) -> String {
+    let mut out = String::new();
+    (field.display)(cache, &mut |value| out = value.to_string());
+    out
+}
+
+/// Each field's name and styled current value, used by [CacheDiff::initial_message]
+pub(crate) fn initial_values<T: CacheDiff + ?Sized>(
+    cache: &T,
+    infos: &[FieldInfo],
+    fields: &[FieldDiff<T>],
+) -> Vec<String> {
+    let policy = crate::redaction::installed();
+    infos
+        .iter()
+        .zip(fields)
+        .map(|(info, field)| {
+            let value = match &policy {
+                Some(policy) if policy.redacts(info.identifier, info.name) => {
+                    policy.placeholder.clone()
+                }
+                _ => display(field, cache),
+            };
+            format!("{} {}", info.name, cache.fmt_value(&value))
+        })
+        .collect()
+}
+
+/// Render structured differences into the strings returned by a derived [CacheDiff::diff]
+#[doc(hidden)]
+pub fn render_differences<T: CacheDiff + ?Sized>(
+    cache: &T,
+    differences: &[Difference],
+) -> Vec<String> {
+    if differences.is_empty() {
+        return Vec::new();
+    }
+    let template = cache.template();
+    differences
+        .iter()
+        .map(|difference| {
+            difference.render_styled(
+                &template,
+                |value| cache.fmt_value(&value),
+                |name| cache.fmt_name(name),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct Metadata {
+        version: String,
+        distro: String,
+    }
+
+    const INFOS: &[FieldInfo] = &[
+        FieldInfo {
+            identifier: "version",
+            name: "version",
+            category: None,
+            code: "version",
+        },
+        FieldInfo {
+            identifier: "distro",
+            name: "distro",
+            category: Some("Operating system"),
+            code: "os",
+        },
+    ];
+
+    #[test]
+    fn test_diff_fields() {
+        let fields: [FieldDiff<Metadata>; 2] = [
+            FieldDiff {
+                differs: |now, old| now.version != old.version,
+                display: |cache, f| f(&cache.version),
+                lines: None,
+                display_pair: None,
+                labels: None,
+                nested: None,
+                custom: None,
+                template: None,
+                skip: None,
+                group: None,
+            },
+            FieldDiff {
+                differs: |now, old| now.distro != old.distro,
+                display: |cache, f| f(&cache.distro),
+                lines: None,
+                display_pair: None,
+                labels: Some(("was", "now")),
+                nested: None,
+                custom: None,
+                template: None,
+                skip: None,
+                group: None,
+            },
+        ];
+        let now = Metadata {
+            version: "3.4.0".to_string(),
+            distro: "Ubuntu".to_string(),
+        };
+        let old = Metadata {
+            version: "3.4.0".to_string(),
+            distro: "Alpine".to_string(),
+        };
+
+        let mut differences = Vec::new();
+        diff_fields(
+            &now,
+            &old,
+            INFOS,
+            &fields,
+            &IgnoreSet::new(),
+            &mut differences,
+        );
+        assert_eq!(
+            differences,
+            vec![Difference::changed("distro", "Alpine", "Ubuntu")
+                .with_identifier("distro")
+                .with_code("os")
+                .with_labels("was", "now")
+                .with_category("Operating system")]
+        );
+    }
+
+    #[test]
+    fn test_filter_field() {
+        let dependencies = vec!["rails", "dev:rspec", "puma"];
+        assert_eq!(
+            filter_field(&dependencies, |name: &&str| !name.starts_with("dev:")),
+            vec!["rails", "puma"]
+        );
+
+        let env = BTreeMap::from([("PATH", "/bin"), ("BUILD_ID", "42")]);
+        assert_eq!(
+            filter_field(&env, |key: &&str, _: &&str| *key != "BUILD_ID"),
+            BTreeMap::from([("PATH", "/bin")])
+        );
+    }
+
+    #[test]
+    fn test_empty_is_none() {
+        assert_eq!(empty_is_none(""), None);
+        assert_eq!(empty_is_none(&String::new()), None);
+        assert_eq!(empty_is_none(&Some(String::new())), None);
+        assert_eq!(empty_is_none(&None::<String>), None);
+        assert_eq!(empty_is_none(&Some("3.4.0")), Some("3.4.0"));
+        assert_eq!(empty_is_none(&"3.4.0".to_string()), Some("3.4.0"));
+    }
+
+    #[test]
+    fn test_diff_fields_display_pair() {
+        let fields: [FieldDiff<Metadata>; 2] = [
+            FieldDiff {
+                differs: |now, old| now.version != old.version,
+                display: |cache, f| f(&cache.version),
+                lines: None,
+                display_pair: Some(|old, now| {
+                    (&old.version[..1], &now.version).into_pair_display()
+                }),
+                labels: None,
+                nested: None,
+                custom: None,
+                template: None,
+                skip: None,
+                group: None,
+            },
+            FieldDiff {
+                differs: |now, old| now.distro != old.distro,
+                display: |cache, f| f(&cache.distro),
+                lines: None,
+                display_pair: Some(|old, now| {
+                    format!("Moved from {} to {}", old.distro, now.distro).into_pair_display()
+                }),
+                labels: None,
+                nested: None,
+                custom: None,
+                template: None,
+                skip: None,
+                group: None,
+            },
+        ];
+        let now = Metadata {
+            version: "3.4.0".to_string(),
+            distro: "Ubuntu".to_string(),
+        };
+        let old = Metadata {
+            version: "3.3.0".to_string(),
+            distro: "Alpine".to_string(),
+        };
+
+        let mut differences = Vec::new();
+        diff_fields(
+            &now,
+            &old,
+            INFOS,
+            &fields,
+            &IgnoreSet::new(),
+            &mut differences,
+        );
+        assert_eq!(
+            differences,
+            vec![
+                Difference::changed("version", "3", "3.4.0")
+                    .with_identifier("version")
+                    .with_code("version"),
+                Difference::message("Moved from Alpine to Ubuntu")
+                    .with_identifier("distro")
+                    .with_code("os")
+                    .with_category("Operating system")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_fields_group() {
+        let fields: [FieldDiff<Metadata>; 2] = [
+            FieldDiff {
+                differs: |now, old| now.version != old.version,
+                display: |cache, f| f(&cache.version),
+                lines: None,
+                display_pair: None,
+                labels: None,
+                nested: None,
+                custom: None,
+                template: None,
+                skip: None,
+                group: Some("Platform"),
+            },
+            FieldDiff {
+                differs: |now, old| now.distro != old.distro,
+                display: |cache, f| f(&cache.distro),
+                lines: None,
+                display_pair: None,
+                labels: None,
+                nested: None,
+                custom: None,
+                template: None,
+                skip: None,
+                group: Some("Platform"),
+            },
+        ];
+        let now = Metadata {
+            version: "3.4.0".to_string(),
+            distro: "Ubuntu".to_string(),
+        };
+        let old = Metadata {
+            version: "3.4.0".to_string(),
+            distro: "Alpine".to_string(),
+        };
+
+        let mut differences = Vec::new();
+        diff_fields(
+            &now,
+            &old,
+            INFOS,
+            &fields,
+            &IgnoreSet::new(),
+            &mut differences,
+        );
+        assert_eq!(
+            differences,
+            vec![
+                Difference::changed("Platform", "3.4.0-Alpine", "3.4.0-Ubuntu")
+                    .with_identifier("Platform")
+                    .with_code("os")
+                    .with_category("Operating system")
+            ]
+        );
+        assert_eq!(differences[0].identifier(), Some("Platform"));
+        assert_eq!(differences[0].code(), Some("os"));
+
+        let changed = Metadata {
+            version: "3.3.0".to_string(),
+            distro: "Ubuntu".to_string(),
+        };
+        let mut differences = Vec::new();
+        diff_fields(
+            &changed,
+            &old,
+            INFOS,
+            &fields,
+            &IgnoreSet::new(),
+            &mut differences,
+        );
+        assert_eq!(differences[0].code(), Some("version,os"));
+
+        let mut ignore = IgnoreSet::new();
+        ignore.insert("version");
+        let mut differences = Vec::new();
+        diff_fields(&now, &old, INFOS, &fields, &ignore, &mut differences);
+        assert_eq!(differences[0].old(), Some("Alpine"));
+
+        ignore.insert("distro");
+        let mut differences = Vec::new();
+        diff_fields(&now, &old, INFOS, &fields, &ignore, &mut differences);
+        assert!(differences.is_empty());
+    }
+}
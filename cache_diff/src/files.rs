@@ -0,0 +1,284 @@
+//! Diff against metadata stored in TOML files
+
+use crate::CacheDiff;
+use serde::de::DeserializeOwned;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+
+/// Diff the current metadata against a previous value stored in a TOML file
+///
+/// ```rust,no_run
+/// use cache_diff::files::CacheDiffFileExt;
+/// use cache_diff::CacheDiff;
+/// use serde::Deserialize;
+///
+/// #[derive(CacheDiff, Deserialize)]
+/// struct Metadata {
+///     version: String,
+/// }
+///
+/// let now = Metadata { version: "3.4.0".to_string() };
+/// match now.diff_path("/layers/ruby.toml".as_ref()) {
+///     Ok(diff) if diff.is_empty() => println!("Using cache"),
+///     Ok(diff) => println!("Clearing cache: {}", diff.join(", ")),
+///     Err(error) => println!("Clearing cache: {error}"),
+/// }
+/// ```
+pub trait CacheDiffFileExt: CacheDiff + DeserializeOwned {
+    /// Read, parse, and diff against the metadata at `path`
+    ///
+    /// Keys written under a field's previous name from `#[cache_diff(alias = "<previous name>")]`
+    /// are read as the field, unless the current key is also present:
+    ///
+    /// ```rust
+    /// use cache_diff::files::CacheDiffFileExt;
+    /// use cache_diff::CacheDiff;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(CacheDiff, Deserialize)]
+    /// struct Metadata {
+    ///     #[cache_diff(alias = "ruby_version")]
+    ///     version: String,
+    /// }
+    ///
+    /// let path = std::env::temp_dir().join(format!("cache_diff_alias_{}.toml", std::process::id()));
+    /// std::fs::write(&path, r#"ruby_version = "3.3.0""#).unwrap();
+    ///
+    /// let now = Metadata { version: "3.4.0".to_string() };
+    /// assert_eq!(
+    ///     now.diff_path(&path).unwrap(),
+    ///     vec!["version (`3.3.0` to `3.4.0`)".to_string()]
+    /// );
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    fn diff_path(&self, path: &Path) -> Result<Vec<String>, DiffFileError> {
+        let mut value = read_value(path)?;
+        rename_aliases(&mut value, Self::FIELD_ALIASES);
+        let old = Self::deserialize(value).map_err(|error| DiffFileError::Schema {
+            path: path.to_path_buf(),
+            source: error,
+        })?;
+
+        Ok(self.diff(&old))
+    }
+}
+
+impl<T> CacheDiffFileExt for T where T: CacheDiff + DeserializeOwned {}
+
+/// Read and parse two TOML files and diff them key by key with [crate::toml::diff_values]
+///
+/// Useful when neither side has a struct to deserialize into, for example from build tooling
+/// comparing a layer's stored metadata against a freshly generated file.
+///
+/// ```rust,no_run
+/// match cache_diff::files::diff_toml("old/ruby.toml".as_ref(), "new/ruby.toml".as_ref()) {
+///     Ok(diff) if diff.is_empty() => println!("No changes"),
+///     Ok(diff) => println!("Changed: {}", diff.join(", ")),
+///     Err(error) => println!("Cannot diff: {error}"),
+/// }
+/// ```
+pub fn diff_toml(old_path: &Path, new_path: &Path) -> Result<Vec<String>, DiffFileError> {
+    let old = read_value(old_path)?;
+    let now = read_value(new_path)?;
+
+    Ok(crate::toml::diff_values(&old, &now))
+}
+
+/// Move values stored under a previous key to the current key when it is absent
+fn rename_aliases(value: &mut toml::Value, aliases: &[(&str, &str)]) {
+    let Some(table) = value.as_table_mut() else {
+        return;
+    };
+    for (alias, key) in aliases {
+        if !table.contains_key(*key) {
+            if let Some(old) = table.remove(*alias) {
+                table.insert(key.to_string(), old);
+            }
+        }
+    }
+}
+
+fn read_value(path: &Path) -> Result<toml::Value, DiffFileError> {
+    let contents = std::fs::read_to_string(path).map_err(|error| {
+        if error.kind() == std::io::ErrorKind::NotFound {
+            DiffFileError::Missing {
+                path: path.to_path_buf(),
+            }
+        } else {
+            DiffFileError::Read {
+                path: path.to_path_buf(),
+                source: error,
+            }
+        }
+    })?;
+    toml::from_str::<toml::Value>(&contents).map_err(|error| DiffFileError::Parse {
+        path: path.to_path_buf(),
+        source: error,
+    })
+}
+
+/// Reasons metadata could not be loaded from a file
+#[derive(Debug)]
+pub enum DiffFileError {
+    /// The file does not exist, usually because there is no previous cache
+    Missing { path: PathBuf },
+    /// The file exists but could not be read
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// The contents are not valid TOML
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+    /// The contents are valid TOML but do not match the metadata struct
+    Schema {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+}
+
+impl Display for DiffFileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DiffFileError::Missing { path } => {
+                write!(f, "No metadata found at {}", path.display())
+            }
+            DiffFileError::Read { path, source } => {
+                write!(f, "Cannot read metadata at {}: {source}", path.display())
+            }
+            DiffFileError::Parse { path, source } => {
+                write!(
+                    f,
+                    "Invalid TOML in metadata at {}: {source}",
+                    path.display()
+                )
+            }
+            DiffFileError::Schema { path, source } => write!(
+                f,
+                "Metadata at {} does not match the expected format: {source}",
+                path.display()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DiffFileError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DiffFileError::Missing { .. } => None,
+            DiffFileError::Read { source, .. } => Some(source),
+            DiffFileError::Parse { source, .. } | DiffFileError::Schema { source, .. } => {
+                Some(source)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::CacheDiff;
+    use serde::Deserialize;
+
+    #[derive(Deserialize)]
+    struct Metadata {
+        version: String,
+    }
+
+    impl CacheDiff for Metadata {
+        fn diff(&self, old: &Self) -> Vec<String> {
+            if self.version == old.version {
+                Vec::new()
+            } else {
+                vec![format!("version ({} to {})", old.version, self.version)]
+            }
+        }
+    }
+
+    fn write(name: &str, contents: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("cache_diff_files_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_diff_path() {
+        let now = Metadata {
+            version: "3.4.0".to_string(),
+        };
+        let path = write("valid.toml", r#"version = "3.3.0""#);
+        assert_eq!(
+            now.diff_path(&path).unwrap(),
+            vec!["version (3.3.0 to 3.4.0)"]
+        );
+    }
+
+    #[test]
+    fn test_rename_aliases() {
+        let mut value = toml::from_str::<toml::Value>(
+            "ruby_version = \"3.3.0\"\nbundler = \"2.5.0\"\nold_bundler = \"2.4.0\"",
+        )
+        .unwrap();
+        rename_aliases(
+            &mut value,
+            &[("ruby_version", "version"), ("old_bundler", "bundler")],
+        );
+        assert_eq!(
+            value,
+            toml::from_str::<toml::Value>(
+                "version = \"3.3.0\"\nbundler = \"2.5.0\"\nold_bundler = \"2.4.0\""
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_diff_path_errors() {
+        let now = Metadata {
+            version: "3.4.0".to_string(),
+        };
+        let missing = std::env::temp_dir().join("cache_diff_does_not_exist.toml");
+        assert!(matches!(
+            now.diff_path(&missing),
+            Err(DiffFileError::Missing { .. })
+        ));
+
+        let path = write("invalid.toml", "version = ");
+        assert!(matches!(
+            now.diff_path(&path),
+            Err(DiffFileError::Parse { .. })
+        ));
+
+        let path = write("schema.toml", "name = \"ruby\"");
+        assert!(matches!(
+            now.diff_path(&path),
+            Err(DiffFileError::Schema { .. })
+        ));
+    }
+
+    #[test]
+    fn test_diff_toml() {
+        let old = write("old.toml", "[ruby]\nversion = \"3.3.0\"");
+        let now = write("new.toml", "[ruby]\nversion = \"3.4.0\"");
+        // Values are styled with the default `DiffStyle`, which colors them with `bullet_stream`
+        assert_eq!(
+            diff_toml(&old, &now)
+                .unwrap()
+                .iter()
+                .map(|line| crate::strip_ansi(line))
+                .collect::<Vec<_>>(),
+            vec!["ruby.version (`3.3.0` to `3.4.0`)"]
+        );
+        assert!(diff_toml(&old, &old).unwrap().is_empty());
+
+        let missing = std::env::temp_dir().join("cache_diff_does_not_exist.toml");
+        assert!(matches!(
+            diff_toml(&old, &missing),
+            Err(DiffFileError::Missing { path }) if path == missing
+        ));
+    }
+}
@@ -0,0 +1,308 @@
+//! Record why a cache was invalidated across builds to debug chronic cache churn
+
+use crate::ttl::format_timestamp;
+use crate::Difference;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Number of entries kept by [CacheDiffLog::new]
+pub const DEFAULT_MAX_ENTRIES: usize = 20;
+
+/// One build's result, appended to a [CacheDiffLog]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct LogEntry {
+    /// Seconds since the unix epoch
+    pub timestamp: u64,
+    /// Identifies the build i.e. a build ID or commit SHA
+    pub build: String,
+    /// Empty when the cache was kept
+    #[serde(default)]
+    pub differences: Vec<Difference>,
+}
+
+impl LogEntry {
+    /// An entry for the current time
+    pub fn new(build: impl Into<String>, differences: Vec<Difference>) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs());
+        Self {
+            timestamp,
+            build: build.into(),
+            differences,
+        }
+    }
+}
+
+/// An append-only TOML file of each build's structured diff, stored inside a cache layer
+///
+/// Only the newest [DEFAULT_MAX_ENTRIES] entries are kept unless configured with
+/// [CacheDiffLog::max_entries]. A missing file is treated as an empty log.
+///
+/// ```rust,no_run
+/// use cache_diff::history::{CacheDiffLog, LogEntry};
+/// use cache_diff::CacheDiff;
+///
+/// #[derive(CacheDiff)]
+/// struct Metadata {
+///     version: String,
+/// }
+/// let old = Metadata { version: "3.3.0".to_string() };
+/// let now = Metadata { version: "3.4.0".to_string() };
+///
+/// let log = CacheDiffLog::new("/layers/ruby/cache_diff_history.toml");
+/// log.append(LogEntry::new("build-42", now.diff_structured(&old))).unwrap();
+/// println!("{}", log.render_history().unwrap());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheDiffLog {
+    path: PathBuf,
+    max_entries: usize,
+}
+
+#[derive(Default, serde::Serialize, serde::Deserialize)]
+struct LogFile {
+    #[serde(default)]
+    entries: Vec<LogEntry>,
+}
+
+impl CacheDiffLog {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            max_entries: DEFAULT_MAX_ENTRIES,
+        }
+    }
+
+    /// Keep at most this many entries, oldest entries are dropped first
+    pub fn max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries;
+        self
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Entries from oldest to newest
+    pub fn entries(&self) -> Result<Vec<LogEntry>, LogError> {
+        let contents = match std::fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(error) => {
+                return Err(LogError::Read {
+                    path: self.path.clone(),
+                    source: error,
+                })
+            }
+        };
+        toml::from_str::<LogFile>(&contents)
+            .map(|file| file.entries)
+            .map_err(|error| LogError::Parse {
+                path: self.path.clone(),
+                source: error,
+            })
+    }
+
+    /// Add an entry and write the log, dropping the oldest entries over the limit
+    pub fn append(&self, entry: LogEntry) -> Result<(), LogError> {
+        let mut entries = self.entries()?;
+        entries.push(entry);
+        let excess = entries.len().saturating_sub(self.max_entries);
+        entries.drain(..excess);
+
+        let contents =
+            toml::to_string(&LogFile { entries }).map_err(|error| LogError::Serialize {
+                path: self.path.clone(),
+                source: error,
+            })?;
+        std::fs::write(&self.path, contents).map_err(|error| LogError::Write {
+            path: self.path.clone(),
+            source: error,
+        })
+    }
+
+    /// Human readable invalidation history, newest build first
+    ///
+    /// ```text
+    /// Cache invalidated in 1 of 2 builds:
+    /// - build-42 (2024-06-01T12:00:00Z):
+    ///   - version (`3.3.0` to `3.4.0`)
+    /// - build-41 (2024-05-31T09:30:00Z): cache kept
+    /// ```
+    pub fn render_history(&self) -> Result<String, LogError> {
+        Ok(render(&self.entries()?))
+    }
+}
+
+fn render(entries: &[LogEntry]) -> String {
+    if entries.is_empty() {
+        return "No builds recorded".to_string();
+    }
+    let invalidated = entries
+        .iter()
+        .filter(|entry| !entry.differences.is_empty())
+        .count();
+    let mut lines = vec![format!(
+        "Cache invalidated in {invalidated} of {} builds:",
+        entries.len()
+    )];
+    for entry in entries.iter().rev() {
+        let time = format_timestamp(entry.timestamp);
+        if entry.differences.is_empty() {
+            lines.push(format!("- {} ({time}): cache kept", entry.build));
+        } else {
+            lines.push(format!("- {} ({time}):", entry.build));
+            lines.extend(
+                entry
+                    .differences
+                    .iter()
+                    .map(|difference| format!("  - {difference}")),
+            );
+        }
+    }
+    lines.join("\n")
+}
+
+/// Reasons the history could not be read or written
+#[derive(Debug)]
+pub enum LogError {
+    /// The file exists but could not be read
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// The contents are not a valid log
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+    /// The entries could not be converted to TOML
+    Serialize {
+        path: PathBuf,
+        source: toml::ser::Error,
+    },
+    /// The file could not be written
+    Write {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+}
+
+impl Display for LogError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LogError::Read { path, source } => {
+                write!(
+                    f,
+                    "Cannot read cache history at {}: {source}",
+                    path.display()
+                )
+            }
+            LogError::Parse { path, source } => {
+                write!(f, "Invalid cache history at {}: {source}", path.display())
+            }
+            LogError::Serialize { path, source } => write!(
+                f,
+                "Cannot serialize cache history for {}: {source}",
+                path.display()
+            ),
+            LogError::Write { path, source } => {
+                write!(
+                    f,
+                    "Cannot write cache history at {}: {source}",
+                    path.display()
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for LogError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LogError::Read { source, .. } | LogError::Write { source, .. } => Some(source),
+            LogError::Parse { source, .. } => Some(source),
+            LogError::Serialize { source, .. } => Some(source),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn log_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("cache_diff_history_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    fn entry(timestamp: u64, build: &str, differences: Vec<Difference>) -> LogEntry {
+        LogEntry {
+            timestamp,
+            build: build.to_string(),
+            differences,
+        }
+    }
+
+    #[test]
+    fn test_append_and_render() {
+        let log = CacheDiffLog::new(log_path("append.toml"));
+        assert!(log.entries().unwrap().is_empty());
+        assert_eq!(log.render_history().unwrap(), "No builds recorded");
+
+        log.append(entry(1_717_147_800, "build-41", vec![]))
+            .unwrap();
+        log.append(entry(
+            1_717_243_200,
+            "build-42",
+            vec![Difference::changed("version", "3.3.0", "3.4.0").with_code("version")],
+        ))
+        .unwrap();
+
+        assert_eq!(log.entries().unwrap().len(), 2);
+        assert_eq!(
+            crate::strip_ansi(&log.render_history().unwrap()),
+            [
+                "Cache invalidated in 1 of 2 builds:",
+                "- build-42 (2024-06-01T12:00:00Z):",
+                "  - version (`3.3.0` to `3.4.0`)",
+                "- build-41 (2024-05-31T09:30:00Z): cache kept",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_max_entries() {
+        let log = CacheDiffLog::new(log_path("max.toml")).max_entries(2);
+        for build in ["one", "two", "three"] {
+            log.append(entry(0, build, vec![Difference::message("changed")]))
+                .unwrap();
+        }
+
+        assert_eq!(
+            log.entries()
+                .unwrap()
+                .into_iter()
+                .map(|entry| entry.build)
+                .collect::<Vec<_>>(),
+            vec!["two", "three"]
+        );
+    }
+
+    #[test]
+    fn test_invalid_file() {
+        let path = log_path("invalid.toml");
+        std::fs::write(&path, "entries = 1").unwrap();
+
+        assert!(matches!(
+            CacheDiffLog::new(path).entries(),
+            Err(LogError::Parse { .. })
+        ));
+    }
+}
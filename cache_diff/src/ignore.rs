@@ -0,0 +1,137 @@
+//! Fields to skip at diff time without changing the struct's attributes
+
+use crate::Difference;
+use std::collections::BTreeSet;
+
+/// A set of fields to skip when diffing, supplied at runtime
+///
+/// Use with [CacheDiff::diff_filtered](crate::CacheDiff::diff_filtered) to temporarily stop a noisy
+/// field from invalidating the cache, for example from configuration, without changing
+/// `#[cache_diff(ignore)]` on the struct. Derived implementations match the Rust identifier of each
+/// field in [CacheDiff::FIELDS](crate::CacheDiff::FIELDS) and do not compare ignored fields at all.
+/// Manual implementations drop differences whose code or name is in the set. Differences from
+/// `custom = <function>` are never filtered.
+///
+/// ```rust
+/// use cache_diff::{CacheDiff, IgnoreSet};
+///
+/// #[derive(CacheDiff)]
+/// struct Metadata {
+///     version: String,
+///     changed_by: String,
+/// }
+/// let old = Metadata { version: "3.4.0".to_string(), changed_by: "CI".to_string() };
+/// let now = Metadata { version: "3.4.0".to_string(), changed_by: "Schneems".to_string() };
+///
+/// assert_eq!(now.diff(&old).len(), 1);
+/// assert!(now.diff_filtered(&old, &IgnoreSet::from(["changed_by"])).is_empty());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IgnoreSet {
+    fields: BTreeSet<String>,
+}
+
+impl IgnoreSet {
+    /// An empty set that ignores nothing, does not allocate
+    pub const fn new() -> Self {
+        Self {
+            fields: BTreeSet::new(),
+        }
+    }
+
+    /// Add a field by its Rust identifier i.e. `changed_by`
+    pub fn insert(&mut self, identifier: impl Into<String>) -> bool {
+        self.fields.insert(identifier.into())
+    }
+
+    /// Returns `true` when the field with the given identifier should be skipped
+    pub fn contains(&self, identifier: &str) -> bool {
+        self.fields.contains(identifier)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    /// Used to filter differences from manual implementations, which have no field table
+    pub(crate) fn matches(&self, difference: &Difference) -> bool {
+        difference.code().is_some_and(|code| self.contains(code))
+            || difference.name().is_some_and(|name| self.contains(name))
+    }
+}
+
+impl<S: Into<String>, const N: usize> From<[S; N]> for IgnoreSet {
+    fn from(fields: [S; N]) -> Self {
+        Self::from_iter(fields)
+    }
+}
+
+impl<S: Into<String>> FromIterator<S> for IgnoreSet {
+    fn from_iter<I: IntoIterator<Item = S>>(fields: I) -> Self {
+        Self {
+            fields: fields.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl<S: Into<String>> Extend<S> for IgnoreSet {
+    fn extend<I: IntoIterator<Item = S>>(&mut self, fields: I) {
+        self.fields.extend(fields.into_iter().map(Into::into));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::CacheDiff;
+
+    struct Metadata {
+        version: String,
+        changed_by: String,
+    }
+
+    impl CacheDiff for Metadata {
+        fn diff(&self, _old: &Self) -> Vec<String> {
+            unreachable!("diff_structured is implemented")
+        }
+
+        fn diff_structured(&self, old: &Self) -> Vec<Difference> {
+            let mut differences = Vec::new();
+            if self.version != old.version {
+                differences.push(Difference::changed("version", &old.version, &self.version));
+            }
+            if self.changed_by != old.changed_by {
+                differences.push(
+                    Difference::changed("Changed by", &old.changed_by, &self.changed_by)
+                        .with_code("changed_by"),
+                );
+            }
+            differences
+        }
+    }
+
+    #[test]
+    fn test_manual_implementation_filters_by_code_or_name() {
+        let old = Metadata {
+            version: "3.3.0".to_string(),
+            changed_by: "CI".to_string(),
+        };
+        let now = Metadata {
+            version: "3.4.0".to_string(),
+            changed_by: "Schneems".to_string(),
+        };
+
+        assert_eq!(
+            now.diff_structured_filtered(&old, &IgnoreSet::from(["changed_by"])),
+            vec![Difference::changed("version", "3.3.0", "3.4.0")]
+        );
+        assert_eq!(
+            now.diff_structured_filtered(&old, &IgnoreSet::from(["version", "Changed by"])),
+            vec![]
+        );
+        assert_eq!(
+            now.diff_structured_filtered(&old, &IgnoreSet::new()).len(),
+            2
+        );
+    }
+}
@@ -0,0 +1,36 @@
+//! Compare and display [`libherokubuildpack::inventory`](https://github.com/heroku/libcnb.rs/tree/main/libherokubuildpack) artifacts stored in metadata
+
+/// Compare and display an inventory `Artifact` field with `#[cache_diff(with = cache_diff::inventory::artifact)]`
+///
+/// Artifacts are the same when their version, OS, architecture, and checksum match, a change to only the
+/// download URL or the artifact's metadata keeps the cache. They display as their version, OS, and
+/// architecture i.e. ``artifact (`3.3.0 (linux-amd64)` to `3.4.0 (linux-amd64)`)``.
+///
+/// ```rust
+/// use cache_diff::CacheDiff;
+/// use libherokubuildpack::inventory::artifact::Artifact;
+/// use sha2::Sha256;
+///
+/// #[derive(CacheDiff)]
+/// struct Metadata {
+///     #[cache_diff(with = cache_diff::inventory::artifact)]
+///     artifact: Artifact<String, Sha256, Option<()>>,
+/// }
+/// ```
+pub mod artifact {
+    use ::libherokubuildpack::inventory::artifact::Artifact;
+    use std::fmt::Display;
+
+    /// Returns `true` when the version, OS, architecture, and checksum are the same
+    pub fn compare<V: PartialEq, D, M>(old: &Artifact<V, D, M>, now: &Artifact<V, D, M>) -> bool {
+        old.version == now.version
+            && old.os == now.os
+            && old.arch == now.arch
+            && old.checksum.value == now.checksum.value
+    }
+
+    /// The version, OS, and architecture i.e. `3.4.0 (linux-amd64)`
+    pub fn display<V: Display, D, M>(artifact: &Artifact<V, D, M>) -> String {
+        format!("{} ({}-{})", artifact.version, artifact.os, artifact.arch)
+    }
+}
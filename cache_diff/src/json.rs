@@ -0,0 +1,199 @@
+//! Diff two arbitrary JSON documents and convert differences to JSON values
+
+use crate::document::Document;
+use crate::Difference;
+use serde_json::{Map, Value};
+
+/// Compare two JSON values and return a message for every key that differs
+///
+/// Objects are compared key by key and nested keys are named with dots i.e. `ruby.version`.
+/// All other values, including arrays, are compared as a whole.
+///
+/// ```rust
+/// use serde_json::json;
+///
+/// let old = json!({"ruby": {"version": "3.3.0"}});
+/// let now = json!({"ruby": {"version": "3.4.0"}});
+///
+/// assert_eq!(
+///     cache_diff::json::diff_values(&old, &now).join(" "),
+///     "ruby.version (`3.3.0` to `3.4.0`)"
+/// );
+/// ```
+pub fn diff_values(old: &Value, now: &Value) -> Vec<String> {
+    diff_values_structured(old, now)
+        .iter()
+        .map(ToString::to_string)
+        .collect()
+}
+
+/// Like [diff_values] but returns a [Difference] for each changed key
+pub fn diff_values_structured(old: &Value, now: &Value) -> Vec<Difference> {
+    crate::document::diff_documents(old, now, false)
+}
+
+/// Like [diff_values_structured] but also reports changes to the shape of the document
+///
+/// Keys that were added or removed and values whose type changed are described as messages with
+/// the dotted key as their code, so an upgrade of the metadata schema itself explains why the
+/// cache was cleared. Other changes are reported the same as [diff_values_structured].
+///
+/// ```rust
+/// use serde_json::json;
+///
+/// let old = json!({"version": "3", "distro": "ubuntu"});
+/// let now = json!({"version": 3, "arch": "arm64"});
+///
+/// let differences = cache_diff::json::diff_schema(&old, &now);
+/// assert_eq!(
+///     differences.iter().map(ToString::to_string).collect::<Vec<_>>(),
+///     vec![
+///         "Field `distro` removed",
+///         "Field `version` changed type from string to number",
+///         "Field `arch` added",
+///     ]
+/// );
+/// ```
+pub fn diff_schema(old: &Value, now: &Value) -> Vec<Difference> {
+    crate::document::diff_documents(old, now, true)
+}
+
+/// Convert differences into a JSON object with a `differences` array, for example to attach
+/// to a build report
+///
+/// ```rust
+/// use cache_diff::Difference;
+///
+/// let value = cache_diff::json::to_value(&[
+///     Difference::changed("version", "3.3.0", "3.4.0").with_code("version"),
+/// ]);
+/// assert_eq!(
+///     value.to_string(),
+///     r#"{"differences":[{"code":"version","name":"version","now":"3.4.0","old":"3.3.0"}]}"#
+/// );
+/// ```
+pub fn to_value(differences: &[Difference]) -> Value {
+    let differences = differences
+        .iter()
+        .map(|difference| {
+            Value::Object(
+                difference
+                    .entries()
+                    .into_iter()
+                    .map(|(key, value)| (key.to_string(), Value::String(value.to_string())))
+                    .collect(),
+            )
+        })
+        .collect();
+
+    Value::Object(Map::from_iter([(
+        "differences".to_string(),
+        Value::Array(differences),
+    )]))
+}
+
+/// Objects are compared key by key, strings are shown without quotes and everything else as compact JSON
+impl Document for Value {
+    fn keys(&self) -> Option<Vec<&str>> {
+        self.as_object()
+            .map(|object| object.keys().map(String::as_str).collect())
+    }
+
+    fn get(&self, key: &str) -> Option<&Self> {
+        self.as_object()?.get(key)
+    }
+
+    fn type_name(&self) -> &'static str {
+        match self {
+            Value::Null => "null",
+            Value::Bool(_) => "boolean",
+            Value::Number(_) => "number",
+            Value::String(_) => "string",
+            Value::Array(_) => "array",
+            Value::Object(_) => "object",
+        }
+    }
+
+    fn display(&self) -> String {
+        match self {
+            Value::String(value) => value.clone(),
+            other => other.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{DiffStyle, Template};
+    use serde_json::json;
+
+    /// Render with backticks so the expected output doesn't depend on enabled features
+    fn backticks(differences: Vec<Difference>) -> Vec<String> {
+        differences
+            .iter()
+            .map(|difference| {
+                difference.render(&Template::default(), |value| {
+                    DiffStyle::Backticks.fmt_value(&value)
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_added_removed_and_nested() {
+        let old = json!({
+            "removed": 1,
+            "list": [1, 2],
+            "os": {"distro": "ubuntu", "version": "22.04"},
+        });
+        let now = json!({
+            "list": [1, 2, 3],
+            "added": true,
+            "os": {"distro": "ubuntu", "version": "24.04"},
+        });
+
+        assert_eq!(
+            backticks(diff_values_structured(&old, &now)),
+            vec![
+                "list (`[1,2]` to `[1,2,3]`)",
+                "os.version (`22.04` to `24.04`)",
+                "removed (`1` to `(not set)`)",
+                "added (`(not set)` to `true`)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_null_is_not_missing() {
+        let old = json!({"version": null});
+        let now = json!({});
+
+        assert_eq!(
+            backticks(diff_values_structured(&old, &now)),
+            vec!["version (`null` to `(not set)`)"]
+        );
+    }
+
+    #[test]
+    fn test_schema_reports_shape_and_value_changes() {
+        let old = json!({"os": {"distro": "ubuntu", "version": "22.04"}, "debug": null});
+        let now = json!({"os": {"distro": "ubuntu", "version": "24.04"}, "debug": false});
+
+        let differences = diff_schema(&old, &now);
+        assert_eq!(
+            differences,
+            vec![
+                Difference::message("Field `debug` changed type from null to boolean")
+                    .with_code("debug"),
+                Difference::changed("os.version", "22.04", "24.04"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_same() {
+        let value = json!({"name": "value"});
+        assert!(diff_values(&value, &value).is_empty());
+    }
+}
@@ -0,0 +1,87 @@
+//! Differences that are only formatted when they are displayed
+
+use crate::{CacheDiff, Difference, Renderer};
+use std::fmt::{Debug, Display};
+
+/// The differences between two cache structs, formatted only when they are displayed
+///
+/// Returned by [CacheDiff::diff_lazy](crate::CacheDiff::diff_lazy). It holds references to both
+/// values instead of messages, so checking whether the cache should be kept compares fields with
+/// [CacheDiff::differs](crate::CacheDiff::differs) without calling any display function. Values
+/// are formatted when it is displayed or converted into messages:
+///
+/// ```rust
+/// use cache_diff::CacheDiff;
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+///
+/// static FORMATTED: AtomicUsize = AtomicUsize::new(0);
+///
+/// fn counted(version: &String) -> &String {
+///     FORMATTED.fetch_add(1, Ordering::SeqCst);
+///     version
+/// }
+///
+/// #[derive(CacheDiff)]
+/// struct Metadata {
+///     #[cache_diff(display = counted)]
+///     version: String,
+/// }
+///
+/// let now = Metadata { version: "3.4.0".to_string() };
+/// let old = Metadata { version: "3.3.0".to_string() };
+/// let diff = now.diff_lazy(&old);
+///
+/// assert!(!diff.is_empty());
+/// assert_eq!(FORMATTED.load(Ordering::SeqCst), 0);
+///
+/// assert_eq!(diff.to_string(), "- version (`3.3.0` to `3.4.0`)");
+/// assert_eq!(FORMATTED.load(Ordering::SeqCst), 2);
+/// ```
+pub struct LazyDiff<'a, T: ?Sized> {
+    now: &'a T,
+    old: &'a T,
+}
+
+impl<'a, T: CacheDiff + ?Sized> LazyDiff<'a, T> {
+    pub(crate) fn new(now: &'a T, old: &'a T) -> Self {
+        Self { now, old }
+    }
+
+    /// Returns `true` when the cache should be retained, without formatting any values
+    pub fn is_empty(&self) -> bool {
+        !self.now.differs(self.old)
+    }
+
+    /// Format the messages, as returned by [CacheDiff::diff](crate::CacheDiff::diff)
+    pub fn lines(&self) -> Vec<String> {
+        self.now.diff(self.old)
+    }
+
+    /// Format the structured differences, as returned by [CacheDiff::diff_structured](crate::CacheDiff::diff_structured)
+    pub fn structured(&self) -> Vec<Difference> {
+        self.now.diff_structured(self.old)
+    }
+}
+
+impl<T: CacheDiff + ?Sized> Display for LazyDiff<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&Renderer::new().render(self.lines()))
+    }
+}
+
+impl<T: ?Sized> Clone for LazyDiff<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: ?Sized> Copy for LazyDiff<'_, T> {}
+
+impl<T: Debug + ?Sized> Debug for LazyDiff<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LazyDiff")
+            .field("now", &self.now)
+            .field("old", &self.old)
+            .finish()
+    }
+}
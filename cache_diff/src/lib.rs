@@ -9,7 +9,17 @@
 //!
 //! Top level struct configuration (Container attributes):
 //!
-//! - `#[cache_diff(custom = <function>)]` Specify a function that receives references to both current and old values and returns a Vec of strings if there are any differences. This function is only called once. It can be in combination with `#[cache_diff(custom)]` on fields to combine multiple related fields into one diff (for example OS distribution and version) or to split apart a monolithic field into multiple differences (for example an "inventory" struct that contains a version and CPU architecture information).
+//! - `#[cache_diff(custom = <function>)]` Specify a function `fn(old: &Self, now: &Self) -> Vec<String>` or `fn(DiffArgs<Self>) -> Vec<String>` (see [DiffArgs]) that returns a Vec of strings if there are any differences, a different signature is a compile error on the attribute. This function is only called once. Repeat the attribute to call several functions in order. It can be in combination with `#[cache_diff(custom)]` on fields to combine multiple related fields into one diff (for example OS distribution and version) or to split apart a monolithic field into multiple differences (for example an "inventory" struct that contains a version and CPU architecture information).
+//! - `#[cache_diff(parallel)]` Call all `custom = <function>`-s concurrently on scoped threads, results keep their attribute order.
+//! - `#[cache_diff(sample_changed)]` Generate [CacheDiff::sample_changed] to build test data with one field changed, requires `Clone`.
+//! - `#[cache_diff(template = "<template>")]` Specify a [Template] for rendering each changed field of this struct i.e. `"{name}: {old} => {new} ({code})"`.
+//! - `#[cache_diff(serde_rename)]` Use a field's `#[serde(rename = "<name>")]` as its name in the output when it does not have a `#[cache_diff(rename = "<new name>")]`.
+//! - `#[cache_diff(content_hash)]` Generate [CacheDiff::content_hash] over all compared fields, which must implement [Hash](std::hash::Hash). Cannot be combined with `custom = <function>`.
+//! - `#[cache_diff(deny_float_eq)]` Make compared `f32` and `f64` fields a compile error, exact float equality often invalidates the cache for tiny rounding differences. Compare a rounded value with `project` or `delegate`, or with a `compare = <function>` or the `compare` function of a `with` module instead i.e. `delegate = "round()"`. Fields using `project` or `delegate` are always allowed because the derive can't see the type they return, and only fields declared as `f32` or `f64` are checked, not types that wrap them. Fields using `ttl`, `max`, `max_uses`, `with`, `nested`, `compare`, `custom` or `semver` are never compared with `!=` and are allowed.
+//! - `#[cache_diff(allow_unknown_attributes)]` Ignore attribute keys this version of the derive doesn't know, on the struct and its fields, so a struct written for a newer version still compiles in a workspace that resolves an older one. Use `allow_unknown_attributes = "warn"` to show a warning on each ignored key
+//! - `#[cache_diff(description = "<what the cache holds>")]` Name the cache in [CacheDiff::restore_message] and [CacheDiff::invalidate_message], i.e. `description = "Ruby install"` produces `Reusing cached Ruby install`
+//! - `#[cache_diff(remote = "<type>")]` Diff a type from another crate by mirroring its fields, like serde's remote derive. Generates `from_remote(&Remote)`, `diff_remote(&now, &old)` and `diff_structured_remote(&now, &old)` on the mirror struct. Every field, including ignored ones, must match the remote type's public fields and implement [Clone]
+//! - `#[cache_diff(style = <path>)]` Specify a [DiffStyle] used to display values for this struct only, for example `DiffStyle::Plain` to remove backticks.
 //!
 //! Attributes for fields are:
 //!
@@ -17,6 +27,30 @@
 //! - `#[cache_diff(ignore)]` or `#[cache_diff(ignore = "<reason>")]` Ignores the given field with an optional comment string.
 //!   If the field is ignored because you're using a custom diff function (see container attributes) you can use
 //!   `cache_diff(ignore = "custom")` which will check that the container implements a custom function.
+//! - `#[cache_diff(code = "<code>")]` Specify a stable code for the field used by the `{code}` [Template] placeholder, defaults to the field identifier
+//! - `#[cache_diff(sample = <function>)]` Specify a function that receives the field's value and returns a different one for [CacheDiff::sample_changed], defaults to [Default]
+//! - `#[cache_diff(category = "<heading>")]` Group the field under a heading when rendered with [Renderer::render_grouped]
+//! - `#[cache_diff(lines)]` Show a line by line diff of long multi-line values instead of the full old and new values, requires the `text` feature
+//! - `#[cache_diff(display_pair = <function>)]` Specify a function that receives the old and new values `(&old, &now)` and returns both rendered values as a tuple, or a full phrase as a `String`
+//! - `#[cache_diff(delegate = "<field or accessor>")]` Compare and display a value inside the field instead of the field itself, for wrapper types i.e. `delegate = "inner"` uses `self.version.inner` and `delegate = "get()"` uses `self.version.get()`. Chain with dots i.e. `"value().inner"`
+//! - `#[cache_diff(project = <function>)]` Compare and display the result of a function applied to the field instead of the field itself, so insignificant parts of a value don't invalidate the cache i.e. only the `major.minor` of a version or the file name of a path. The function takes a reference to the field and returns any `PartialEq + Display` value
+//! - `#[cache_diff(empty_is_none)]` Compare an empty string and `None` as equal on `String` and `Option<String>` fields, so a serializer that round-trips a missing value as `""` does not invalidate the cache. Only applies to the `!=` comparison, so it cannot be combined with `ttl`, `max`, `max_uses`, `with`, `compare`, `custom` or `semver`
+//! - `#[cache_diff(filter = <function>)]` Only compare and display the elements of a collection the function keeps, i.e. to drop dev dependencies. The function returns `bool` and receives `&T` for a `Vec` or set, and `&K, &V` for a map. It runs before `project`
+//! - `#[cache_diff(with = <module>)]` Compare and display the field with functions from a module, `compare(old: &T, now: &T) -> bool` returns `true` when the values are the same and `display(&T)` returns a value that implements Display. Keeps related logic together so it can be shared between buildpacks. Cannot be combined with `display`. With the `inventory` feature, `with = cache_diff::inventory::artifact` handles `libherokubuildpack` inventory artifacts
+//! - `#[cache_diff(labels = "was/now")]` Show the old and new values with labels i.e. ``stack (was `heroku-22`, now `heroku-24`)`` instead of the template
+//! - `#[cache_diff(nested)]` Include the differences of a field whose type implements [CacheDiff] itself, each keyed under the field in [CacheDiff::diff_paths]. `BTreeMap` and `HashMap` fields with displayable keys and values report one difference per added, removed, or changed key
+//! - `#[cache_diff(alias = "<previous name>")]` The name the field had in metadata written by an earlier version of the struct, listed in [CacheDiff::FIELD_ALIASES]. With the `toml` feature, `cache_diff::files::CacheDiffFileExt::diff_path` reads a value stored under the previous key as this field, so a rename shows as a changed value instead of failing to load the old metadata
+//! - `#[cache_diff(normalize_path)]` or `#[cache_diff(normalize_path = "<base dir>")]` Display a path, or any value that implements `AsRef<Path>`, with forward slashes and relative to the base directory when given, see [normalize_path]. Output is identical on Windows and Linux, for example in snapshot tests
+//! - `#[cache_diff(compare = <function>)]` Specify a function `fn(old: &T, now: &T) -> bool` that returns `true` when the values are the same, used instead of `!=` while keeping the derived message, for example to ignore patch versions. Cannot be combined with `ttl`, `max`, `max_uses` or `with`
+//! - `#[cache_diff(custom = <function>)]` Specify a function `fn(old: &T, now: &T) -> Option<String>` or `-> Vec<String>` that returns this field's messages instead of the derived difference, while the other fields stay derived. Cannot be combined with attributes that decide when the field differs or how its difference is shown. The value is still displayed in [CacheDiff::initial_message], use `display` for values that don't implement Display. When a [RedactionPolicy] hides the field, its messages are replaced by a single difference with the placeholder values
+//! - `#[cache_diff(fmt = "<template>")]` Specify a [Template] for this field only, replacing the struct's template i.e. `"{name} changed from {old} to {new}"`. Invalid placeholders are a compile error. Cannot be combined with `labels` or `lines`
+//! - `#[cache_diff(debug)]` Display the value with its `Debug` output `{:?}`, for types that don't implement Display without writing a `display = <function>`
+//! - `#[cache_diff(semver = "major")]`, `"minor"` or `"patch"` Only report a difference when a version string changes at or above that [SemverPart], i.e. `semver = "major"` keeps the cache from `20.11.0` to `20.18.1`. Cannot be combined with `compare`, `with`, `ttl`, `max` or `max_uses`
+//! - `#[cache_diff(skip_if = <function>)]` Specify a function `fn(old: &T, now: &T) -> bool` of the field's values, the field is not compared when it returns `true`. To decide based on other fields, i.e. ignore a checksum when the download URL also changed, the function can instead take the whole struct `fn(old: &Self, now: &Self) -> bool`
+//! - `#[cache_diff(group = "<name>")]` Report every field with the same group name as one difference, their values joined with `-` in declaration order, i.e. `group = "OS"` on `os_distribution` and `os_version` reports ``OS (`ubuntu-22.04` to `ubuntu-24.04`)`` when either changes. The difference's identifier is the group name and its code lists the codes of the fields that changed. Cannot be combined with attributes that replace how the field's difference is shown
+//! - `#[cache_diff(ttl = "<duration>")]` Invalidate the cache when the old timestamp is older than the duration, i.e. `ttl = "30d"` on `created_at` reports `created at expired (created 45 days ago, limit 30 days)`. Units are `s`, `m`, `h`, `d` and `w`. The field must implement [Timestamp], which includes [std::time::SystemTime] and unix seconds as `u64` or `i64`
+//! - `#[cache_diff(max = <number>)]` Invalidate the cache when the new value is larger than the limit, i.e. `max = 200` reports `cache usage count (205) exceeded limit 200`. The value is not otherwise compared, so a counter that changes every build keeps the cache
+//! - `#[cache_diff(max_uses = <count>)]` Invalidate the cache once the old value of a usage counter reaches the count. Call [CacheDiff::record_reuse] when keeping the cache to carry the old count forward plus one, see [UsageCount]
 //!
 //! ## Why
 //!
@@ -38,10 +72,20 @@
 //! $ cargo add cache_diff --features bullet_stream
 //! ```
 //!
+//! To color values without pulling in `bullet_stream`, add the `owo-colors` feature and opt a struct into
+//! it with `#[cache_diff(style = cache_diff::DiffStyle::OwoColors)]`, which also makes field names bold.
+//!
+//! The runtime crate compiles for `wasm32-unknown-unknown` as long as the `bullet_stream` feature is disabled.
+//!
+//! Buildpacks that hold on to the [Diff] of many layers can enable the `smallvec` feature, which stores up to four
+//! differences inline instead of allocating.
+//!
 //! ## Derive usage
 //!
 //! By default a `#[derive(CacheDiff)]` will generate a `diff` function that compares each field in the struct.
-//! You can disable this dependency by specifying `features = []`.
+//! The macro is provided by the default `derive` feature. Crates that only use or implement the trait, such as
+//! generic layer handling code, can disable it with `default-features = false` to keep `syn` and `quote` out of
+//! their build.
 //!
 //! ```rust
 //! use cache_diff::CacheDiff;
@@ -124,6 +168,24 @@
 //! assert_eq!(diff.join(" "), "Ruby version (`3.3.0` to `3.4.0`)");
 //! ```
 //!
+//! If your struct is already renamed for serde, you can reuse those names instead of repeating them:
+//!
+//! ```rust
+//! use cache_diff::CacheDiff;
+//! use serde::Deserialize;
+//!
+//! #[derive(CacheDiff, Deserialize)]
+//! #[cache_diff(serde_rename)]
+//! struct Metadata {
+//!     #[serde(rename = "ruby_version")]
+//!     version: String,
+//! }
+//! let now = Metadata { version: "3.4.0".to_string() };
+//! let diff = now.diff(&Metadata { version: "3.3.0".to_string() });
+//!
+//! assert_eq!(diff.join(" "), "ruby_version (`3.3.0` to `3.4.0`)");
+//! ```
+//!
 //! ## Ignore attributes
 //!
 //! If the struct contains fields that should not be included in the diff comparison, you can ignore them:
@@ -144,6 +206,13 @@
 //! assert!(diff.is_empty());
 //! ```
 //!
+//! When some fields are informational, an [InvalidationPolicy] only recommends clearing the cache for significant
+//! differences, or once enough minor differences accumulate i.e.
+//! `InvalidationPolicy::new().minor(["changed_by"]).threshold(2).should_invalidate(&now.diff_structured(&old))`.
+//!
+//! To skip a field without changing the struct, for example from configuration while a noisy field is investigated,
+//! pass an [IgnoreSet] to [CacheDiff::diff_filtered] i.e. `now.diff_filtered(&old, &IgnoreSet::from(["changed_by"]))`.
+//!
 //! ## Handle structs missing display
 //!
 //! Not all structs implement the [`Display`](std::fmt::Display) trait, for example [`std::path::PathBuf`](std::path::PathBuf) requires that you call `display()` on it.
@@ -175,6 +244,32 @@
 //! assert_eq!(diff.join(" "), "version (`custom 3.3.0` to `custom 3.4.0`)");
 //! ```
 //!
+//! Some values only make sense relative to each other. A `display_pair = <function>` receives both the old and new
+//! value and returns either the pair of rendered values, or a `String` phrase that replaces the whole line. The
+//! field's `Display` (or `display = <function>`) is still used when a single value is shown:
+//!
+//! ```rust
+//! use cache_diff::CacheDiff;
+//! use std::path::{Path, PathBuf};
+//!
+//! #[derive(CacheDiff)]
+//! struct Metadata {
+//!     #[cache_diff(display_pair = changed_suffix)]
+//!     install_dir: PathBuf,
+//! }
+//!
+//! fn changed_suffix(old: &PathBuf, now: &PathBuf) -> (String, String) {
+//!     let common = old.components().zip(now.components()).take_while(|(a, b)| a == b).count();
+//!     let suffix = |path: &Path| format!(".../{}", path.components().skip(common).collect::<PathBuf>().display());
+//!     (suffix(old), suffix(now))
+//! }
+//!
+//! let now = Metadata { install_dir: PathBuf::from("/layers/heroku_ruby/ruby/3.4.0") };
+//! let diff = now.diff(&Metadata { install_dir: PathBuf::from("/layers/heroku_ruby/ruby/3.3.0") });
+//!
+//! assert_eq!(diff.join(" "), "install dir (`.../3.3.0` to `.../3.4.0`)");
+//! ```
+//!
 //! ## Customize one or more field differences
 //!
 //! You can provide a custom implementation for a diffing a subset of fields without having to roll your own implementation.
@@ -226,12 +321,369 @@
 //! you only wanted to have one output for a combined `os_distribution` and `os_version` in one output
 //! like "OS (ubuntu-22 to ubuntu-24)". Alternatively, you can use <https://github.com/schneems/magic_migrate> to
 //! re-arrange your struct to only have one field with a custom display.
+//!
+//! A numeric limit like this one is common enough to be built in. The same check without a custom function:
+//!
+//! ```rust
+//! use cache_diff::CacheDiff;
+//!
+//! #[derive(Debug, CacheDiff)]
+//! pub(crate) struct Metadata {
+//!     #[cache_diff(max = 200.0)]
+//!     cache_usage_count: f32,
+//!
+//!     binary_version: String,
+//! }
+//! let now = Metadata { cache_usage_count: 205.0, binary_version: "1.0".to_string() };
+//! let old = Metadata { cache_usage_count: 204.0, binary_version: "1.0".to_string() };
+//!
+//! assert_eq!(
+//!     now.diff(&old),
+//!     vec!["cache usage count (205) exceeded limit 200".to_string()]
+//! );
+//! ```
+//!
+//! ### Multiple custom functions
+//!
+//! Repeat `custom = <function>` to call more than one function, their differences are listed in the
+//! order the attributes are written, before any derived field differences. When the functions do real
+//! work, such as hashing files or running a subprocess to check a version, add `parallel` to call them
+//! concurrently with [`std::thread::scope`]. The struct must be [Sync] to use `parallel`:
+//!
+//! ```rust
+//! use cache_diff::CacheDiff;
+//!
+//! #[derive(CacheDiff)]
+//! #[cache_diff(custom = diff_lockfile, custom = diff_compiler, parallel)]
+//! struct Metadata {
+//!     version: String,
+//!     #[cache_diff(ignore = "custom")]
+//!     lockfile_digest: String,
+//!     #[cache_diff(ignore = "custom")]
+//!     compiler: String,
+//! }
+//!
+//! fn diff_lockfile(old: &Metadata, now: &Metadata) -> Vec<String> {
+//!     if old.lockfile_digest != now.lockfile_digest {
+//!         vec!["Lockfile changed".to_string()]
+//!     } else {
+//!         Vec::new()
+//!     }
+//! }
+//!
+//! fn diff_compiler(old: &Metadata, now: &Metadata) -> Vec<String> {
+//!     if old.compiler != now.compiler {
+//!         vec!["Compiler changed".to_string()]
+//!     } else {
+//!         Vec::new()
+//!     }
+//! }
+//!
+//! let old = Metadata { version: "3.3.0".to_string(), lockfile_digest: "a".to_string(), compiler: "gcc".to_string() };
+//! let now = Metadata { version: "3.4.0".to_string(), lockfile_digest: "b".to_string(), compiler: "clang".to_string() };
+//!
+//! assert_eq!(
+//!     now.diff(&old),
+//!     vec!["Lockfile changed", "Compiler changed", "version (`3.3.0` to `3.4.0`)"]
+//! );
+//! ```
+//!
+//! ## Diff TOML documents
+//!
+//! Some layers store metadata without a concrete struct to derive on. With the `toml` feature,
+//! `cache_diff::toml::diff_values` compares two `toml::Value` documents and returns messages in the same style,
+//! naming nested keys with dots i.e. `ruby.version`. With the `serde_json` feature, `cache_diff::json::diff_values`
+//! does the same for free-form `serde_json::Value` metadata. Both modules provide `diff_schema`, which also
+//! explains fields that were added, removed, or changed type, for when the shape of the metadata itself changes.
+//!
+//! ## Metadata files
+//!
+//! Most cached layers read the previous metadata from a TOML file before diffing. With the `toml` feature,
+//! `cache_diff::files::CacheDiffFileExt::diff_path` reads, parses, and diffs in one call and returns a `DiffFileError` that
+//! distinguishes a missing file, invalid TOML, and metadata that no longer matches the struct.
+//! To compare two files without a struct, `cache_diff::files::diff_toml` loads both and diffs them with dotted
+//! key names.
+//!
+//! ## Diff long strings
+//!
+//! Showing the full old and new values of a long string such as a lockfile or a `PATH` buries the change.
+//! With the `text` feature, `cache_diff::text::inline` and `cache_diff::text::unified` show only what changed
+//! within a value. They are used by the `#[cache_diff(lines)]` field attribute and can be called from a
+//! `custom = <function>` so its output matches.
+//!
+//! ## Telemetry
+//!
+//! To aggregate cache churn across builds, the `telemetry` feature's `cache_diff::telemetry::attributes` converts a
+//! structured diff into a bounded set of key-values (counts and field codes, never values) that map directly onto
+//! OpenTelemetry span attributes. With the `tracing` feature, `cache_diff::telemetry::record_invalidation` records an
+//! event with the changed field names and codes whenever a diff is not empty, so invalidations show up in distributed
+//! traces.
+//!
+//! ## Testing
+//!
+//! For one line layer cache tests, use [CacheDiff::assert_invalidates] and [CacheDiff::assert_keeps].
+//!
+//! With the `libcnb-test` feature, the `cache_diff::testing` module helps assert on diffs without brittle regexes.
+//! Compare values directly with `assert_no_diff!` and `assert_diff_contains!`, or check that captured `pack build`
+//! output reports a cache invalidation for each field name with `assert_invalidated_with!(output, ["Ruby version"])`
+//! and `assert_not_invalidated_with!`, without hardcoding the full messages. To inspect a single line of output,
+//! `cache_diff::testing::parse_line` returns its `(name, old, new)` parts. To lock user facing wording,
+//! `cache_diff::testing::assert_golden` compares a diff against a checked in fixture file and rewrites it when run with
+//! `CACHE_DIFF_UPDATE_GOLDEN=1`.
+//!
+//! For property tests, the `proptest` feature's `cache_diff::proptest::mutated_pair` generates an old value and a
+//! copy with a random set of fields changed by [CacheDiff::sample_changed], to assert that exactly those fields
+//! are reported for any value.
+//!
+//! ## Export
+//!
+//! To record why a layer was rebuilt as structured data, convert a structured diff with
+//! `cache_diff::toml::to_value` (`toml` feature) or `cache_diff::json::to_value` (`serde_json` feature).
+//!
+//! ## Field manifest
+//!
+//! Derived implementations describe the fields they compare in [CacheDiff::FIELDS], including names, categories,
+//! and codes. With the `serde` feature each [FieldInfo] can be serialized, for example to generate documentation
+//! of what invalidates a cache. Both are `const`, so `const _: () = assert!(Metadata::CACHE_DIFF_FIELD_COUNT == 7);`
+//! fails to compile when a field is added or removed without updating the assertion.
+//!
+//! ## Redaction
+//!
+//! Values of sensitive fields can be hidden from the output for all derived diffs in a process by
+//! installing a [RedactionPolicy]. This lets security policy live in one place instead of on every struct:
+//!
+//! ```rust
+//! use cache_diff::RedactionPolicy;
+//!
+//! RedactionPolicy::new()
+//!     .placeholder("[REDACTED]")
+//!     .pattern("*password*")
+//!     .pattern("*_token")
+//!     .install();
+//! ```
+//!
+//! ## Rendering
+//!
+//! A diff is a list of strings, to display them to a user use a [Renderer]. It places each
+//! difference on its own line and can optionally prefix the output with the number of changes:
+//!
+//! ```rust
+//! use cache_diff::{CacheDiff, Renderer};
+//!
+//! #[derive(CacheDiff)]
+//! struct Metadata {
+//!     version: String,
+//!     distro: String,
+//! }
+//! let now = Metadata { version: "3.4.0".to_string(), distro: "Ubuntu".to_string() };
+//! let diff = now.diff(&Metadata { version: "3.3.0".to_string(), distro: "Alpine".to_string() });
+//!
+//! assert_eq!(
+//!     Renderer::new().summary(true).render(&diff),
+//!     "2 changes detected:\n- version (`3.3.0` to `3.4.0`)\n- distro (`Alpine` to `Ubuntu`)"
+//! );
+//! ```
+//!
+//! If you only need to display the differences when there are some, use [CacheDiff::changes]
+//! which returns a [Diff] that renders the same way:
+//!
+//! ```rust
+//! use cache_diff::CacheDiff;
+//!
+//! #[derive(CacheDiff)]
+//! struct Metadata {
+//!     version: String,
+//! }
+//! let now = Metadata { version: "3.4.0".to_string() };
+//!
+//! if let Some(diff) = now.changes(&Metadata { version: "3.3.0".to_string() }) {
+//!     println!("Clearing cache:\n{diff}");
+//! }
+//! ```
+//!
+//! When there is no old metadata, [CacheDiff::initial_message] describes the current values with the same names,
+//! display functions, and style, for example to print `Installing Ruby version `3.4.0`` on a cold cache.
+//!
+//! Output from [CacheDiff::diff] may contain ANSI colors when the `bullet_stream` feature is enabled by any crate in
+//! the build. For output that is stable regardless of features use [render_plain], or [strip_ansi] to clean up a string.
+//! For snapshot tests, [render_stable] renders a [Diff] the same way across feature combinations and releases.
+//!
+//! With the `bullet_stream` feature, `cache_diff::bullet::print_diff` writes each difference of a [Diff] as a sub-bullet.
+//! Buildpacks that use the `libherokubuildpack` log helpers can enable the `libherokubuildpack` feature and print a
+//! header followed by each difference with `cache_diff::log::log_diff`.
+//!
+//! With the `libcnb` feature, `cache_diff::libcnb::restored_layer_action` turns a diff into the
+//! `(RestoredLayerAction, Vec<String>)` that a libcnb `CachedLayerDefinition` expects, keeping the layer when
+//! there are no differences. `cache_diff::libcnb::invalidate_if_changed(&metadata)` builds the whole
+//! `restored_layer_action` closure.
+//!
+//! When the metadata struct has changed between releases, the `magic_migrate` feature's
+//! `cache_diff::migrate::diff_after_migration` migrates the stored TOML to the current struct before diffing,
+//! and reports whether it did not match any known version or failed to migrate.
+//!
+//! To surface invalidation reasons in the GitHub Actions UI, render a structured diff with [github_annotations].
+//!
+//! Large structs can group their fields with a `category` and render them under subheadings
+//! with [Renderer::render_grouped]:
+//!
+//! ```rust
+//! use cache_diff::{CacheDiff, Renderer};
+//!
+//! #[derive(CacheDiff)]
+//! struct Metadata {
+//!     #[cache_diff(category = "Runtime")]
+//!     version: String,
+//!     #[cache_diff(category = "Operating system")]
+//!     distro: String,
+//!     #[cache_diff(category = "Operating system")]
+//!     distro_version: String,
+//! }
+//! let now = Metadata { version: "3.4.0".to_string(), distro: "Ubuntu".to_string(), distro_version: "24.04".to_string() };
+//! let diff = now.diff_structured(&Metadata { version: "3.3.0".to_string(), distro: "Ubuntu".to_string(), distro_version: "22.04".to_string() });
+//!
+//! assert_eq!(
+//!     Renderer::new().render_grouped(&diff),
+//!     "Runtime:\n- version (`3.3.0` to `3.4.0`)\nOperating system:\n- distro version (`22.04` to `24.04`)"
+//! );
+//! ```
+//!
+//! Environment variables don't fit a derived struct well, capture them with [EnvSnapshot] to report each added,
+//! removed, or changed variable. `PATH`-like variables report the directories that were added or removed.
+//!
+//! Metadata with `#[cache_diff(nested)]` fields can be rendered as an indented tree with each parent field
+//! above its differences with [DiffTree].
+//!
+//! With the `history` feature, `cache_diff::history::CacheDiffLog` appends each build's structured diff along with
+//! a timestamp and build identifier to a TOML file inside the layer, and renders the invalidation history to help
+//! debug a cache that is cleared more often than expected.
+//!
+//! Buildpacks with several cached layers can collect each layer's [CacheDiff::changes] in a [LayerDiffReport]
+//! to print one consolidated report i.e. `2 layers invalidated: ruby, bundler` followed by each layer's differences.
+
+#[cfg(feature = "bullet_stream")]
+pub mod bullet;
+mod custom;
+mod diff;
+mod diff_or_new;
+mod difference;
+mod differences;
+#[cfg(any(feature = "toml", feature = "serde_json"))]
+mod document;
+mod env;
+mod field_info;
+#[cfg(feature = "toml")]
+pub mod files;
+#[cfg(feature = "history")]
+pub mod history;
+mod ignore;
+#[cfg(feature = "inventory")]
+pub mod inventory;
+#[cfg(feature = "serde_json")]
+pub mod json;
+mod lazy;
+#[cfg(feature = "libcnb")]
+pub mod libcnb;
+#[cfg(feature = "libherokubuildpack")]
+pub mod log;
+mod map;
+#[cfg(feature = "magic_migrate")]
+pub mod migrate;
+mod path;
+mod plain;
+mod policy;
+#[cfg(feature = "proptest")]
+pub mod proptest;
+mod redaction;
+mod render;
+mod report;
+mod semver;
+mod style;
+#[cfg(feature = "telemetry")]
+pub mod telemetry;
+mod template;
+#[cfg(feature = "libcnb-test")]
+pub mod testing;
+#[cfg(feature = "text")]
+pub mod text;
+#[cfg(feature = "toml")]
+pub mod toml;
+mod tree;
+mod ttl;
+mod usage;
+
+pub use custom::DiffArgs;
+#[doc(hidden)]
+pub use custom::{CustomDiff, Named, Positional};
+pub use diff::Diff;
+pub use diff_or_new::DiffOrNew;
+pub use difference::Difference;
+pub use differences::Differences;
+pub use env::EnvSnapshot;
+#[doc(hidden)]
+pub use field_info::{
+    diff_fields, empty_is_none, exceeds_max, field_custom, field_debug, field_display, field_ne,
+    filter_field, max_message, nested_diff, nested_differs, nested_display, render_differences,
+    DebugDisplay, DisplayCallback, EmptyIsNone, FieldCustom, FieldDebug, FieldDiff, FieldDisplay,
+    FieldPartialEq, FilterField, IntoPairDisplay, NestedCacheDiff, PairDisplay, SkipField, SkipIf,
+    SkipStruct,
+};
+pub use field_info::{FieldExplanation, FieldInfo, FieldStatus};
+pub use ignore::IgnoreSet;
+pub use lazy::LazyDiff;
+pub use path::normalize_path;
+pub use plain::{render_plain, render_stable, strip_ansi};
+pub use policy::InvalidationPolicy;
+pub use redaction::RedactionPolicy;
+pub use render::{github_annotations, Renderer};
+pub use report::LayerDiffReport;
+pub use semver::SemverPart;
+#[doc(hidden)]
+pub use semver::{same_semver_major, same_semver_minor, same_semver_patch};
+pub use style::DiffStyle;
+pub use template::{Template, TemplateError};
+pub use tree::DiffTree;
+pub use ttl::Timestamp;
+#[doc(hidden)]
+pub use ttl::{ttl_display, ttl_expired, ttl_message};
+pub use usage::UsageCount;
+#[doc(hidden)]
+pub use usage::{increment_uses, uses_message, uses_reached};
 
 /// Centralized cache invalidation logic with human readable differences
 ///
 /// When a struct is used to represent values in a cache, this trait can be implemented to
 /// to determine whether or not that cache needs to be invalidated.
 pub trait CacheDiff {
+    /// The fields compared by a derived implementation in the order they are compared
+    ///
+    /// Manual implementations do not need to provide this.
+    const FIELDS: &'static [FieldInfo] = &[];
+
+    /// Previous and current keys of fields renamed with `#[cache_diff(alias = "<previous name>")]`
+    ///
+    /// Used by `cache_diff::files::CacheDiffFileExt::diff_path` to read metadata written before the
+    /// rename, so the field reports a changed value instead of failing to deserialize. Manual implementations do not need to provide this.
+    const FIELD_ALIASES: &'static [(&'static str, &'static str)] = &[];
+
+    /// The number of fields compared by a derived implementation
+    ///
+    /// Usable in const contexts to catch accidental changes to cache relevant fields at compile time:
+    ///
+    /// ```rust
+    /// use cache_diff::CacheDiff;
+    ///
+    /// #[derive(CacheDiff)]
+    /// struct Metadata {
+    ///     version: String,
+    ///     distro: String,
+    ///     #[cache_diff(ignore)]
+    ///     changed_by: String,
+    /// }
+    ///
+    /// const _: () = assert!(Metadata::CACHE_DIFF_FIELD_COUNT == 2);
+    /// ```
+    const CACHE_DIFF_FIELD_COUNT: usize = Self::FIELDS.len();
+
     /// Given another cache object, returns a list of differences between the two.
     ///
     /// If no differences, return an empty list. An empty list should indicate that the
@@ -239,17 +691,543 @@ pub trait CacheDiff {
     /// the cached value should be invalidated.
     fn diff(&self, old: &Self) -> Vec<String>;
 
-    #[cfg(feature = "bullet_stream")]
-    fn fmt_value<T: std::fmt::Display>(&self, value: &T) -> String {
-        bullet_stream::style::value(value.to_string())
+    /// Like [CacheDiff::diff] but only formats values when the result is displayed
+    ///
+    /// Checking [LazyDiff::is_empty] compares fields without calling display functions, so the
+    /// common keep-the-cache path does no formatting:
+    ///
+    /// ```rust
+    /// use cache_diff::CacheDiff;
+    ///
+    /// #[derive(CacheDiff)]
+    /// struct Metadata {
+    ///     version: String,
+    /// }
+    /// let now = Metadata { version: "3.4.0".to_string() };
+    /// let old = Metadata { version: "3.3.0".to_string() };
+    ///
+    /// let diff = now.diff_lazy(&old);
+    /// if !diff.is_empty() {
+    ///     println!("Clearing cache:\n{diff}");
+    /// }
+    /// ```
+    fn diff_lazy<'a>(&'a self, old: &'a Self) -> LazyDiff<'a, Self> {
+        LazyDiff::new(self, old)
+    }
+
+    /// Given another cache object, returns the messages of [CacheDiff::diff] along with their
+    /// structured [Difference]-s
+    ///
+    /// [Differences] dereferences to `Vec<String>` so it can be used anywhere the result of
+    /// [CacheDiff::diff] is, it is empty when the cache should be retained:
+    ///
+    /// ```rust
+    /// use cache_diff::CacheDiff;
+    ///
+    /// #[derive(CacheDiff)]
+    /// struct Metadata {
+    ///     version: String,
+    /// }
+    /// let now = Metadata { version: "3.4.0".to_string() };
+    /// let differences = now.differences(&Metadata { version: "3.3.0".to_string() });
+    ///
+    /// if !differences.is_empty() {
+    ///     println!("Clearing cache:\n{}", differences.render());
+    /// }
+    /// assert_eq!(differences, vec!["version (`3.3.0` to `3.4.0`)"]);
+    /// ```
+    fn differences(&self, old: &Self) -> Differences {
+        Differences::new(self, self.diff_structured(old))
+    }
+
+    /// Like [CacheDiff::diff] for when the old metadata may not exist, i.e. on the first build
+    ///
+    /// Returns [DiffOrNew::New] when there is no old metadata, so call sites don't need to match on it:
+    ///
+    /// ```rust
+    /// use cache_diff::{CacheDiff, DiffOrNew};
+    ///
+    /// #[derive(CacheDiff)]
+    /// struct Metadata {
+    ///     version: String,
+    /// }
+    /// let now = Metadata { version: "3.4.0".to_string() };
+    /// let old: Option<Metadata> = None;
+    ///
+    /// match now.diff_or_new(old.as_ref()) {
+    ///     DiffOrNew::New => println!("Creating cache"),
+    ///     DiffOrNew::Diff(diff) if diff.is_empty() => println!("Using cache"),
+    ///     DiffOrNew::Diff(diff) => println!("Clearing cache: {}", diff.join(", ")),
+    /// }
+    /// ```
+    fn diff_or_new(&self, old: Option<&Self>) -> DiffOrNew {
+        match old {
+            Some(old) => DiffOrNew::Diff(self.diff(old)),
+            None => DiffOrNew::New,
+        }
+    }
+
+    /// Report what is not default about this value, i.e. to describe a layer's state when debugging
+    ///
+    /// Diffs against [Default::default] as the old value, so each message shows the default and the current value:
+    ///
+    /// ```rust
+    /// use cache_diff::CacheDiff;
+    ///
+    /// #[derive(CacheDiff, Default)]
+    /// struct Metadata {
+    ///     version: String,
+    ///     distro: String,
+    /// }
+    /// let now = Metadata { version: "3.4.0".to_string(), ..Default::default() };
+    ///
+    /// assert_eq!(
+    ///     now.diff_against_default(),
+    ///     vec!["version (`` to `3.4.0`)".to_string()]
+    /// );
+    /// ```
+    fn diff_against_default(&self) -> Vec<String>
+    where
+        Self: Default + Sized,
+    {
+        self.diff(&Self::default())
+    }
+
+    /// Like [CacheDiff::diff] as an associated function with the old value first
+    ///
+    /// Naming both values at the call site avoids swapping which one is old:
+    ///
+    /// ```rust
+    /// use cache_diff::CacheDiff;
+    ///
+    /// #[derive(CacheDiff)]
+    /// struct Metadata {
+    ///     version: String,
+    /// }
+    /// let old = Metadata { version: "3.3.0".to_string() };
+    /// let now = Metadata { version: "3.4.0".to_string() };
+    ///
+    /// assert_eq!(
+    ///     Metadata::diff_between(&old, &now),
+    ///     vec!["version (`3.3.0` to `3.4.0`)".to_string()]
+    /// );
+    /// ```
+    fn diff_between(old: &Self, now: &Self) -> Vec<String>
+    where
+        Self: Sized,
+    {
+        now.diff(old)
+    }
+
+    /// Given another cache object, returns a list of structured differences between the two.
+    ///
+    /// Derived implementations return one [Difference] per changed field with its name,
+    /// category and values. The default implementation wraps each message from [CacheDiff::diff].
+    fn diff_structured(&self, old: &Self) -> Vec<Difference> {
+        self.diff(old)
+            .into_iter()
+            .map(Difference::message)
+            .collect()
+    }
+
+    /// Like [CacheDiff::diff_structured] with each difference keyed by its path
+    ///
+    /// A path is the identifier of the changed field, preceded by the identifiers of the fields it
+    /// is nested in, see [Difference::path]. Tooling can use it to address changes structurally,
+    /// for example to group them by layer or to drop a subtree:
+    ///
+    /// ```rust
+    /// use cache_diff::CacheDiff;
+    ///
+    /// #[derive(CacheDiff)]
+    /// struct Ruby {
+    ///     version: String,
+    /// }
+    ///
+    /// #[derive(CacheDiff)]
+    /// struct Metadata {
+    ///     #[cache_diff(nested)]
+    ///     ruby: Ruby,
+    ///     distro: String,
+    /// }
+    /// let now = Metadata { ruby: Ruby { version: "3.4.0".to_string() }, distro: "Ubuntu".to_string() };
+    /// let old = Metadata { ruby: Ruby { version: "3.3.0".to_string() }, distro: "Ubuntu".to_string() };
+    ///
+    /// let paths = now.diff_paths(&old);
+    /// assert_eq!(paths[0].0, vec!["ruby", "version"]);
+    /// assert_eq!(paths[0].1.to_string(), "version (`3.3.0` to `3.4.0`)");
+    /// ```
+    fn diff_paths(&self, old: &Self) -> Vec<(Vec<String>, Difference)> {
+        self.diff_structured(old)
+            .into_iter()
+            .map(|difference| {
+                let path = difference.path().into_iter().map(String::from).collect();
+                (path, difference)
+            })
+            .collect()
+    }
+
+    /// Like [CacheDiff::diff] but skips the fields in `ignore`
+    ///
+    /// See [IgnoreSet] for how fields are matched.
+    fn diff_filtered(&self, old: &Self, ignore: &IgnoreSet) -> Vec<String> {
+        render_differences(self, &self.diff_structured_filtered(old, ignore))
+    }
+
+    /// Like [CacheDiff::diff_structured] but skips the fields in `ignore`
+    ///
+    /// Derived implementations do not compare ignored fields. The default implementation drops
+    /// differences whose code or name is in the set.
+    fn diff_structured_filtered(&self, old: &Self, ignore: &IgnoreSet) -> Vec<Difference> {
+        let mut differences = self.diff_structured(old);
+        if !ignore.is_empty() {
+            differences.retain(|difference| !ignore.matches(difference));
+        }
+        differences
+    }
+
+    /// Describes each compared field's current value for when there is no old metadata, i.e. a cold cache
+    ///
+    /// Derived implementations return `<name> <value>` for every field in [CacheDiff::FIELDS] using the same
+    /// names, display functions, and style as [CacheDiff::diff], so one set of annotations powers both the
+    /// first install and invalidation messages. Fields handled by `custom = <function>` are not included.
+    /// Manual implementations return an empty list unless they override it.
+    ///
+    /// ```rust
+    /// use cache_diff::CacheDiff;
+    ///
+    /// #[derive(CacheDiff)]
+    /// struct Metadata {
+    ///     #[cache_diff(rename = "Ruby version")]
+    ///     version: String,
+    ///     distro: String,
+    /// }
+    /// let now = Metadata { version: "3.4.0".to_string(), distro: "Ubuntu".to_string() };
+    ///
+    /// assert_eq!(
+    ///     now.initial_message(),
+    ///     vec!["Ruby version `3.4.0`", "distro `Ubuntu`"]
+    /// );
+    /// ```
+    fn initial_message(&self) -> Vec<String> {
+        let mut values = Vec::new();
+        self.visit_field_diffs(&mut |fields| {
+            values = field_info::initial_values(self, Self::FIELDS, fields);
+        });
+        values
+    }
+
+    /// Passes how a derived implementation compares and displays each of [CacheDiff::FIELDS], in the same order
+    ///
+    /// The table is generated once and shared by every method that walks the fields.
+    #[doc(hidden)]
+    fn visit_field_diffs(&self, visit: &mut dyn FnMut(&[FieldDiff<Self>])) {
+        visit(&[]);
+    }
+
+    /// Returns `true` when there is at least one difference, meaning the cache should be invalidated
+    ///
+    /// Derived implementations only compare fields and never format values, use this instead of
+    /// `diff(old).is_empty()` when the differences will not be displayed:
+    ///
+    /// ```rust
+    /// use cache_diff::CacheDiff;
+    ///
+    /// #[derive(CacheDiff)]
+    /// struct Metadata {
+    ///     version: String,
+    /// }
+    /// let now = Metadata { version: "3.4.0".to_string() };
+    ///
+    /// assert!(now.differs(&Metadata { version: "3.3.0".to_string() }));
+    /// assert!(!now.differs(&Metadata { version: "3.4.0".to_string() }));
+    /// ```
+    fn differs(&self, old: &Self) -> bool {
+        !self.diff(old).is_empty()
+    }
+
+    /// A hash of every compared field, `None` unless the struct opts in
+    ///
+    /// When deriving, enable with the `#[cache_diff(content_hash)]` container attribute, every
+    /// compared field must implement [Hash](std::hash::Hash). The hash uses
+    /// [DefaultHasher](std::hash::DefaultHasher) which is not guaranteed to be the same across
+    /// Rust releases, so only use it as a pre-check with [CacheDiff::differs_from_hash].
+    fn content_hash(&self) -> Option<u64> {
+        None
+    }
+
+    /// Like [CacheDiff::differs] but returns `false` without comparing fields when `old_hash`
+    /// matches [CacheDiff::content_hash]
+    ///
+    /// Store the hash alongside the cache to make the unchanged case a single integer compare:
+    ///
+    /// ```rust
+    /// use cache_diff::CacheDiff;
+    ///
+    /// #[derive(CacheDiff)]
+    /// #[cache_diff(content_hash)]
+    /// struct Metadata {
+    ///     version: String,
+    ///     #[cache_diff(ignore = "Stored hash of the other fields")]
+    ///     hash: Option<u64>,
+    /// }
+    ///
+    /// let mut now = Metadata { version: "3.4.0".to_string(), hash: None };
+    /// now.hash = now.content_hash();
+    /// let old = Metadata { version: "3.4.0".to_string(), hash: now.hash };
+    ///
+    /// assert!(!now.differs_from_hash(&old, old.hash));
+    /// assert!(now.differs_from_hash(&Metadata { version: "3.3.0".to_string(), hash: None }, None));
+    /// ```
+    ///
+    /// A mismatched or missing hash falls back to comparing each field. Derived implementations don't
+    /// hash `ttl`, `max` or `max_uses` fields, they are still checked against their limit when the hashes match.
+    fn differs_from_hash(&self, old: &Self, old_hash: Option<u64>) -> bool {
+        match (self.content_hash(), old_hash) {
+            (Some(now_hash), Some(old_hash)) if now_hash == old_hash => false,
+            _ => self.differs(old),
+        }
+    }
+
+    /// A copy of `self` with the field `identifier` changed, `None` if the field is not compared
+    ///
+    /// When deriving, enable with the `#[cache_diff(sample_changed)]` container attribute, the struct
+    /// must implement [Clone]. Fields are set to their [Default] unless a
+    /// `#[cache_diff(sample = <function>)]` is given that receives the current value and returns a
+    /// different one. Combined with [CacheDiff::FIELDS] it checks that every field invalidates the
+    /// cache on its own:
+    ///
+    /// ```rust
+    /// use cache_diff::CacheDiff;
+    ///
+    /// #[derive(Clone, CacheDiff)]
+    /// #[cache_diff(sample_changed)]
+    /// struct Metadata {
+    ///     #[cache_diff(sample = bump)]
+    ///     version: u32,
+    ///     distro: String,
+    /// }
+    ///
+    /// fn bump(version: &u32) -> u32 {
+    ///     version + 1
+    /// }
+    ///
+    /// let metadata = Metadata { version: 1, distro: "Ubuntu".to_string() };
+    /// for field in Metadata::FIELDS {
+    ///     let changed = metadata.sample_changed(field.identifier).unwrap();
+    ///     assert_eq!(changed.diff_structured(&metadata)[0].name(), Some(field.name));
+    /// }
+    /// ```
+    fn sample_changed(&self, identifier: &str) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let _ = identifier;
+        None
+    }
+
+    /// Continue counting uses from the old cache when it is kept, does nothing by default
+    ///
+    /// Derived implementations set every `#[cache_diff(max_uses = N)]` field to the old count plus
+    /// one, see [UsageCount] for an example.
+    fn record_reuse(&mut self, old: &Self) {
+        let _ = old;
+    }
+
+    /// Panics unless there is at least one difference, meaning the cache would be invalidated
+    ///
+    /// Makes layer cache tests a single line:
+    ///
+    /// ```rust
+    /// use cache_diff::CacheDiff;
+    ///
+    /// #[derive(CacheDiff)]
+    /// struct Metadata {
+    ///     version: String,
+    /// }
+    /// let now = Metadata { version: "3.4.0".to_string() };
+    ///
+    /// now.assert_invalidates(&Metadata { version: "3.3.0".to_string() });
+    /// now.assert_keeps(&Metadata { version: "3.4.0".to_string() });
+    /// ```
+    #[track_caller]
+    fn assert_invalidates(&self, old: &Self) {
+        if self.diff_structured(old).is_empty() {
+            panic!("Expected the cache to be invalidated, but no differences were found");
+        }
+    }
+
+    /// Panics with the rendered differences if there are any, meaning the cache would be invalidated
+    #[track_caller]
+    fn assert_keeps(&self, old: &Self) {
+        let differences = self.diff_structured(old);
+        if !differences.is_empty() {
+            panic!(
+                "Expected the cache to be kept, found {} difference(s):\n{}",
+                differences.len(),
+                render_plain(&differences)
+            );
+        }
+    }
+
+    /// Write each difference into `w` as a bullet point on its own line, returning how many were written
+    ///
+    /// Produces the same lines as [CacheDiff::diff] without collecting them into a `Vec<String>`
+    /// first, which is useful when the output is immediately printed or appended to a buffer:
+    ///
+    /// ```rust
+    /// use cache_diff::CacheDiff;
+    ///
+    /// #[derive(CacheDiff)]
+    /// struct Metadata {
+    ///     version: String,
+    /// }
+    /// let mut out = String::new();
+    /// let count = Metadata { version: "3.4.0".to_string() }
+    ///     .write_diff(&Metadata { version: "3.3.0".to_string() }, &mut out)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(count, 1);
+    /// assert_eq!(out, "- version (`3.3.0` to `3.4.0`)\n");
+    /// ```
+    fn write_diff<W: std::fmt::Write + ?Sized>(
+        &self,
+        old: &Self,
+        w: &mut W,
+    ) -> Result<usize, std::fmt::Error> {
+        let differences = self.diff_structured(old);
+        if differences.is_empty() {
+            return Ok(0);
+        }
+        let template = self.template();
+        for difference in &differences {
+            w.write_str("- ")?;
+            difference.write_styled(
+                w,
+                &template,
+                |value| self.fmt_value(&value),
+                |name| self.fmt_name(name),
+            )?;
+            w.write_str("\n")?;
+        }
+        Ok(differences.len())
+    }
+
+    /// Given another cache object, returns `Some` [Diff] if there are any differences
+    ///
+    /// A `None` indicates that the cache should be retained.
+    fn changes(&self, old: &Self) -> Option<Diff> {
+        let differences = self.diff_structured(old);
+        if differences.is_empty() {
+            None
+        } else {
+            Some(
+                Diff::from(differences)
+                    .with_style(self.style())
+                    .with_template(self.template()),
+            )
+        }
+    }
+
+    /// The style used to display values in the diff output
+    ///
+    /// Override to change the style for a single struct. When deriving, use the
+    /// `#[cache_diff(style = <path>)]` container attribute, for example to drop backticks:
+    ///
+    /// ```rust
+    /// use cache_diff::{CacheDiff, DiffStyle};
+    ///
+    /// #[derive(CacheDiff)]
+    /// #[cache_diff(style = DiffStyle::Plain)]
+    /// struct Metadata {
+    ///     version: String,
+    /// }
+    /// let diff = Metadata { version: "3.4.0".to_string() }
+    ///     .diff(&Metadata { version: "3.3.0".to_string() });
+    ///
+    /// assert_eq!(diff.join(" "), "version (3.3.0 to 3.4.0)");
+    /// ```
+    fn style(&self) -> DiffStyle {
+        DiffStyle::default()
+    }
+
+    /// The format used to render each changed field, the default is the installed [Template]
+    /// or `"{name} ({old} to {new})"`
+    ///
+    /// When deriving, use the `#[cache_diff(template = "...")]` container attribute.
+    fn template(&self) -> Template {
+        Template::current()
     }
 
-    /// How values are displayed in the diff output, the default is to wrap them in backticks
+    /// Describes how each field of the struct is compared and displayed, including ignored fields
+    ///
+    /// Use it to debug why a field did or didn't invalidate the cache, see [FieldExplanation].
+    /// Manual implementations return an empty list unless they override it.
+    fn explain(&self) -> &'static [FieldExplanation] {
+        &[]
+    }
+
+    /// What the cache holds i.e. `Ruby install`, used by [CacheDiff::restore_message] and
+    /// [CacheDiff::invalidate_message]
+    ///
+    /// When deriving, use the `#[cache_diff(description = "...")]` container attribute.
+    fn description(&self) -> Option<&str> {
+        None
+    }
+
+    /// The standard message for keeping the cache
+    ///
+    /// ```rust
+    /// use cache_diff::CacheDiff;
+    ///
+    /// #[derive(CacheDiff)]
+    /// #[cache_diff(description = "Ruby install")]
+    /// struct Metadata {
+    ///     version: String,
+    /// }
+    /// let now = Metadata { version: "3.4.0".to_string() };
+    /// let old = Metadata { version: "3.3.0".to_string() };
+    ///
+    /// assert_eq!(now.restore_message(), "Reusing cached Ruby install");
+    /// assert_eq!(
+    ///     now.invalidate_message(&now.diff(&old)),
+    ///     "Clearing cached Ruby install: version (`3.3.0` to `3.4.0`)"
+    /// );
+    /// ```
+    fn restore_message(&self) -> String {
+        match self.description() {
+            Some(description) => format!("Reusing cached {description}"),
+            None => "Reusing cache".to_string(),
+        }
+    }
+
+    /// The standard message for clearing the cache, with the differences from [CacheDiff::diff]
+    /// joined by `, `
+    ///
+    /// Without a [CacheDiff::description] the message is `Clearing cache: <differences>`.
+    fn invalidate_message(&self, diff: &[String]) -> String {
+        let cache = match self.description() {
+            Some(description) => format!("cached {description}"),
+            None => "cache".to_string(),
+        };
+        format!("Clearing {cache}: {}", diff.join(", "))
+    }
+
+    /// How values are displayed in the diff output, the default is to use [CacheDiff::style]
+    /// which wraps them in backticks
     ///
     /// Enable ANSI colors with `features = ["bullet_stream"]`
-    #[cfg(not(feature = "bullet_stream"))]
     fn fmt_value<T: std::fmt::Display>(&self, value: &T) -> String {
-        format!("`{value}`")
+        self.style().fmt_value(value)
+    }
+
+    /// How field names are displayed in the diff output, the default is to use [CacheDiff::style]
+    /// which leaves them unchanged
+    ///
+    /// Make names bold with `features = ["owo-colors"]` and `#[cache_diff(style = cache_diff::DiffStyle::OwoColors)]`
+    fn fmt_name(&self, name: &str) -> String {
+        self.style().fmt_name(name)
     }
 }
+#[cfg(feature = "derive")]
 pub use cache_diff_derive::CacheDiff;
@@ -0,0 +1,78 @@
+//! Decide whether to keep a cached [libcnb](https://github.com/heroku/libcnb.rs) layer from its metadata
+
+use crate::CacheDiff;
+use ::libcnb::layer::RestoredLayerAction;
+use std::path::Path;
+
+/// Keep the layer when the metadata is the same, otherwise delete it with the differences from [CacheDiff::diff]
+///
+/// The tuple is what libcnb expects from the `restored_layer_action` closure of a `CachedLayerDefinition`.
+/// When the layer is deleted, the differences are returned as the `cause` of
+/// `LayerState::Empty { cause: EmptyLayerCause::RestoredLayerAction { cause } }` so they can be shown to the user.
+///
+/// ```rust
+/// use cache_diff::CacheDiff;
+/// use libcnb::layer::RestoredLayerAction;
+///
+/// #[derive(CacheDiff)]
+/// struct Metadata {
+///     version: String,
+/// }
+/// let now = Metadata { version: "3.4.0".to_string() };
+///
+/// let (action, diff) = cache_diff::libcnb::restored_layer_action(&now, &Metadata { version: "3.3.0".to_string() });
+/// assert!(matches!(action, RestoredLayerAction::DeleteLayer));
+/// assert_eq!(diff, vec!["version (`3.3.0` to `3.4.0`)".to_string()]);
+///
+/// let (action, diff) = cache_diff::libcnb::restored_layer_action(&now, &Metadata { version: "3.4.0".to_string() });
+/// assert!(matches!(action, RestoredLayerAction::KeepLayer));
+/// assert!(diff.is_empty());
+/// ```
+pub fn restored_layer_action<M: CacheDiff>(now: &M, old: &M) -> (RestoredLayerAction, Vec<String>) {
+    let diff = now.diff(old);
+    if diff.is_empty() {
+        (RestoredLayerAction::KeepLayer, diff)
+    } else {
+        (RestoredLayerAction::DeleteLayer, diff)
+    }
+}
+
+/// Build the `restored_layer_action` closure of a `CachedLayerDefinition` from the current metadata
+///
+/// libcnb deserializes the metadata stored in the layer and passes it to the closure, which diffs it against
+/// `now` with [restored_layer_action]. Metadata that can no longer be deserialized is handled by the
+/// `invalid_metadata_action` instead:
+///
+/// ```text
+/// CachedLayerDefinition {
+///     build: true,
+///     launch: true,
+///     invalid_metadata_action: &|_| InvalidMetadataAction::DeleteLayer,
+///     restored_layer_action: &cache_diff::libcnb::invalidate_if_changed(&metadata),
+/// }
+/// ```
+///
+/// ```rust
+/// use cache_diff::CacheDiff;
+/// use libcnb::layer::RestoredLayerAction;
+/// use std::path::Path;
+///
+/// #[derive(CacheDiff)]
+/// struct Metadata {
+///     version: String,
+/// }
+/// let now = Metadata { version: "3.4.0".to_string() };
+/// let restored_layer_action = cache_diff::libcnb::invalidate_if_changed(&now);
+///
+/// let (action, diff) = restored_layer_action(
+///     &Metadata { version: "3.3.0".to_string() },
+///     Path::new("/layers/ruby"),
+/// );
+/// assert!(matches!(action, RestoredLayerAction::DeleteLayer));
+/// assert_eq!(diff, vec!["version (`3.3.0` to `3.4.0`)".to_string()]);
+/// ```
+pub fn invalidate_if_changed<M: CacheDiff>(
+    now: &M,
+) -> impl Fn(&M, &Path) -> (RestoredLayerAction, Vec<String>) + '_ {
+    move |old: &M, _: &Path| restored_layer_action(now, old)
+}
@@ -0,0 +1,30 @@
+//! Print differences with the [`libherokubuildpack`](https://github.com/heroku/libcnb.rs/tree/main/libherokubuildpack) log helpers
+
+use crate::Diff;
+use ::libherokubuildpack::log::{log_header, log_info};
+
+/// Write a header followed by each difference as a step with `libherokubuildpack::log`
+///
+/// ```rust,no_run
+/// use cache_diff::CacheDiff;
+///
+/// #[derive(CacheDiff)]
+/// struct Metadata {
+///     version: String,
+/// }
+/// let now = Metadata { version: "3.4.0".to_string() };
+///
+/// if let Some(diff) = now.changes(&Metadata { version: "3.3.0".to_string() }) {
+///     // Prints:
+///     //
+///     // [Clearing cache]
+///     // - version (`3.3.0` to `3.4.0`)
+///     cache_diff::log::log_diff("Clearing cache", &diff);
+/// }
+/// ```
+pub fn log_diff(header: impl AsRef<str>, diff: &Diff) {
+    log_header(header);
+    for line in diff.lines() {
+        log_info(format!("- {line}"));
+    }
+}
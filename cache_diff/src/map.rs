@@ -0,0 +1,149 @@
+//! Diff maps key by key
+
+use crate::env::MISSING;
+use crate::{CacheDiff, Difference, RedactionPolicy};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Display;
+use std::hash::Hash;
+
+/// A difference for one key, `None` when its value is the same in both maps
+fn key_difference<K: Display, V: Display + PartialEq>(
+    policy: Option<&RedactionPolicy>,
+    key: &K,
+    old: Option<&V>,
+    now: Option<&V>,
+) -> Option<Difference> {
+    if old == now {
+        return None;
+    }
+    let key = key.to_string();
+    let difference = match policy {
+        Some(policy) if policy.matches(&key) => Difference::changed(
+            key.clone(),
+            policy.placeholder.clone(),
+            policy.placeholder.clone(),
+        ),
+        _ => Difference::changed(
+            key.clone(),
+            old.map_or_else(|| MISSING.to_string(), ToString::to_string),
+            now.map_or_else(|| MISSING.to_string(), ToString::to_string),
+        ),
+    };
+    Some(difference.with_identifier(key))
+}
+
+/// Compares maps key by key, one difference per added, removed, or changed key in key order
+///
+/// A map field marked `#[cache_diff(nested)]` reports each key under the field instead of needing
+/// a `custom = <function>`:
+///
+/// ```rust
+/// use cache_diff::CacheDiff;
+/// use std::collections::BTreeMap;
+///
+/// #[derive(CacheDiff)]
+/// struct Metadata {
+///     #[cache_diff(nested)]
+///     env: BTreeMap<String, String>,
+/// }
+///
+/// let old = Metadata {
+///     env: BTreeMap::from([
+///         ("RUBY_YJIT_ENABLE".to_string(), "0".to_string()),
+///         ("LANG".to_string(), "C".to_string()),
+///     ]),
+/// };
+/// let now = Metadata {
+///     env: BTreeMap::from([
+///         ("LANG".to_string(), "C.UTF-8".to_string()),
+///         ("WEB_CONCURRENCY".to_string(), "5".to_string()),
+///     ]),
+/// };
+/// assert_eq!(
+///     now.diff(&old),
+///     vec![
+///         "LANG (`C` to `C.UTF-8`)",
+///         "RUBY_YJIT_ENABLE (`0` to `(not set)`)",
+///         "WEB_CONCURRENCY (`(not set)` to `5`)",
+///     ]
+/// );
+/// assert_eq!(now.diff_structured(&old)[0].path(), vec!["env", "LANG"]);
+/// ```
+///
+/// Values of keys matching the installed [RedactionPolicy](crate::RedactionPolicy) are hidden. For
+/// environment variables, [EnvSnapshot](crate::EnvSnapshot) also compares `PATH`-like values element-wise.
+impl<K, V> CacheDiff for BTreeMap<K, V>
+where
+    K: Display + Ord,
+    V: Display + PartialEq,
+{
+    fn diff(&self, old: &Self) -> Vec<String> {
+        crate::render_differences(self, &self.diff_structured(old))
+    }
+
+    fn diff_structured(&self, old: &Self) -> Vec<Difference> {
+        let mut keys = old.keys().chain(self.keys()).collect::<Vec<_>>();
+        keys.sort();
+        keys.dedup();
+        let policy = crate::redaction::installed();
+        keys.into_iter()
+            .filter_map(|key| key_difference(policy.as_ref(), key, old.get(key), self.get(key)))
+            .collect()
+    }
+
+    fn differs(&self, old: &Self) -> bool {
+        self != old
+    }
+}
+
+/// Compares maps key by key like `BTreeMap`, keys are sorted so the output doesn't depend on hashing
+impl<K, V, S> CacheDiff for HashMap<K, V, S>
+where
+    K: Display + Ord + Hash,
+    V: Display + PartialEq,
+    S: std::hash::BuildHasher,
+{
+    fn diff(&self, old: &Self) -> Vec<String> {
+        crate::render_differences(self, &self.diff_structured(old))
+    }
+
+    fn diff_structured(&self, old: &Self) -> Vec<Difference> {
+        let mut keys = old.keys().chain(self.keys()).collect::<Vec<_>>();
+        keys.sort();
+        keys.dedup();
+        let policy = crate::redaction::installed();
+        keys.into_iter()
+            .filter_map(|key| key_difference(policy.as_ref(), key, old.get(key), self.get(key)))
+            .collect()
+    }
+
+    fn differs(&self, old: &Self) -> bool {
+        self != old
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{DiffStyle, Template};
+
+    #[test]
+    fn test_hash_map_sorts_keys() {
+        let old = HashMap::from([("b", 1), ("c", 3)]);
+        let now = HashMap::from([("a", 1), ("b", 2), ("c", 3)]);
+        let backticks = |difference: &Difference| {
+            difference.render(&Template::default(), |value| {
+                DiffStyle::Backticks.fmt_value(&value)
+            })
+        };
+        assert_eq!(
+            now.diff_structured(&old)
+                .iter()
+                .map(backticks)
+                .collect::<Vec<_>>(),
+            vec!["a (`(not set)` to `1`)", "b (`1` to `2`)"]
+        );
+        assert!(now.differs(&old));
+        assert!(!now.differs(&now.clone()));
+    }
+}
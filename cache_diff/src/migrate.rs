@@ -0,0 +1,83 @@
+//! Diff against metadata written by an earlier version of the struct with [magic_migrate](https://github.com/heroku-buildpacks/magic_migrate)
+
+use crate::CacheDiff;
+use magic_migrate::TryMigrate;
+use std::fmt::{Debug, Display};
+
+/// Migrate stored TOML metadata to the current struct, then diff it against `now`
+///
+/// Metadata that matches any version of the struct in the migration chain is migrated forward before
+/// it's compared, so a schema change only invalidates the cache when the values differ. The differences
+/// are the same as [CacheDiff::diff].
+///
+/// ```rust
+/// use cache_diff::CacheDiff;
+/// use magic_migrate::TryMigrate;
+/// use serde::Deserialize;
+/// # extern crate toml_08 as toml;
+///
+/// #[derive(Debug, Deserialize, TryMigrate)]
+/// #[try_migrate(from = None)]
+/// #[serde(deny_unknown_fields)]
+/// struct MetadataV1 {
+///     ruby_version: String,
+/// }
+///
+/// #[derive(CacheDiff, Debug, Deserialize, TryMigrate)]
+/// #[try_migrate(from = MetadataV1)]
+/// #[serde(deny_unknown_fields)]
+/// struct MetadataV2 {
+///     version: String,
+/// }
+///
+/// impl From<MetadataV1> for MetadataV2 {
+///     fn from(v1: MetadataV1) -> Self {
+///         MetadataV2 { version: v1.ruby_version }
+///     }
+/// }
+///
+/// let now = MetadataV2 { version: "3.4.0".to_string() };
+/// assert_eq!(
+///     cache_diff::migrate::diff_after_migration(&now, r#"ruby_version = "3.3.0""#).unwrap(),
+///     vec!["version (`3.3.0` to `3.4.0`)".to_string()]
+/// );
+/// assert!(cache_diff::migrate::diff_after_migration(&now, r#"distro = "ubuntu""#).is_err());
+/// ```
+pub fn diff_after_migration<M, S>(
+    now: &M,
+    old_toml: S,
+) -> Result<Vec<String>, MigrateDiffError<<M as TryMigrate>::Error>>
+where
+    M: CacheDiff + TryMigrate,
+    S: AsRef<str>,
+{
+    match M::try_from_str_migrations(old_toml.as_ref()) {
+        Some(Ok(old)) => Ok(now.diff(&old)),
+        Some(Err(error)) => Err(MigrateDiffError::Migration(error)),
+        None => Err(MigrateDiffError::Unrecognized),
+    }
+}
+
+/// Reasons stored metadata could not be migrated to the current struct
+#[derive(Debug)]
+pub enum MigrateDiffError<E> {
+    /// The metadata does not deserialize into any version of the struct in the migration chain
+    Unrecognized,
+    /// The metadata matches an earlier version of the struct, but converting it to the current one failed
+    Migration(E),
+}
+
+impl<E: Display> Display for MigrateDiffError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MigrateDiffError::Unrecognized => {
+                write!(f, "Metadata does not match any known format")
+            }
+            MigrateDiffError::Migration(error) => {
+                write!(f, "Cannot migrate metadata to the current format: {error}")
+            }
+        }
+    }
+}
+
+impl<E: Debug + Display> std::error::Error for MigrateDiffError<E> {}
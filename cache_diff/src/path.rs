@@ -0,0 +1,50 @@
+//! Display paths the same way on every platform
+
+use std::path::Path;
+
+/// A path with forward slashes, relative to `base` when it is inside of it
+///
+/// Windows separators are replaced so diff output, and snapshot tests of it, are identical on Windows
+/// and Linux build environments:
+///
+/// ```rust
+/// use cache_diff::normalize_path;
+/// use std::path::Path;
+///
+/// assert_eq!(normalize_path(Path::new(r"C:\layers\ruby\bin"), None), "C:/layers/ruby/bin");
+/// assert_eq!(normalize_path(Path::new("/layers/ruby/bin"), Some("/layers")), "ruby/bin");
+/// assert_eq!(normalize_path(Path::new(r"C:\layers\ruby"), Some("C:/layers/")), "ruby");
+/// assert_eq!(normalize_path(Path::new("/layers"), Some("/layers")), ".");
+/// assert_eq!(normalize_path(Path::new("/app/bin"), Some("/layers")), "/app/bin");
+/// ```
+///
+/// When deriving, use `#[cache_diff(normalize_path)]` or `#[cache_diff(normalize_path = "<base>")]`
+/// on the field.
+pub fn normalize_path(path: &Path, base: Option<&str>) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let Some(base) = base.map(|base| base.replace('\\', "/")) else {
+        return path;
+    };
+    match path.strip_prefix(base.trim_end_matches('/')) {
+        Some("") => ".".to_string(),
+        Some(relative) if relative.starts_with('/') => relative.trim_start_matches('/').to_string(),
+        _ => path,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_base_is_a_whole_directory() {
+        assert_eq!(
+            normalize_path(Path::new("/layers_old/ruby"), Some("/layers")),
+            "/layers_old/ruby"
+        );
+        assert_eq!(
+            normalize_path(Path::new(r"\\server\share\ruby"), Some(r"\\server\share")),
+            "ruby"
+        );
+    }
+}
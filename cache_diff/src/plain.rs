@@ -0,0 +1,238 @@
+//! Output that is guaranteed to be free of ANSI escape codes regardless of enabled features
+
+use crate::{Diff, Difference, Renderer, Template};
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// Remove ANSI escape sequences (i.e. colors from the `bullet_stream` feature) from a string
+///
+/// ```rust
+/// assert_eq!(
+///     cache_diff::strip_ansi("version (\x1b[0;33m3.3.0\x1b[0m to \x1b[0;33m3.4.0\x1b[0m)"),
+///     "version (3.3.0 to 3.4.0)"
+/// );
+/// ```
+pub fn strip_ansi(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            output.push(c);
+            continue;
+        }
+        match chars.peek() {
+            // Control Sequence Introducer `ESC [` ends with a byte in the range `@` to `~`
+            Some('[') => {
+                chars.next();
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            // Operating System Command `ESC ]` ends with BEL or `ESC \`
+            Some(']') => {
+                chars.next();
+                while let Some(c) = chars.next() {
+                    if c == '\x07' {
+                        break;
+                    }
+                    if c == '\x1b' && chars.peek() == Some(&'\\') {
+                        chars.next();
+                        break;
+                    }
+                }
+            }
+            // Two character escape sequence
+            Some(_) => {
+                chars.next();
+            }
+            None => {}
+        }
+    }
+    output
+}
+
+/// Render differences one per line with values in backticks and without ANSI escape codes
+///
+/// The output of [CacheDiff::diff](crate::CacheDiff::diff) depends on enabled features and the
+/// [DiffStyle](crate::DiffStyle) and [Template] in use. This output does not, which makes it
+/// suitable for snapshot tests and log parsers:
+///
+/// ```rust
+/// use cache_diff::{CacheDiff, render_plain};
+///
+/// #[derive(CacheDiff)]
+/// struct Metadata {
+///     version: String,
+/// }
+/// let diff = Metadata { version: "3.4.0".to_string() }
+///     .diff_structured(&Metadata { version: "3.3.0".to_string() });
+///
+/// assert_eq!(render_plain(&diff), "- version (`3.3.0` to `3.4.0`)");
+/// ```
+pub fn render_plain(differences: &[Difference]) -> String {
+    plain_lines(differences, |value| format!("`{value}`"))
+}
+
+/// Render a [Diff] for snapshot tests, output only changes when the differences change
+///
+/// The [DiffStyle](crate::DiffStyle) and [Template] attached to the diff, any installed template,
+/// and enabled features are all ignored. Values are wrapped in backticks, ANSI escape codes are
+/// removed, line endings are normalized to `\n`, and the default template is used. Entries of maps
+/// and sets displayed with `Debug` i.e. `{"b": 2, "a": 1}` are sorted, so a `HashMap` or `HashSet`
+/// renders the same on every run. Differences keep their order, which follows the struct's field order:
+///
+/// ```rust
+/// use cache_diff::{CacheDiff, DiffStyle, render_stable};
+///
+/// #[derive(CacheDiff)]
+/// #[cache_diff(style = DiffStyle::Plain, template = "{name}: {old} => {new}")]
+/// struct Metadata {
+///     version: String,
+/// }
+/// let diff = Metadata { version: "3.4.0".to_string() }
+///     .changes(&Metadata { version: "3.3.0".to_string() })
+///     .unwrap();
+///
+/// assert_eq!(render_stable(&diff), "- version (`3.3.0` to `3.4.0`)");
+/// ```
+pub fn render_stable(diff: &Diff) -> String {
+    plain_lines(diff, |value| format!("`{}`", sort_entries(value))).replace("\r\n", "\n")
+}
+
+fn plain_lines<'a>(
+    differences: impl IntoIterator<Item = &'a Difference>,
+    fmt_value: impl Fn(&str) -> String,
+) -> String {
+    let template = Template::default();
+    Renderer::new().render(
+        differences
+            .into_iter()
+            .map(|difference| strip_ansi(&difference.render(&template, &fmt_value))),
+    )
+}
+
+/// Sort the entries between `{` and `}`, including nested ones, i.e. `{"b": 2, "a": 1}` becomes `{"a": 1, "b": 2}`
+///
+/// Entries are split on `, ` outside of quotes and brackets, the `Debug` output of maps and sets.
+/// Lists and tuples keep their order.
+fn sort_entries(value: &str) -> String {
+    let mut chars = value.chars().peekable();
+    let mut output = String::with_capacity(value.len());
+    while let Some(c) = chars.next() {
+        push_token(c, &mut chars, &mut output);
+    }
+    output
+}
+
+/// Copy `c`, or the whole quoted string or bracketed group it starts, into `output`
+fn push_token(c: char, chars: &mut Peekable<Chars<'_>>, output: &mut String) {
+    let close = match c {
+        '"' => {
+            output.push(c);
+            while let Some(c) = chars.next() {
+                output.push(c);
+                match c {
+                    '\\' => output.extend(chars.next()),
+                    '"' => break,
+                    _ => {}
+                }
+            }
+            return;
+        }
+        '{' => '}',
+        '[' => ']',
+        '(' => ')',
+        _ => {
+            output.push(c);
+            return;
+        }
+    };
+    let mut entries = vec![String::new()];
+    let mut closed = false;
+    while let Some(next) = chars.next() {
+        if next == close {
+            closed = true;
+            break;
+        }
+        if next == ',' && chars.peek() == Some(&' ') {
+            chars.next();
+            entries.push(String::new());
+            continue;
+        }
+        if let Some(entry) = entries.last_mut() {
+            push_token(next, chars, entry);
+        }
+    }
+    if c == '{' && closed {
+        entries.sort();
+    }
+    output.push(c);
+    output.push_str(&entries.join(", "));
+    if closed {
+        output.push(close);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_strip_ansi() {
+        assert_eq!(strip_ansi("plain"), "plain");
+        assert_eq!(strip_ansi("\x1b[1;36mbold\x1b[0m"), "bold");
+        assert_eq!(
+            strip_ansi("\x1b]8;;https://example.com\x1b\\link\x1b]8;;\x1b\\"),
+            "link"
+        );
+        assert_eq!(strip_ansi("trailing\x1b"), "trailing");
+    }
+
+    #[test]
+    fn test_sort_entries() {
+        assert_eq!(sort_entries(r#"{"b": 2, "a": 1}"#), r#"{"a": 1, "b": 2}"#);
+        assert_eq!(
+            sort_entries(r#"{"z": {"b", "a"}, "y": ["b", "a"]}"#),
+            r#"{"y": ["b", "a"], "z": {"a", "b"}}"#
+        );
+        assert_eq!(sort_entries(r#"{"b, a", "a"}"#), r#"{"a", "b, a"}"#);
+        assert_eq!(sort_entries("3.4.0"), "3.4.0");
+        assert_eq!(sort_entries("{unclosed, a"), "{unclosed, a");
+    }
+
+    #[test]
+    fn test_render_stable_sorts_hash_map() {
+        let old = (0..20)
+            .map(|i| (format!("gem_{i}"), i))
+            .collect::<std::collections::HashMap<_, _>>();
+        let mut now = old.clone();
+        now.insert("gem_0".to_string(), 100);
+        let mut keys = now.keys().collect::<Vec<_>>();
+        keys.sort();
+        let sorted = |map: &std::collections::HashMap<String, i32>| {
+            let entries = keys
+                .iter()
+                .map(|key| format!("{key:?}: {}", map[*key]))
+                .collect::<Vec<_>>();
+            format!("{{{}}}", entries.join(", "))
+        };
+
+        let diff = Diff::from(vec![Difference::changed(
+            "gems",
+            format!("{old:?}"),
+            format!("{now:?}"),
+        )]);
+        assert_eq!(
+            render_stable(&diff),
+            format!("- gems (`{}` to `{}`)", sorted(&old), sorted(&now))
+        );
+    }
+
+    #[test]
+    fn test_render_plain_strips_messages() {
+        let diff = vec![Difference::message("\x1b[0;33mCache\x1b[0m expired")];
+        assert_eq!(render_plain(&diff), "- Cache expired");
+    }
+}
@@ -0,0 +1,110 @@
+//! Decide whether a set of differences is worth invalidating the cache for
+
+use crate::Difference;
+use std::collections::BTreeSet;
+
+/// Recommends invalidation only for significant differences
+///
+/// Every difference is significant by default. Fields marked [InvalidationPolicy::minor] are
+/// informational, they only invalidate the cache when there are at least
+/// [InvalidationPolicy::threshold] differences in total. Fields are matched by the difference's
+/// code, which defaults to the field's Rust identifier, or by its name. Messages from
+/// `custom = <function>` have no code and are always significant unless their text is listed.
+///
+/// ```rust
+/// use cache_diff::{CacheDiff, InvalidationPolicy};
+///
+/// #[derive(CacheDiff, Clone)]
+/// struct Metadata {
+///     version: String,
+///     changed_by: String,
+///     build_host: String,
+/// }
+/// let old = Metadata { version: "3.4.0".to_string(), changed_by: "CI".to_string(), build_host: "a".to_string() };
+/// let policy = InvalidationPolicy::new()
+///     .minor(["changed_by", "build_host"])
+///     .threshold(2);
+///
+/// let now = Metadata { changed_by: "Schneems".to_string(), ..old.clone() };
+/// assert!(!policy.should_invalidate(&now.diff_structured(&old)));
+///
+/// let now = Metadata { changed_by: "Schneems".to_string(), build_host: "b".to_string(), ..old.clone() };
+/// assert!(policy.should_invalidate(&now.diff_structured(&old)));
+///
+/// let now = Metadata { version: "3.4.1".to_string(), ..old.clone() };
+/// assert!(policy.should_invalidate(&now.diff_structured(&old)));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InvalidationPolicy {
+    minor: BTreeSet<String>,
+    threshold: Option<usize>,
+}
+
+impl InvalidationPolicy {
+    /// A policy where every difference invalidates the cache
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Mark fields as informational, they do not invalidate the cache by themselves
+    pub fn minor<S: Into<String>>(mut self, fields: impl IntoIterator<Item = S>) -> Self {
+        self.minor.extend(fields.into_iter().map(Into::into));
+        self
+    }
+
+    /// Invalidate when there are at least this many differences, including minor ones
+    ///
+    /// Without a threshold, minor differences never invalidate the cache.
+    pub fn threshold(mut self, count: usize) -> Self {
+        self.threshold = Some(count);
+        self
+    }
+
+    /// Returns `true` when the difference is informational
+    pub fn is_minor(&self, difference: &Difference) -> bool {
+        let listed = |key: &str| self.minor.contains(key);
+        match (difference.code(), difference.name()) {
+            (None, None) => listed(&difference.to_string()),
+            (code, name) => code.is_some_and(listed) || name.is_some_and(listed),
+        }
+    }
+
+    /// Returns `true` when the differences are significant enough to invalidate the cache
+    pub fn should_invalidate(&self, differences: &[Difference]) -> bool {
+        differences
+            .iter()
+            .any(|difference| !self.is_minor(difference))
+            || self
+                .threshold
+                .is_some_and(|threshold| threshold > 0 && differences.len() >= threshold)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_default_invalidates_on_any_difference() {
+        let policy = InvalidationPolicy::new();
+        assert!(!policy.should_invalidate(&[]));
+        assert!(policy.should_invalidate(&[Difference::message("Lockfile changed")]));
+    }
+
+    #[test]
+    fn test_minor_by_code_name_or_message() {
+        let policy = InvalidationPolicy::new().minor(["changed_by", "Build host", "Clock skew"]);
+        let differences = [
+            Difference::changed("Changed by", "CI", "Schneems").with_code("changed_by"),
+            Difference::changed("Build host", "a", "b").with_code("build_host"),
+            Difference::message("Clock skew"),
+        ];
+
+        assert!(differences
+            .iter()
+            .all(|difference| policy.is_minor(difference)));
+        assert!(!policy.should_invalidate(&differences));
+        assert!(policy.clone().threshold(3).should_invalidate(&differences));
+        assert!(!policy.threshold(4).should_invalidate(&differences));
+    }
+}
@@ -0,0 +1,146 @@
+//! Generate pairs of metadata with known changed fields for [proptest](https://github.com/proptest-rs/proptest)
+
+use crate::CacheDiff;
+use ::proptest::collection::vec;
+use ::proptest::prelude::{any, Strategy};
+use std::fmt::Debug;
+
+/// An old value and a copy with some of its fields changed by [mutated_pair] or [mutated_pair_with]
+#[derive(Debug, Clone)]
+pub struct MutatedPair<T> {
+    pub old: T,
+    pub now: T,
+    /// Identifiers of the changed fields in the order of [CacheDiff::FIELDS]
+    pub mutated: Vec<&'static str>,
+}
+
+impl<T: CacheDiff> MutatedPair<T> {
+    /// Identifiers of the fields that `now.diff_structured(&old)` reports in the order they're reported
+    ///
+    /// Differences without a [Difference::identifier](crate::Difference::identifier), such as messages
+    /// from the struct's `custom = <function>`, are left out.
+    pub fn diffed(&self) -> Vec<String> {
+        self.now
+            .diff_structured(&self.old)
+            .iter()
+            .filter_map(|difference| difference.identifier().map(str::to_string))
+            .collect()
+    }
+
+    /// Panics unless the diff reports exactly the mutated fields
+    pub fn assert_exact(&self) {
+        assert_eq!(
+            self.diffed(),
+            self.mutated,
+            "Expected only the mutated fields to be reported"
+        );
+    }
+}
+
+/// Generate values from `values` along with a copy that has a random set of its fields changed
+///
+/// Requires `#[cache_diff(sample_changed)]` on the struct. Each field is changed with
+/// [CacheDiff::sample_changed], a field is not selected when its sample is equal to the generated value,
+/// for example a field that is already its [Default]. Use it to check that exactly the changed fields
+/// invalidate the cache for any value:
+///
+/// ```rust
+/// use cache_diff::proptest::mutated_pair;
+/// use cache_diff::CacheDiff;
+/// use proptest::prelude::Strategy;
+/// use proptest::test_runner::TestRunner;
+///
+/// #[derive(Clone, Debug, CacheDiff)]
+/// #[cache_diff(sample_changed)]
+/// struct Metadata {
+///     version: String,
+///     distro: String,
+/// }
+///
+/// let values = ("[0-9.]{0,5}", "[a-z]{0,8}")
+///     .prop_map(|(version, distro)| Metadata { version, distro });
+///
+/// TestRunner::default()
+///     .run(&mutated_pair(values), |pair| {
+///         pair.assert_exact();
+///         Ok(())
+///     })
+///     .unwrap();
+/// ```
+pub fn mutated_pair<T>(values: impl Strategy<Value = T>) -> impl Strategy<Value = MutatedPair<T>>
+where
+    T: CacheDiff + Clone + Debug,
+{
+    mutated_pair_with(values, |value: &T, identifier| {
+        value.sample_changed(identifier)
+    })
+}
+
+/// Like [mutated_pair] with a function that changes a field, for structs without `#[cache_diff(sample_changed)]`
+///
+/// For each selected field, `mutate` is called with the value and the field's identifier from
+/// [CacheDiff::FIELDS] and returns a copy with that field changed, or `None` to leave it alone. A field
+/// is not selected when its change is equal to the generated value. Use it to check that exactly the
+/// changed fields invalidate the cache for any value:
+///
+/// ```rust
+/// use cache_diff::proptest::mutated_pair_with;
+/// use cache_diff::CacheDiff;
+/// use proptest::prelude::Strategy;
+/// use proptest::test_runner::TestRunner;
+///
+/// #[derive(Clone, Debug, CacheDiff)]
+/// struct Metadata {
+///     version: String,
+///     distro: String,
+/// }
+///
+/// let values = ("[0-9.]{0,5}", "[a-z]{0,8}")
+///     .prop_map(|(version, distro)| Metadata { version, distro });
+/// let pairs = mutated_pair_with(values, |metadata: &Metadata, identifier| {
+///     let mut changed = metadata.clone();
+///     match identifier {
+///         "version" => changed.version.push('1'),
+///         "distro" => changed.distro.push('x'),
+///         _ => return None,
+///     }
+///     Some(changed)
+/// });
+///
+/// TestRunner::default()
+///     .run(&pairs, |pair| {
+///         pair.assert_exact();
+///         Ok(())
+///     })
+///     .unwrap();
+/// ```
+pub fn mutated_pair_with<T, F>(
+    values: impl Strategy<Value = T>,
+    mutate: F,
+) -> impl Strategy<Value = MutatedPair<T>>
+where
+    T: CacheDiff + Clone + Debug,
+    F: Fn(&T, &str) -> Option<T>,
+{
+    (values, vec(any::<bool>(), T::FIELDS.len())).prop_map(move |(old, selected)| {
+        let mut now = old.clone();
+        let mut mutated = Vec::new();
+        for (field, _) in T::FIELDS
+            .iter()
+            .zip(selected)
+            .filter(|(_, selected)| *selected)
+        {
+            let Some(changed) = mutate(&old, field.identifier) else {
+                continue;
+            };
+            if !changed.differs(&old) {
+                continue;
+            }
+            if let Some(changed) = mutate(&now, field.identifier) {
+                now = changed;
+                mutated.push(field.identifier);
+            }
+        }
+        MutatedPair { old, now, mutated }
+    })
+}
@@ -0,0 +1,137 @@
+//! Process-wide masking of sensitive values in diff output
+
+use std::sync::{PoisonError, RwLock};
+
+static POLICY: RwLock<Option<RedactionPolicy>> = RwLock::new(None);
+
+/// Centralized configuration for hiding sensitive values in the derived diff output
+///
+/// Any field whose name matches one of the patterns will have both of its values
+/// replaced by the placeholder. Patterns are compared case insensitively against both the
+/// field identifier (i.e. `api_token`) and the display name (i.e. `API token` when renamed).
+/// A `*` in a pattern matches zero or more characters.
+///
+/// ```rust
+/// use cache_diff::{CacheDiff, RedactionPolicy};
+///
+/// #[derive(CacheDiff)]
+/// struct Metadata {
+///     version: String,
+///     api_token: String,
+/// }
+///
+/// RedactionPolicy::new()
+///     .placeholder("<hidden>")
+///     .pattern("*token*")
+///     .install();
+///
+/// let now = Metadata { version: "3.4.0".to_string(), api_token: "secret".to_string() };
+/// let diff = now.diff(&Metadata { version: "3.4.0".to_string(), api_token: "hunter2".to_string() });
+///
+/// assert_eq!(diff.join(" "), "api token (`<hidden>` to `<hidden>`)");
+/// ```
+///
+/// Changes are still detected for redacted fields, only the rendered values are hidden.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RedactionPolicy {
+    pub(crate) placeholder: String,
+    patterns: Vec<String>,
+}
+
+impl Default for RedactionPolicy {
+    fn default() -> Self {
+        Self {
+            placeholder: "[REDACTED]".to_string(),
+            patterns: Vec::new(),
+        }
+    }
+}
+
+impl RedactionPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Text shown in place of a redacted value, defaults to `[REDACTED]`
+    pub fn placeholder(mut self, placeholder: impl Into<String>) -> Self {
+        self.placeholder = placeholder.into();
+        self
+    }
+
+    /// Add a field name pattern that should always be redacted i.e. `password` or `*_token`
+    pub fn pattern(mut self, pattern: impl Into<String>) -> Self {
+        self.patterns.push(pattern.into());
+        self
+    }
+
+    /// Make this the policy used by all derived diffs in the current process
+    pub fn install(self) {
+        *POLICY.write().unwrap_or_else(PoisonError::into_inner) = Some(self);
+    }
+
+    /// Remove any installed policy, values are no longer redacted
+    pub fn reset() {
+        *POLICY.write().unwrap_or_else(PoisonError::into_inner) = None;
+    }
+
+    /// Returns true if a field with the given name should be redacted
+    pub fn matches(&self, name: &str) -> bool {
+        self.patterns
+            .iter()
+            .any(|pattern| wildcard_match(&pattern.to_lowercase(), &name.to_lowercase()))
+    }
+
+    /// Returns true if a field should be redacted by either its identifier or display name
+    pub(crate) fn redacts(&self, identifier: &str, name: &str) -> bool {
+        self.matches(identifier) || self.matches(name)
+    }
+}
+
+/// A copy of the installed policy, so a whole diff is rendered without taking the lock per field
+pub(crate) fn installed() -> Option<RedactionPolicy> {
+    POLICY
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+}
+
+/// Glob style matching where `*` matches zero or more characters
+fn wildcard_match(pattern: &str, value: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == value,
+        Some((prefix, rest)) => {
+            let Some(remaining) = value.strip_prefix(prefix) else {
+                return false;
+            };
+            remaining
+                .char_indices()
+                .map(|(index, _)| index)
+                .chain(std::iter::once(remaining.len()))
+                .any(|index| wildcard_match(rest, &remaining[index..]))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_wildcard_match() {
+        assert!(wildcard_match("password", "password"));
+        assert!(!wildcard_match("password", "password_hint"));
+        assert!(wildcard_match("*token*", "api_token_value"));
+        assert!(wildcard_match("*_token", "api_token"));
+        assert!(!wildcard_match("*_token", "api_token_value"));
+        assert!(wildcard_match("*", ""));
+        assert!(wildcard_match("a*c*e", "abcde"));
+    }
+
+    #[test]
+    fn test_matches_case_insensitive() {
+        let policy = RedactionPolicy::new().pattern("*Token");
+        assert!(policy.matches("API token"));
+        assert!(policy.matches("api_token"));
+        assert!(!policy.matches("version"));
+    }
+}
@@ -0,0 +1,244 @@
+//! Turn a list of differences into text for display to the user
+
+use crate::Difference;
+use std::fmt::Display;
+
+/// Renders a list of differences into a single string
+///
+/// Each difference is placed on its own line as a bullet point. Optionally the output
+/// can be prefixed with a summary header containing the number of differences:
+///
+/// ```rust
+/// use cache_diff::{CacheDiff, Renderer};
+///
+/// #[derive(CacheDiff)]
+/// struct Metadata {
+///     version: String,
+/// }
+/// let diff = Metadata { version: "3.4.0".to_string() }
+///     .diff(&Metadata { version: "3.3.0".to_string() });
+///
+/// assert_eq!(
+///     Renderer::new().summary(true).render(&diff),
+///     "1 change detected:\n- version (`3.3.0` to `3.4.0`)"
+/// );
+/// ```
+///
+/// When there are no differences, an empty string is returned.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Renderer {
+    summary: bool,
+}
+
+impl Renderer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Prefix output with `N change(s) detected:`
+    pub fn summary(mut self, summary: bool) -> Self {
+        self.summary = summary;
+        self
+    }
+
+    pub fn render<I>(&self, differences: I) -> String
+    where
+        I: IntoIterator,
+        I::Item: Display,
+    {
+        let lines = differences
+            .into_iter()
+            .map(|difference| format!("- {difference}"))
+            .collect::<Vec<String>>();
+
+        if lines.is_empty() {
+            String::new()
+        } else if self.summary {
+            format!("{}:\n{}", summary(lines.len()), lines.join("\n"))
+        } else {
+            lines.join("\n")
+        }
+    }
+
+    /// Render differences grouped under their category as a subheading
+    ///
+    /// Differences without a category are listed first, followed by each category in the order it
+    /// first appears:
+    ///
+    /// ```rust
+    /// use cache_diff::{CacheDiff, Renderer};
+    ///
+    /// #[derive(CacheDiff)]
+    /// struct Metadata {
+    ///     #[cache_diff(category = "Runtime")]
+    ///     version: String,
+    ///     #[cache_diff(category = "Operating system")]
+    ///     distro: String,
+    /// }
+    /// let now = Metadata { version: "3.4.0".to_string(), distro: "Ubuntu".to_string() };
+    /// let diff = now.diff_structured(&Metadata { version: "3.3.0".to_string(), distro: "Alpine".to_string() });
+    ///
+    /// assert_eq!(
+    ///     Renderer::new().render_grouped(&diff),
+    ///     "Runtime:\n- version (`3.3.0` to `3.4.0`)\nOperating system:\n- distro (`Alpine` to `Ubuntu`)"
+    /// );
+    /// ```
+    pub fn render_grouped(&self, differences: &[Difference]) -> String {
+        let mut categories: Vec<Option<&str>> = vec![None];
+        for difference in differences {
+            if !categories.contains(&difference.category()) {
+                categories.push(difference.category());
+            }
+        }
+
+        let mut lines = Vec::new();
+        for category in categories {
+            let mut group = differences
+                .iter()
+                .filter(|difference| difference.category() == category)
+                .map(|difference| format!("- {difference}"))
+                .peekable();
+
+            if group.peek().is_some() {
+                if let Some(category) = category {
+                    lines.push(format!("{category}:"));
+                }
+                lines.extend(group);
+            }
+        }
+
+        if lines.is_empty() {
+            String::new()
+        } else if self.summary {
+            format!("{}:\n{}", summary(differences.len()), lines.join("\n"))
+        } else {
+            lines.join("\n")
+        }
+    }
+}
+
+/// Render differences as GitHub Actions workflow commands
+///
+/// Each difference becomes a `::notice` annotation so cache invalidation reasons are shown
+/// in the Actions UI. Values are not styled:
+///
+/// ```rust
+/// use cache_diff::{CacheDiff, github_annotations};
+///
+/// #[derive(CacheDiff)]
+/// struct Metadata {
+///     version: String,
+/// }
+/// let diff = Metadata { version: "3.4.0".to_string() }
+///     .diff_structured(&Metadata { version: "3.3.0".to_string() });
+///
+/// assert_eq!(
+///     github_annotations(&diff),
+///     "::notice title=Cache invalidated::version changed from 3.3.0 to 3.4.0"
+/// );
+/// ```
+pub fn github_annotations(differences: &[Difference]) -> String {
+    differences
+        .iter()
+        .map(|difference| {
+            let message = match (difference.name(), difference.old(), difference.now()) {
+                (Some(name), Some(old), Some(now)) => {
+                    format!("{name} changed from {old} to {now}")
+                }
+                _ => difference.render(&crate::Template::default(), |value| value.to_string()),
+            };
+            format!(
+                "::notice title=Cache invalidated::{}",
+                escape_annotation_data(&message)
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Escape characters with special meaning in a workflow command message
+fn escape_annotation_data(data: &str) -> String {
+    data.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Pluralized count of changes i.e. `1 change detected` or `2 changes detected`
+fn summary(count: usize) -> String {
+    if count == 1 {
+        format!("{count} change detected")
+    } else {
+        format!("{count} changes detected")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_render_empty() {
+        let empty: Vec<String> = Vec::new();
+        assert_eq!(Renderer::new().render(&empty), "");
+        assert_eq!(Renderer::new().summary(true).render(&empty), "");
+    }
+
+    #[test]
+    fn test_render_summary_pluralization() {
+        let diff = vec!["one".to_string(), "two".to_string()];
+        assert_eq!(
+            Renderer::new().summary(true).render(&diff),
+            "2 changes detected:\n- one\n- two"
+        );
+        assert_eq!(
+            Renderer::new().summary(true).render(&diff[..1]),
+            "1 change detected:\n- one"
+        );
+    }
+
+    #[test]
+    fn test_render_grouped() {
+        let diff = vec![
+            Difference::changed("version", "3.3.0", "3.4.0").with_category("Runtime"),
+            Difference::changed("distro", "Alpine", "Ubuntu").with_category("Operating system"),
+            Difference::message("Cache count exceeded"),
+            Difference::changed("arch", "amd64", "arm64").with_category("Runtime"),
+        ];
+        // Values are styled with the default `DiffStyle`, which colors them with `bullet_stream`
+        assert_eq!(
+            crate::strip_ansi(&Renderer::new().summary(true).render_grouped(&diff)),
+            [
+                "4 changes detected:",
+                "- Cache count exceeded",
+                "Runtime:",
+                "- version (`3.3.0` to `3.4.0`)",
+                "- arch (`amd64` to `arm64`)",
+                "Operating system:",
+                "- distro (`Alpine` to `Ubuntu`)",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_github_annotations_escaped() {
+        let diff = vec![
+            Difference::changed("path", "50%", "100%"),
+            Difference::message("first\nsecond"),
+        ];
+        assert_eq!(
+            github_annotations(&diff),
+            [
+                "::notice title=Cache invalidated::path changed from 50%25 to 100%25",
+                "::notice title=Cache invalidated::first%0Asecond",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_render_without_summary() {
+        let diff = vec!["one".to_string(), "two".to_string()];
+        assert_eq!(Renderer::new().render(&diff), "- one\n- two");
+    }
+}
@@ -0,0 +1,132 @@
+//! Combine the diffs of several layers into one report
+
+use crate::{CacheDiff, Diff};
+use std::fmt::Display;
+
+/// The differences of several layers' metadata keyed by layer name
+///
+/// Layers are kept in the order they are added. It displays as a single summary line naming the
+/// invalidated layers, followed by each invalidated layer's differences:
+///
+/// ```rust
+/// use cache_diff::{CacheDiff, LayerDiffReport};
+///
+/// #[derive(CacheDiff)]
+/// struct Metadata {
+///     version: String,
+/// }
+/// let metadata = |version: &str| Metadata { version: version.to_string() };
+///
+/// let mut report = LayerDiffReport::new();
+/// report.compare("ruby", &metadata("3.4.0"), &metadata("3.3.0"));
+/// report.compare("node", &metadata("22.0.0"), &metadata("22.0.0"));
+/// report.compare("bundler", &metadata("2.6.0"), &metadata("2.5.0"));
+///
+/// assert_eq!(report.invalidated().collect::<Vec<_>>(), vec!["ruby", "bundler"]);
+/// assert_eq!(
+///     report.to_string(),
+///     "2 layers invalidated: ruby, bundler\nruby:\n- version (`3.3.0` to `3.4.0`)\nbundler:\n- version (`2.5.0` to `2.6.0`)"
+/// );
+/// ```
+///
+/// When no layer is invalidated it displays as an empty string.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LayerDiffReport {
+    layers: Vec<(String, Option<Diff>)>,
+}
+
+impl LayerDiffReport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a layer's result from [CacheDiff::changes], `None` means the cache is kept
+    pub fn add(&mut self, layer: impl Into<String>, changes: Option<Diff>) -> &mut Self {
+        self.layers.push((layer.into(), changes));
+        self
+    }
+
+    /// Diff a layer's current metadata against the old and record the result
+    pub fn compare<T: CacheDiff>(
+        &mut self,
+        layer: impl Into<String>,
+        now: &T,
+        old: &T,
+    ) -> &mut Self {
+        self.add(layer, now.changes(old))
+    }
+
+    /// Names of the layers that should be invalidated
+    pub fn invalidated(&self) -> impl Iterator<Item = &str> {
+        self.changes().map(|(layer, _)| layer)
+    }
+
+    /// Names of the layers whose cache is kept
+    pub fn kept(&self) -> impl Iterator<Item = &str> {
+        self.layers
+            .iter()
+            .filter(|(_, diff)| diff.is_none())
+            .map(|(layer, _)| layer.as_str())
+    }
+
+    /// Each invalidated layer's name and differences
+    pub fn changes(&self) -> impl Iterator<Item = (&str, &Diff)> {
+        self.layers
+            .iter()
+            .filter_map(|(layer, diff)| diff.as_ref().map(|diff| (layer.as_str(), diff)))
+    }
+
+    /// Returns `true` when no layer is invalidated
+    pub fn is_empty(&self) -> bool {
+        self.changes().next().is_none()
+    }
+}
+
+impl Display for LayerDiffReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let invalidated = self.invalidated().collect::<Vec<&str>>();
+        match invalidated.len() {
+            0 => return Ok(()),
+            1 => write!(f, "1 layer invalidated: ")?,
+            count => write!(f, "{count} layers invalidated: ")?,
+        }
+        f.write_str(&invalidated.join(", "))?;
+        for (layer, diff) in self.changes() {
+            write!(f, "\n{layer}:\n{diff}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Difference;
+
+    #[test]
+    fn test_empty_and_kept() {
+        let mut report = LayerDiffReport::new();
+        assert!(report.is_empty());
+        assert_eq!(report.to_string(), "");
+
+        report.add("ruby", None);
+        assert!(report.is_empty());
+        assert_eq!(report.to_string(), "");
+        assert_eq!(report.kept().collect::<Vec<_>>(), vec!["ruby"]);
+    }
+
+    #[test]
+    fn test_single_layer() {
+        let mut report = LayerDiffReport::new();
+        report.add("node", None).add(
+            "ruby",
+            Some(Diff::from(vec![Difference::message("Lockfile changed")])),
+        );
+
+        assert!(!report.is_empty());
+        assert_eq!(
+            report.to_string(),
+            "1 layer invalidated: ruby\nruby:\n- Lockfile changed"
+        );
+    }
+}
@@ -0,0 +1,107 @@
+//! Compare version strings only up to a semver boundary
+
+/// The most specific part of a version that invalidates the cache when it changes
+///
+/// Versions are compared by their numeric `major.minor.patch` parts, missing parts count as `0`. A
+/// leading `v` and any build suffix i.e. `+build.5` are ignored. A pre-release suffix i.e. `-rc1` is
+/// a different version at [SemverPart::Patch] and ignored otherwise. Versions that aren't numeric
+/// are compared as strings:
+///
+/// ```rust
+/// use cache_diff::SemverPart;
+///
+/// assert!(SemverPart::Major.same("18.1.0", "v18.20.4"));
+/// assert!(!SemverPart::Major.same("18.20.4", "20.0.0"));
+/// assert!(SemverPart::Minor.same("3.4.0", "3.4.1"));
+/// assert!(SemverPart::Patch.same("3.4.1", "v3.4.1+build.5"));
+/// assert!(!SemverPart::Patch.same("3.4.1-rc1", "3.4.1"));
+/// assert!(SemverPart::Minor.same("3.4.1-rc1", "3.4.1"));
+/// assert!(!SemverPart::Patch.same("latest", "3.4.1"));
+/// ```
+///
+/// When deriving, use `#[cache_diff(semver = "major")]`, `"minor"` or `"patch"` on a field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SemverPart {
+    /// Only a different major version differs i.e. `18.x` to `20.x`
+    Major,
+    /// A different major or minor version differs i.e. `3.3.x` to `3.4.x`
+    Minor,
+    /// Any different numeric version differs i.e. `3.4.0` to `3.4.1`
+    Patch,
+}
+
+impl SemverPart {
+    /// Returns `true` when the versions are the same up to and including this part
+    pub fn same(self, old: &str, now: &str) -> bool {
+        let len = match self {
+            SemverPart::Major => 1,
+            SemverPart::Minor => 2,
+            SemverPart::Patch => 3,
+        };
+        match (numeric_parts(old), numeric_parts(now)) {
+            (Some((old, old_pre)), Some((now, now_pre))) => {
+                old[..len] == now[..len] && (self != SemverPart::Patch || old_pre == now_pre)
+            }
+            _ => old == now,
+        }
+    }
+}
+
+/// The `[major, minor, patch]` and pre-release of a version i.e. `v3.4.1-rc1` is `([3, 4, 1], "rc1")`
+fn numeric_parts(version: &str) -> Option<([u64; 3], &str)> {
+    let version = version.trim();
+    let version = version.strip_prefix(['v', 'V']).unwrap_or(version);
+    let version = version.split('+').next()?;
+    let (numbers, pre) = version.split_once('-').unwrap_or((version, ""));
+    let mut parts = [0; 3];
+    for (index, part) in numbers.split('.').enumerate() {
+        *parts.get_mut(index)? = part.parse().ok()?;
+    }
+    Some((parts, pre))
+}
+
+/// Used by generated code for `semver = "major"`
+#[doc(hidden)]
+pub fn same_semver_major<T: AsRef<str> + ?Sized>(old: &T, now: &T) -> bool {
+    SemverPart::Major.same(old.as_ref(), now.as_ref())
+}
+
+/// Used by generated code for `semver = "minor"`
+#[doc(hidden)]
+pub fn same_semver_minor<T: AsRef<str> + ?Sized>(old: &T, now: &T) -> bool {
+    SemverPart::Minor.same(old.as_ref(), now.as_ref())
+}
+
+/// Used by generated code for `semver = "patch"`
+#[doc(hidden)]
+pub fn same_semver_patch<T: AsRef<str> + ?Sized>(old: &T, now: &T) -> bool {
+    SemverPart::Patch.same(old.as_ref(), now.as_ref())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_numeric_parts() {
+        assert_eq!(numeric_parts("3"), Some(([3, 0, 0], "")));
+        assert_eq!(numeric_parts(" v3.4 "), Some(([3, 4, 0], "")));
+        assert_eq!(numeric_parts("3.4.1+build.5"), Some(([3, 4, 1], "")));
+        assert_eq!(
+            numeric_parts("3.4.1-rc.1+build-5"),
+            Some(([3, 4, 1], "rc.1"))
+        );
+        assert_eq!(numeric_parts("3.4.1.2"), None);
+        assert_eq!(numeric_parts("3.x"), None);
+        assert_eq!(numeric_parts(""), None);
+    }
+
+    #[test]
+    fn test_pre_release_differs_at_patch() {
+        assert!(!SemverPart::Patch.same("3.4.1", "3.4.1-rc1"));
+        assert!(!SemverPart::Patch.same("3.4.1-rc1", "3.4.1-rc2"));
+        assert!(SemverPart::Patch.same("3.4.1-rc1", "v3.4.1-rc1+build.5"));
+        assert!(SemverPart::Minor.same("3.4.0-rc1", "3.4.1"));
+        assert!(SemverPart::Major.same("18.0.0-rc1", "18.20.4"));
+    }
+}
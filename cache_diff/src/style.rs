@@ -0,0 +1,85 @@
+//! Controls how values are displayed in the diff output
+
+use std::fmt::Display;
+
+/// How values are displayed in the diff output
+///
+/// The default wraps values in backticks, or styles them with ANSI colors when the `bullet_stream`
+/// feature is enabled. Field names are only styled by [DiffStyle::OwoColors]. A single struct can opt into a different style by overriding
+/// [CacheDiff::style](crate::CacheDiff::style) without affecting other structs:
+///
+/// ```rust
+/// use cache_diff::DiffStyle;
+///
+/// assert_eq!(DiffStyle::Plain.fmt_value(&"3.4.0"), "3.4.0");
+/// assert_eq!(DiffStyle::Backticks.fmt_value(&"3.4.0"), "`3.4.0`");
+/// ```
+///
+/// Variants depend on enabled features, so a `match` on it needs a wildcard arm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DiffStyle {
+    /// Wrap values in backticks i.e. `` `3.4.0` ``
+    Backticks,
+    /// Display values as is i.e. `3.4.0`
+    Plain,
+    /// Color values yellow and make names bold via `owo-colors`, without needing `bullet_stream`
+    #[cfg(feature = "owo-colors")]
+    OwoColors,
+    /// Style values with ANSI colors via `bullet_stream::style::value`
+    #[cfg(feature = "bullet_stream")]
+    BulletStream,
+}
+
+impl Default for DiffStyle {
+    #[cfg(feature = "bullet_stream")]
+    fn default() -> Self {
+        DiffStyle::BulletStream
+    }
+
+    #[cfg(not(feature = "bullet_stream"))]
+    fn default() -> Self {
+        DiffStyle::Backticks
+    }
+}
+
+impl DiffStyle {
+    /// Display a value of a changed field
+    pub fn fmt_value<T: Display>(&self, value: &T) -> String {
+        match self {
+            DiffStyle::Backticks => format!("`{value}`"),
+            DiffStyle::Plain => value.to_string(),
+            #[cfg(feature = "owo-colors")]
+            DiffStyle::OwoColors => owo_colors::OwoColorize::yellow(value).to_string(),
+            #[cfg(feature = "bullet_stream")]
+            DiffStyle::BulletStream => bullet_stream::style::value(value.to_string()),
+        }
+    }
+
+    /// Display the name of a changed field, unchanged unless the style colors names
+    pub fn fmt_name(&self, name: &str) -> String {
+        match self {
+            #[cfg(feature = "owo-colors")]
+            DiffStyle::OwoColors => owo_colors::OwoColorize::bold(&name).to_string(),
+            _ => name.to_string(),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "owo-colors"))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_owo_colors() {
+        assert_eq!(
+            DiffStyle::OwoColors.fmt_value(&"3.4.0"),
+            "\x1b[33m3.4.0\x1b[39m"
+        );
+        assert_eq!(
+            DiffStyle::OwoColors.fmt_name("Ruby version"),
+            "\x1b[1mRuby version\x1b[0m"
+        );
+        assert_eq!(DiffStyle::Plain.fmt_name("Ruby version"), "Ruby version");
+    }
+}
@@ -0,0 +1,174 @@
+//! Bounded, low cardinality key-values describing a diff for metrics and tracing backends
+
+use crate::Difference;
+
+/// Maximum number of distinct codes reported by [attributes]
+pub const MAX_CODES: usize = 16;
+
+/// Maximum length in bytes of a single reported code
+pub const MAX_CODE_LEN: usize = 64;
+
+/// Reported for differences without a code, for example messages from a `custom = <function>`
+pub const CUSTOM_CODE: &str = "custom";
+
+/// The value of a telemetry attribute, mirrors the OpenTelemetry value types used
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttributeValue {
+    Bool(bool),
+    I64(i64),
+    StringArray(Vec<String>),
+}
+
+/// Convert a structured diff into key-values suitable for span attributes
+///
+/// Only codes are reported, never names or values, so the output is bounded in size and
+/// safe to aggregate across builds. Codes are deduplicated, limited to [MAX_CODES] entries of
+/// at most [MAX_CODE_LEN] bytes each.
+///
+/// ```rust
+/// use cache_diff::CacheDiff;
+/// use cache_diff::telemetry::{attributes, AttributeValue};
+///
+/// #[derive(CacheDiff)]
+/// struct Metadata {
+///     #[cache_diff(rename = "Ruby version")]
+///     ruby_version: String,
+/// }
+/// let diff = Metadata { ruby_version: "3.4.0".to_string() }
+///     .diff_structured(&Metadata { ruby_version: "3.3.0".to_string() });
+///
+/// assert_eq!(
+///     attributes(&diff),
+///     vec![
+///         ("cache_diff.invalidated", AttributeValue::Bool(true)),
+///         ("cache_diff.difference_count", AttributeValue::I64(1)),
+///         ("cache_diff.codes", AttributeValue::StringArray(vec!["ruby_version".to_string()])),
+///         ("cache_diff.codes_truncated", AttributeValue::Bool(false)),
+///     ]
+/// );
+/// ```
+pub fn attributes(differences: &[Difference]) -> Vec<(&'static str, AttributeValue)> {
+    let (codes, truncated) = bounded_codes(differences);
+
+    vec![
+        (
+            "cache_diff.invalidated",
+            AttributeValue::Bool(!differences.is_empty()),
+        ),
+        (
+            "cache_diff.difference_count",
+            AttributeValue::I64(i64::try_from(differences.len()).unwrap_or(i64::MAX)),
+        ),
+        ("cache_diff.codes", AttributeValue::StringArray(codes)),
+        (
+            "cache_diff.codes_truncated",
+            AttributeValue::Bool(truncated),
+        ),
+    ]
+}
+
+/// Record a `tracing` event with the names and codes of the differences when the cache is invalidated
+///
+/// Nothing is recorded for an empty diff. The event is emitted at `INFO` level with the `cache_diff` target
+/// and the message `cache invalidated`. Its `fields` are the names of the changed fields, messages from a
+/// `custom = <function>` have no name and are only counted. Codes are bounded the same as [attributes].
+///
+/// ```rust
+/// use cache_diff::CacheDiff;
+///
+/// #[derive(CacheDiff)]
+/// struct Metadata {
+///     #[cache_diff(rename = "Ruby version")]
+///     ruby_version: String,
+/// }
+/// let now = Metadata { ruby_version: "3.4.0".to_string() };
+/// let differences = now.diff_structured(&Metadata { ruby_version: "3.3.0".to_string() });
+///
+/// // Records `cache invalidated difference_count=1 fields=["Ruby version"] codes=["ruby_version"]`
+/// cache_diff::telemetry::record_invalidation(&differences);
+/// ```
+#[cfg(feature = "tracing")]
+pub fn record_invalidation(differences: &[Difference]) {
+    if differences.is_empty() {
+        return;
+    }
+    let (codes, codes_truncated) = bounded_codes(differences);
+    let fields = differences
+        .iter()
+        .filter_map(Difference::name)
+        .collect::<Vec<&str>>();
+
+    ::tracing::info!(
+        target: "cache_diff",
+        difference_count = differences.len(),
+        fields = ?fields,
+        codes = ?codes,
+        codes_truncated,
+        "cache invalidated"
+    );
+}
+
+/// Deduplicated codes of the differences limited to [MAX_CODES], and whether any were left out
+fn bounded_codes(differences: &[Difference]) -> (Vec<String>, bool) {
+    let mut codes: Vec<String> = Vec::new();
+    let mut truncated = false;
+    for difference in differences {
+        let code = truncate(difference.code().unwrap_or(CUSTOM_CODE));
+        if codes.iter().any(|existing| existing == code) {
+            continue;
+        }
+        if codes.len() < MAX_CODES {
+            codes.push(code.to_string());
+        } else {
+            truncated = true;
+        }
+    }
+    (codes, truncated)
+}
+
+/// Shorten a code to [MAX_CODE_LEN] bytes without splitting a character
+fn truncate(code: &str) -> &str {
+    if code.len() <= MAX_CODE_LEN {
+        return code;
+    }
+    let mut end = MAX_CODE_LEN;
+    while !code.is_char_boundary(end) {
+        end -= 1;
+    }
+    &code[..end]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_bounded_codes() {
+        let mut differences = (0..20)
+            .map(|i| Difference::changed("name", "a", "b").with_code(format!("code_{i}")))
+            .collect::<Vec<Difference>>();
+        differences.push(Difference::message("custom one"));
+        differences.push(Difference::message("custom two"));
+
+        let attributes = attributes(&differences);
+        assert_eq!(
+            attributes[1],
+            ("cache_diff.difference_count", AttributeValue::I64(22))
+        );
+        let AttributeValue::StringArray(codes) = &attributes[2].1 else {
+            panic!("Expected codes, got {attributes:?}")
+        };
+        assert_eq!(codes.len(), MAX_CODES);
+        assert_eq!(
+            attributes[3],
+            ("cache_diff.codes_truncated", AttributeValue::Bool(true))
+        );
+    }
+
+    #[test]
+    fn test_truncate_on_char_boundary() {
+        let code = "é".repeat(MAX_CODE_LEN);
+        assert_eq!(truncate(&code).len(), MAX_CODE_LEN);
+        assert_eq!(truncate("short"), "short");
+    }
+}
@@ -0,0 +1,249 @@
+//! Runtime line format for a single field difference i.e. `"{name} ({old} to {new})"`
+
+use std::fmt::Display;
+use std::sync::{PoisonError, RwLock};
+
+static TEMPLATE: RwLock<Option<Template>> = RwLock::new(None);
+
+/// The format used to render a changed field
+///
+/// A template is a string with named placeholders that are filled in when a difference is
+/// rendered. Valid placeholders are:
+///
+/// - `{name}` The name of the field i.e. `Ruby version`
+/// - `{old}` The previous value, styled with [CacheDiff::fmt_value](crate::CacheDiff::fmt_value)
+/// - `{new}` The current value, styled with [CacheDiff::fmt_value](crate::CacheDiff::fmt_value)
+/// - `{code}` A stable code for the field, by default its identifier i.e. `ruby_version`
+///
+/// Literal braces are escaped by doubling them `{{` and `}}`. The default template is
+/// `"{name} ({old} to {new})"`.
+///
+/// ```rust
+/// use cache_diff::{CacheDiff, Template};
+///
+/// #[derive(CacheDiff)]
+/// #[cache_diff(template = "{name}: {old} => {new} ({code})")]
+/// struct Metadata {
+///     version: String,
+/// }
+/// let diff = Metadata { version: "3.4.0".to_string() }
+///     .diff(&Metadata { version: "3.3.0".to_string() });
+///
+/// assert_eq!(diff.join(" "), "version: `3.3.0` => `3.4.0` (version)");
+///
+/// let template = Template::parse("{{{name}}} changed").unwrap();
+/// assert_eq!(template.render("version", "`3.3.0`", "`3.4.0`", "version"), "{version} changed");
+/// ```
+///
+/// To change the template for every struct that does not set its own, use [Template::install].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Literal(String),
+    Name,
+    Old,
+    New,
+    Code,
+}
+
+/// Error returned when a [Template] cannot be parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateError {
+    /// A placeholder that is not one of `{name}`, `{old}`, `{new}`, or `{code}`
+    UnknownPlaceholder(String),
+    /// A `{` without a matching `}`
+    UnclosedPlaceholder,
+    /// A `}` that is not part of a placeholder and is not escaped as `}}`
+    UnmatchedClose,
+}
+
+impl Display for TemplateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TemplateError::UnknownPlaceholder(name) => write!(
+                f,
+                "Unknown template placeholder `{{{name}}}`. Must be one of `{{name}}`, `{{old}}`, `{{new}}`, `{{code}}`"
+            ),
+            TemplateError::UnclosedPlaceholder => {
+                write!(f, "Template contains a `{{` without a closing `}}`, escape literal braces as `{{{{`")
+            }
+            TemplateError::UnmatchedClose => {
+                write!(f, "Template contains a `}}` without an opening `{{`, escape literal braces as `}}}}`")
+            }
+        }
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+impl Default for Template {
+    fn default() -> Self {
+        Self {
+            segments: vec![
+                Segment::Name,
+                Segment::Literal(" (".to_string()),
+                Segment::Old,
+                Segment::Literal(" to ".to_string()),
+                Segment::New,
+                Segment::Literal(")".to_string()),
+            ],
+        }
+    }
+}
+
+impl Template {
+    /// Parse a template string such as `"{name} ({old} to {new})"`
+    ///
+    /// Placeholders are a name in braces, one of `{name}`, `{old}`, `{new}`, or `{code}`. Each
+    /// may be used any number of times or not at all. Everything else is copied as is, literal
+    /// braces are escaped by doubling them `{{` and `}}`.
+    ///
+    /// # Errors
+    ///
+    /// - [TemplateError::UnknownPlaceholder] when a placeholder is not one of the names above,
+    ///   including `{}` and names with whitespace such as `{ name }`
+    /// - [TemplateError::UnclosedPlaceholder] when a `{` has no closing `}`
+    /// - [TemplateError::UnmatchedClose] when a single `}` is not closing a placeholder
+    ///
+    /// ```rust
+    /// use cache_diff::{Template, TemplateError};
+    ///
+    /// assert!(Template::parse("{name}: {old} => {new}").is_ok());
+    /// assert_eq!(
+    ///     Template::parse("{version} changed"),
+    ///     Err(TemplateError::UnknownPlaceholder("version".to_string()))
+    /// );
+    /// assert_eq!(Template::parse("{name"), Err(TemplateError::UnclosedPlaceholder));
+    /// assert_eq!(Template::parse("name}"), Err(TemplateError::UnmatchedClose));
+    /// ```
+    pub fn parse(template: &str) -> Result<Self, TemplateError> {
+        let mut segments = Vec::new();
+        let mut literal = String::new();
+        let mut chars = template.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '}' => return Err(TemplateError::UnmatchedClose),
+                '{' => {
+                    let mut placeholder = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => placeholder.push(c),
+                            None => return Err(TemplateError::UnclosedPlaceholder),
+                        }
+                    }
+                    if !literal.is_empty() {
+                        segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                    }
+                    segments.push(match placeholder.as_str() {
+                        "name" => Segment::Name,
+                        "old" => Segment::Old,
+                        "new" => Segment::New,
+                        "code" => Segment::Code,
+                        _ => return Err(TemplateError::UnknownPlaceholder(placeholder)),
+                    });
+                }
+                c => literal.push(c),
+            }
+        }
+        if !literal.is_empty() {
+            segments.push(Segment::Literal(literal));
+        }
+
+        Ok(Self { segments })
+    }
+
+    /// Fill in the placeholders, values are expected to already be styled
+    pub fn render(&self, name: &str, old: &str, new: &str, code: &str) -> String {
+        let mut out = String::new();
+        self.write_to(&mut out, name, old, new, code)
+            .expect("Writing to a String cannot fail");
+        out
+    }
+
+    /// Like [Template::render] but writes directly into `w` instead of allocating a String
+    pub fn write_to<W: std::fmt::Write + ?Sized>(
+        &self,
+        w: &mut W,
+        name: &str,
+        old: &str,
+        new: &str,
+        code: &str,
+    ) -> std::fmt::Result {
+        for segment in &self.segments {
+            w.write_str(match segment {
+                Segment::Literal(literal) => literal.as_str(),
+                Segment::Name => name,
+                Segment::Old => old,
+                Segment::New => new,
+                Segment::Code => code,
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Make this the template used by every struct in the current process that does
+    /// not specify its own with `#[cache_diff(template = "...")]`
+    pub fn install(self) {
+        *TEMPLATE.write().unwrap_or_else(PoisonError::into_inner) = Some(self);
+    }
+
+    /// Remove any installed template, the default is used again
+    pub fn reset() {
+        *TEMPLATE.write().unwrap_or_else(PoisonError::into_inner) = None;
+    }
+
+    /// The installed template or the default if none was installed
+    pub fn current() -> Self {
+        TEMPLATE
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_default_matches_parsed() {
+        assert_eq!(
+            Template::default(),
+            Template::parse("{name} ({old} to {new})").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_escaped_braces() {
+        let template = Template::parse("{{{name}}}: {old}}} {{{new}").unwrap();
+        assert_eq!(template.render("a", "b", "c", "d"), "{a}: b} {c");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            Template::parse("{name} {version}"),
+            Err(TemplateError::UnknownPlaceholder("version".to_string()))
+        );
+        assert_eq!(
+            Template::parse("{name"),
+            Err(TemplateError::UnclosedPlaceholder)
+        );
+        assert_eq!(Template::parse("name}"), Err(TemplateError::UnmatchedClose));
+    }
+}
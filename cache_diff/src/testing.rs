@@ -0,0 +1,383 @@
+//! Helpers for asserting on diff output in tests
+
+use crate::{render_plain, Difference};
+use std::path::Path;
+
+/// Set to `1` to write golden files instead of comparing against them, see [assert_golden]
+pub const UPDATE_ENV_VAR: &str = "CACHE_DIFF_UPDATE_GOLDEN";
+
+pub use crate::strip_ansi;
+
+/// Returns true if the output contains a rendered difference for the field `name`
+///
+/// Output is searched line by line for `<name> (<old> to <new>)` after removing ANSI
+/// escape codes, so it works on captured `pack build` logs regardless of prefixes or styling.
+///
+/// ```rust
+/// use cache_diff::testing::contains_difference;
+///
+/// let log = "[Installing Ruby]\n  - Clearing cache\n  - Ruby version (`3.3.0` to `3.4.0`)\n";
+/// assert!(contains_difference(log, "Ruby version"));
+/// assert!(!contains_difference(log, "Bundler version"));
+/// ```
+pub fn contains_difference(output: &str, name: &str) -> bool {
+    strip_ansi(output).lines().any(|line| {
+        line.match_indices(&format!("{name} ("))
+            .any(|(index, matched)| {
+                let rest = &line[index + matched.len()..];
+                rest.contains(" to ") && rest.trim_end().ends_with(')')
+            })
+    })
+}
+
+/// Parse one rendered difference back into its `(name, old, new)` parts
+///
+/// Works on lines in the default `<name> (<old> to <new>)` format with or without a leading `- `
+/// bullet, ANSI colors, or backticks around values. Returns `None` for free form messages.
+///
+/// ```rust
+/// use cache_diff::testing::parse_line;
+///
+/// assert_eq!(
+///     parse_line("  - Ruby version (`3.3.0` to `3.4.0`)"),
+///     Some(("Ruby version".to_string(), "3.3.0".to_string(), "3.4.0".to_string()))
+/// );
+/// assert_eq!(parse_line("Cache count (201) exceeded limit 200"), None);
+/// ```
+pub fn parse_line(line: &str) -> Option<(String, String, String)> {
+    let line = strip_ansi(line);
+    let line = line.trim();
+    let line = line.strip_prefix("- ").unwrap_or(line);
+    let inner = line.strip_suffix(')')?;
+
+    inner.match_indices(" (").find_map(|(index, matched)| {
+        let values = &inner[index + matched.len()..];
+        let (old, new) = match values.split_once("` to `") {
+            Some((old, new)) => (old.strip_prefix('`')?, new.strip_suffix('`')?),
+            None => values.split_once(" to ")?,
+        };
+        Some((inner[..index].to_string(), old.to_string(), new.to_string()))
+    })
+}
+
+/// Assert that build output reports a cache invalidation for each of the given field names
+///
+/// ```rust
+/// use cache_diff::assert_invalidated_with;
+///
+/// let pack_stdout = "- Clearing cache\n- Ruby version (`3.3.0` to `3.4.0`)\n- OS (`22.04` to `24.04`)";
+/// assert_invalidated_with!(pack_stdout, ["Ruby version", "OS"]);
+/// ```
+#[macro_export]
+macro_rules! assert_invalidated_with {
+    ($output:expr, [$($name:expr),+ $(,)?]) => {{
+        let output: &str = ::std::convert::AsRef::<str>::as_ref(&$output);
+        $(
+            if !$crate::testing::contains_difference(output, $name) {
+                panic!(
+                    "Expected output to report an invalidation for `{}`, but it was not found in:\n{}",
+                    $name, output
+                );
+            }
+        )+
+    }};
+}
+
+/// Returns true if a difference is for the field `name`, or is a message containing `name`
+#[doc(hidden)]
+pub fn has_difference(differences: &[Difference], name: &str) -> bool {
+    differences
+        .iter()
+        .any(|difference| match difference.name() {
+            Some(field) => field == name,
+            None => difference.to_string().contains(name),
+        })
+}
+
+/// Assert that two cache values have no differences, panics with the rendered diff if they do
+///
+/// ```rust
+/// use cache_diff::{assert_no_diff, CacheDiff};
+///
+/// #[derive(CacheDiff)]
+/// struct Metadata {
+///     version: String,
+/// }
+///
+/// let now = Metadata { version: "3.4.0".to_string() };
+/// assert_no_diff!(now, Metadata { version: "3.4.0".to_string() });
+/// ```
+///
+/// On failure the message contains each difference on its own line:
+///
+/// ```text
+/// Expected no differences, found 1:
+/// - version (`3.3.0` to `3.4.0`)
+/// ```
+#[macro_export]
+macro_rules! assert_no_diff {
+    ($now:expr, $old:expr $(,)?) => {{
+        let differences = $crate::CacheDiff::diff_structured(&$now, &$old);
+        if !differences.is_empty() {
+            panic!(
+                "Expected no differences, found {}:\n{}",
+                differences.len(),
+                $crate::render_plain(&differences)
+            );
+        }
+    }};
+}
+
+/// Assert that two cache values differ in each of the given fields, panics with the rendered diff if not
+///
+/// A field matches by its name in the output, free form messages from `custom = <function>`
+/// match when they contain the given text:
+///
+/// ```rust
+/// use cache_diff::{assert_diff_contains, CacheDiff};
+///
+/// #[derive(CacheDiff)]
+/// struct Metadata {
+///     #[cache_diff(rename = "Ruby version")]
+///     version: String,
+///     distro: String,
+/// }
+///
+/// let now = Metadata { version: "3.4.0".to_string(), distro: "Ubuntu".to_string() };
+/// let old = Metadata { version: "3.3.0".to_string(), distro: "Alpine".to_string() };
+/// assert_diff_contains!(now, old, ["Ruby version", "distro"]);
+/// ```
+#[macro_export]
+macro_rules! assert_diff_contains {
+    ($now:expr, $old:expr, [$($name:expr),+ $(,)?] $(,)?) => {{
+        let differences = $crate::CacheDiff::diff_structured(&$now, &$old);
+        $(
+            if !$crate::testing::has_difference(&differences, $name) {
+                panic!(
+                    "Expected a difference for `{}`, but it was not found in:\n{}",
+                    $name,
+                    if differences.is_empty() {
+                        "(no differences)".to_string()
+                    } else {
+                        $crate::render_plain(&differences)
+                    }
+                );
+            }
+        )+
+    }};
+}
+
+/// Compare rendered differences against a checked in fixture file
+///
+/// Differences are rendered with [render_plain](crate::render_plain) so fixtures don't change
+/// with enabled features. Run tests with `CACHE_DIFF_UPDATE_GOLDEN=1` to create or update the file,
+/// then review the wording change in the PR:
+///
+/// ```rust,no_run
+/// use cache_diff::CacheDiff;
+///
+/// #[derive(CacheDiff)]
+/// struct Metadata {
+///     #[cache_diff(rename = "Ruby version")]
+///     version: String,
+/// }
+/// let diff = Metadata { version: "3.4.0".to_string() }
+///     .diff_structured(&Metadata { version: "3.3.0".to_string() });
+///
+/// cache_diff::testing::assert_golden("tests/fixtures/ruby_version.txt", &diff);
+/// ```
+#[track_caller]
+pub fn assert_golden(path: impl AsRef<Path>, differences: &[Difference]) {
+    let update = std::env::var(UPDATE_ENV_VAR).is_ok_and(|value| value == "1");
+    if let Err(message) = check_golden(path.as_ref(), differences, update) {
+        panic!("{message}");
+    }
+}
+
+fn check_golden(path: &Path, differences: &[Difference], update: bool) -> Result<(), String> {
+    let actual = format!("{}\n", render_plain(differences));
+    if update {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|error| format!("Could not create {}: {error}", parent.display()))?;
+        }
+        return std::fs::write(path, actual)
+            .map_err(|error| format!("Could not write {}: {error}", path.display()));
+    }
+
+    let expected = std::fs::read_to_string(path).map_err(|error| {
+        format!(
+            "Could not read golden file {}: {error}\nRun with {UPDATE_ENV_VAR}=1 to create it",
+            path.display()
+        )
+    })?;
+    if expected.replace("\r\n", "\n") == actual {
+        Ok(())
+    } else {
+        Err(format!(
+            "Golden file {} does not match\nExpected:\n{expected}\nActual:\n{actual}\nRun with {UPDATE_ENV_VAR}=1 to update it",
+            path.display()
+        ))
+    }
+}
+
+/// Assert that build output does not report a cache invalidation for any of the given field names
+///
+/// ```rust
+/// use cache_diff::assert_not_invalidated_with;
+///
+/// let pack_stdout = "- Clearing cache\n- Ruby version (`3.3.0` to `3.4.0`)";
+/// assert_not_invalidated_with!(pack_stdout, ["Bundler version"]);
+/// ```
+#[macro_export]
+macro_rules! assert_not_invalidated_with {
+    ($output:expr, [$($name:expr),+ $(,)?]) => {{
+        let output: &str = ::std::convert::AsRef::<str>::as_ref(&$output);
+        $(
+            if $crate::testing::contains_difference(output, $name) {
+                panic!(
+                    "Expected output to not report an invalidation for `{}`, but it was found in:\n{}",
+                    $name, output
+                );
+            }
+        )+
+    }};
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_contains_difference_with_ansi() {
+        let log = "remote: \x1b[0;36m- Ruby version (\x1b[0;33m3.3.0\x1b[0m to \x1b[0;33m3.4.0\x1b[0m)\x1b[0m";
+        assert!(contains_difference(log, "Ruby version"));
+        assert!(!contains_difference(log, "Ruby"));
+    }
+
+    #[test]
+    #[should_panic(expected = "Expected output to report an invalidation for `Bundler version`")]
+    fn test_assert_invalidated_with_panics() {
+        assert_invalidated_with!("- Ruby version (`3.3.0` to `3.4.0`)", ["Bundler version"]);
+    }
+
+    #[test]
+    fn test_parse_line() {
+        assert_eq!(
+            parse_line("- Ruby version (\x1b[0;33m3.3.0\x1b[0m to \x1b[0;33m3.4.0\x1b[0m)"),
+            Some((
+                "Ruby version".to_string(),
+                "3.3.0".to_string(),
+                "3.4.0".to_string()
+            ))
+        );
+        assert_eq!(
+            parse_line("OS (ubuntu) (`22 to 24` to `24 to 26`)"),
+            Some((
+                "OS (ubuntu)".to_string(),
+                "22 to 24".to_string(),
+                "24 to 26".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Expected output to not report an invalidation for `Ruby version`")]
+    fn test_assert_not_invalidated_with_panics() {
+        assert_not_invalidated_with!("- Ruby version (`3.3.0` to `3.4.0`)", ["Ruby version"]);
+    }
+
+    struct Metadata {
+        version: String,
+    }
+
+    impl crate::CacheDiff for Metadata {
+        fn diff(&self, old: &Self) -> Vec<String> {
+            self.diff_structured(old)
+                .iter()
+                .map(ToString::to_string)
+                .collect()
+        }
+
+        fn diff_structured(&self, old: &Self) -> Vec<Difference> {
+            if self.version == old.version {
+                Vec::new()
+            } else {
+                vec![Difference::changed(
+                    "version",
+                    old.version.clone(),
+                    self.version.clone(),
+                )]
+            }
+        }
+    }
+
+    fn metadata(version: &str) -> Metadata {
+        Metadata {
+            version: version.to_string(),
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Expected no differences, found 1:\n- version (`3.3.0` to `3.4.0`)")]
+    fn test_assert_no_diff_panics() {
+        assert_no_diff!(metadata("3.4.0"), metadata("3.3.0"));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Expected a difference for `distro`, but it was not found in:\n- version"
+    )]
+    fn test_assert_diff_contains_panics() {
+        assert_diff_contains!(metadata("3.4.0"), metadata("3.3.0"), ["version", "distro"]);
+    }
+
+    #[test]
+    fn test_check_golden() {
+        let path = std::env::temp_dir()
+            .join(format!("cache_diff_golden_{}", std::process::id()))
+            .join("version.txt");
+        let differences = vec![Difference::changed("version", "3.3.0", "3.4.0")];
+
+        let error = check_golden(&path, &differences, false).unwrap_err();
+        assert!(
+            error.contains("Run with CACHE_DIFF_UPDATE_GOLDEN=1 to create it"),
+            "{error}"
+        );
+
+        check_golden(&path, &differences, true).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "- version (`3.3.0` to `3.4.0`)\n"
+        );
+        check_golden(&path, &differences, false).unwrap();
+
+        let error = check_golden(
+            &path,
+            &[Difference::changed("version", "3.3.0", "3.5.0")],
+            false,
+        )
+        .unwrap_err();
+        assert!(error.contains("does not match"), "{error}");
+
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Expected the cache to be kept, found 1 difference(s):\n- version (`3.3.0` to `3.4.0`)"
+    )]
+    fn test_assert_keeps_panics() {
+        use crate::CacheDiff;
+        metadata("3.4.0").assert_keeps(&metadata("3.3.0"));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Expected the cache to be invalidated, but no differences were found"
+    )]
+    fn test_assert_invalidates_panics() {
+        use crate::CacheDiff;
+        metadata("3.4.0").assert_invalidates(&metadata("3.4.0"));
+    }
+}
@@ -0,0 +1,120 @@
+//! Helpers for displaying differences within long string values
+//!
+//! Useful in custom diff functions so long values are displayed consistently with the
+//! `#[cache_diff(lines)]` field attribute. For example a `PATH`-like value where only one entry
+//! changed, or a multi-line config file.
+//!
+//! ```rust
+//! use cache_diff::CacheDiff;
+//!
+//! #[derive(CacheDiff)]
+//! struct Metadata {
+//!     #[cache_diff(lines)]
+//!     gemfile_lock: String,
+//! }
+//! let diff = Metadata { gemfile_lock: "rack 3.1\nrake 13.2\n".to_string() }
+//!     .diff(&Metadata { gemfile_lock: "rack 3.0\nrake 13.2\n".to_string() });
+//!
+//! assert_eq!(
+//!     diff.join(" "),
+//!     "gemfile lock changed:\n-rack 3.0\n+rack 3.1\n rake 13.2"
+//! );
+//! ```
+
+use similar::{ChangeTag, TextDiff};
+
+/// Show only the parts of a single line value that changed
+///
+/// Unchanged characters are kept and each changed run is shown as `[-removed-]{+added+}`:
+///
+/// ```rust
+/// use cache_diff::text::inline;
+///
+/// assert_eq!(
+///     inline("/layers/ruby/3.3.0/bin", "/layers/ruby/3.4.0/bin"),
+///     "/layers/ruby/3.[-3-]{+4+}.0/bin"
+/// );
+/// ```
+pub fn inline(old: &str, now: &str) -> String {
+    let diff = TextDiff::from_chars(old, now);
+    let mut output = String::new();
+    let mut removed = String::new();
+    let mut added = String::new();
+    for change in diff.iter_all_changes() {
+        match change.tag() {
+            ChangeTag::Delete => removed.push_str(change.value()),
+            ChangeTag::Insert => added.push_str(change.value()),
+            ChangeTag::Equal => {
+                flush_inline(&mut output, &mut removed, &mut added);
+                output.push_str(change.value());
+            }
+        }
+    }
+    flush_inline(&mut output, &mut removed, &mut added);
+    output
+}
+
+/// Line by line diff of two multi-line values in a unified style
+///
+/// Unchanged lines are prefixed with a space, removed lines with `-` and added lines with `+`:
+///
+/// ```rust
+/// use cache_diff::text::unified;
+///
+/// assert_eq!(
+///     unified("ruby 3.3.0\nbundler 2.5\n", "ruby 3.4.0\nbundler 2.5\n"),
+///     "-ruby 3.3.0\n+ruby 3.4.0\n bundler 2.5"
+/// );
+/// ```
+pub fn unified(old: &str, now: &str) -> String {
+    // Compare on `str::lines` so a missing trailing newline does not count as a change
+    let old = old.lines().collect::<Vec<&str>>();
+    let now = now.lines().collect::<Vec<&str>>();
+    TextDiff::from_slices(&old, &now)
+        .iter_all_changes()
+        .map(|change| {
+            let sign = match change.tag() {
+                ChangeTag::Equal => ' ',
+                ChangeTag::Delete => '-',
+                ChangeTag::Insert => '+',
+            };
+            format!("{sign}{}", change.value())
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Append a pending run of removed and added characters to the output
+fn flush_inline(output: &mut String, removed: &mut String, added: &mut String) {
+    if !removed.is_empty() {
+        output.push_str(&format!("[-{removed}-]"));
+        removed.clear();
+    }
+    if !added.is_empty() {
+        output.push_str(&format!("{{+{added}+}}"));
+        added.clear();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_inline() {
+        assert_eq!(inline("same", "same"), "same");
+        assert_eq!(inline("abc", "abcd"), "abc{+d+}");
+        assert_eq!(inline("abcd", "abc"), "abc[-d-]");
+        assert_eq!(inline("", "new"), "{+new+}");
+        assert_eq!(inline("aXa", "aa"), "a[-X-]a");
+        assert_eq!(inline("añb", "aéb"), "a[-ñ-]{+é+}b");
+        assert_eq!(inline("a1b2c", "a3b4c"), "a[-1-]{+3+}b[-2-]{+4+}c");
+    }
+
+    #[test]
+    fn test_unified() {
+        assert_eq!(unified("a\nb\nc", "a\nc\nd"), " a\n-b\n c\n+d");
+        assert_eq!(unified("", "a"), "+a");
+        assert_eq!(unified("a", ""), "-a");
+    }
+}
@@ -0,0 +1,220 @@
+//! Diff two arbitrary TOML documents when there is no struct to derive on
+
+use crate::document::Document;
+use crate::Difference;
+use ::toml::Value;
+
+/// Compare two TOML values and return a message for every key that differs
+///
+/// Tables are compared key by key and nested keys are named with dots i.e. `ruby.version`.
+/// All other values, including arrays, are compared as a whole.
+///
+/// ```rust
+/// let old: toml::Value = toml::from_str(r#"
+/// [ruby]
+/// version = "3.3.0"
+/// "#).unwrap();
+/// let now: toml::Value = toml::from_str(r#"
+/// [ruby]
+/// version = "3.4.0"
+/// "#).unwrap();
+///
+/// assert_eq!(
+///     cache_diff::toml::diff_values(&old, &now).join(" "),
+///     "ruby.version (`3.3.0` to `3.4.0`)"
+/// );
+/// ```
+pub fn diff_values(old: &Value, now: &Value) -> Vec<String> {
+    diff_values_structured(old, now)
+        .iter()
+        .map(ToString::to_string)
+        .collect()
+}
+
+/// Like [diff_values] but returns a [Difference] for each changed key
+pub fn diff_values_structured(old: &Value, now: &Value) -> Vec<Difference> {
+    crate::document::diff_documents(old, now, false)
+}
+
+/// Like [diff_values_structured] but also reports changes to the shape of the document
+///
+/// Keys that were added or removed and values whose type changed are described as messages with
+/// the dotted key as their code, so an upgrade of the metadata schema itself explains why the
+/// cache was cleared. Other changes are reported the same as [diff_values_structured].
+///
+/// ```rust
+/// let old: toml::Value = toml::from_str(r#"
+/// version = "3"
+/// distro = "ubuntu"
+/// "#).unwrap();
+/// let now: toml::Value = toml::from_str(r#"
+/// version = 3
+/// arch = "arm64"
+/// "#).unwrap();
+///
+/// let differences = cache_diff::toml::diff_schema(&old, &now);
+/// assert_eq!(
+///     differences.iter().map(ToString::to_string).collect::<Vec<_>>(),
+///     vec![
+///         "Field `distro` removed",
+///         "Field `version` changed type from string to integer",
+///         "Field `arch` added",
+///     ]
+/// );
+/// ```
+pub fn diff_schema(old: &Value, now: &Value) -> Vec<Difference> {
+    crate::document::diff_documents(old, now, true)
+}
+
+/// Convert differences into a TOML table with a `differences` array, for example to record
+/// why a layer was rebuilt in `store.toml`
+///
+/// ```rust
+/// use cache_diff::Difference;
+///
+/// let value = cache_diff::toml::to_value(&[
+///     Difference::changed("version", "3.3.0", "3.4.0").with_code("version"),
+/// ]);
+/// assert_eq!(
+///     toml::to_string(&value).unwrap(),
+///     "[[differences]]\ncode = \"version\"\nname = \"version\"\nnow = \"3.4.0\"\nold = \"3.3.0\"\n"
+/// );
+/// ```
+pub fn to_value(differences: &[Difference]) -> Value {
+    let differences = differences
+        .iter()
+        .map(|difference| {
+            Value::Table(
+                difference
+                    .entries()
+                    .into_iter()
+                    .map(|(key, value)| (key.to_string(), Value::String(value.to_string())))
+                    .collect(),
+            )
+        })
+        .collect();
+
+    Value::Table(::toml::Table::from_iter([(
+        "differences".to_string(),
+        Value::Array(differences),
+    )]))
+}
+
+/// Tables are compared key by key, strings are shown without quotes and everything else as inline TOML
+impl Document for Value {
+    fn keys(&self) -> Option<Vec<&str>> {
+        self.as_table()
+            .map(|table| table.keys().map(String::as_str).collect())
+    }
+
+    fn get(&self, key: &str) -> Option<&Self> {
+        self.as_table()?.get(key)
+    }
+
+    fn type_name(&self) -> &'static str {
+        match self {
+            Value::String(_) => "string",
+            Value::Integer(_) => "integer",
+            Value::Float(_) => "float",
+            Value::Boolean(_) => "boolean",
+            Value::Datetime(_) => "datetime",
+            Value::Array(_) => "array",
+            Value::Table(_) => "table",
+        }
+    }
+
+    fn display(&self) -> String {
+        match self {
+            Value::String(value) => value.clone(),
+            other => other.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{DiffStyle, Template};
+
+    /// Render with backticks so the expected output doesn't depend on enabled features
+    fn backticks(differences: Vec<Difference>) -> Vec<String> {
+        differences
+            .iter()
+            .map(|difference| {
+                difference.render(&Template::default(), |value| {
+                    DiffStyle::Backticks.fmt_value(&value)
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_added_removed_and_nested() {
+        let old: Value = ::toml::from_str(
+            r#"
+            removed = 1
+            list = [1, 2]
+
+            [os]
+            distro = "ubuntu"
+            version = "22.04"
+            "#,
+        )
+        .unwrap();
+        let now: Value = ::toml::from_str(
+            r#"
+            list = [1, 2, 3]
+            added = true
+
+            [os]
+            distro = "ubuntu"
+            version = "24.04"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            backticks(diff_values_structured(&old, &now)),
+            vec![
+                "list (`[1, 2]` to `[1, 2, 3]`)",
+                "os.version (`22.04` to `24.04`)",
+                "removed (`1` to `(not set)`)",
+                "added (`(not set)` to `true`)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_schema_reports_shape_and_value_changes() {
+        let old: Value = ::toml::from_str(
+            r#"
+            [os]
+            version = "22.04"
+            "#,
+        )
+        .unwrap();
+        let now: Value = ::toml::from_str(
+            r#"
+            os = "ubuntu-24.04"
+            [ruby]
+            version = "3.4.0"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            diff_schema(&old, &now),
+            vec![
+                Difference::message("Field `os` changed type from table to string").with_code("os"),
+                Difference::message("Field `ruby` added").with_code("ruby"),
+            ]
+        );
+        assert_eq!(diff_schema(&now, &now), vec![]);
+    }
+
+    #[test]
+    fn test_same() {
+        let value: Value = ::toml::from_str("name = \"value\"").unwrap();
+        assert!(diff_values(&value, &value).is_empty());
+    }
+}
@@ -0,0 +1,185 @@
+//! Differences of nested metadata arranged as a tree
+
+use crate::Difference;
+use std::fmt::Display;
+
+/// Differences grouped under the fields they are nested in
+///
+/// Built from [Difference]-s using their [Difference::parents], such as those of a
+/// `#[cache_diff(nested)]` field. It displays as an indented tree with each parent on its own line
+/// and its differences beneath, which reads better than flat lines for deeply nested metadata:
+///
+/// ```rust
+/// use cache_diff::{CacheDiff, DiffTree};
+///
+/// #[derive(CacheDiff)]
+/// struct Bundler {
+///     version: String,
+/// }
+///
+/// #[derive(CacheDiff)]
+/// struct Ruby {
+///     version: String,
+///     #[cache_diff(nested)]
+///     bundler: Bundler,
+/// }
+///
+/// #[derive(CacheDiff)]
+/// struct Metadata {
+///     #[cache_diff(nested)]
+///     ruby: Ruby,
+///     distro: String,
+/// }
+/// let metadata = |ruby: &str, bundler: &str, distro: &str| Metadata {
+///     ruby: Ruby {
+///         version: ruby.to_string(),
+///         bundler: Bundler { version: bundler.to_string() },
+///     },
+///     distro: distro.to_string(),
+/// };
+/// let now = metadata("3.4.0", "2.5.0", "Ubuntu");
+/// let old = metadata("3.3.0", "2.4.0", "Alpine");
+///
+/// let tree = DiffTree::new(now.diff_structured(&old));
+/// assert_eq!(
+///     tree.to_string(),
+///     "- ruby\n  - version (`3.3.0` to `3.4.0`)\n  - bundler\n    - version (`2.4.0` to `2.5.0`)\n- distro (`Alpine` to `Ubuntu`)"
+/// );
+/// ```
+///
+/// Parents and differences keep the order they first appear in. An empty tree displays as an empty string.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DiffTree {
+    nodes: Vec<Node>,
+}
+
+/// A difference at this level of the tree or a parent with differences beneath it
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Node {
+    Difference(Difference),
+    Parent { name: String, tree: DiffTree },
+}
+
+impl DiffTree {
+    pub fn new(differences: impl IntoIterator<Item = Difference>) -> Self {
+        let mut tree = Self::default();
+        for difference in differences {
+            let parents = difference
+                .parents()
+                .into_iter()
+                .map(String::from)
+                .collect::<Vec<String>>();
+            tree.insert(&parents, difference);
+        }
+        tree
+    }
+
+    fn insert(&mut self, parents: &[String], difference: Difference) {
+        let Some((parent, rest)) = parents.split_first() else {
+            self.nodes.push(Node::Difference(difference));
+            return;
+        };
+        let existing = self.nodes.iter_mut().find_map(|node| match node {
+            Node::Parent { name, tree } if name == parent => Some(tree),
+            _ => None,
+        });
+        match existing {
+            Some(tree) => tree.insert(rest, difference),
+            None => {
+                let mut tree = DiffTree::default();
+                tree.insert(rest, difference);
+                self.nodes.push(Node::Parent {
+                    name: parent.clone(),
+                    tree,
+                });
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// The number of differences at every level of the tree
+    pub fn len(&self) -> usize {
+        self.nodes
+            .iter()
+            .map(|node| match node {
+                Node::Difference(_) => 1,
+                Node::Parent { tree, .. } => tree.len(),
+            })
+            .sum()
+    }
+
+    /// The differences directly at this level, without those beneath a parent
+    pub fn differences(&self) -> impl Iterator<Item = &Difference> {
+        self.nodes.iter().filter_map(|node| match node {
+            Node::Difference(difference) => Some(difference),
+            Node::Parent { .. } => None,
+        })
+    }
+
+    /// The tree beneath `parent`, i.e. the differences of a nested field
+    pub fn child(&self, parent: &str) -> Option<&DiffTree> {
+        self.nodes.iter().find_map(|node| match node {
+            Node::Parent { name, tree } if name == parent => Some(tree),
+            _ => None,
+        })
+    }
+
+    fn write_lines(&self, depth: usize, lines: &mut Vec<String>) {
+        let indent = "  ".repeat(depth);
+        for node in &self.nodes {
+            match node {
+                Node::Difference(difference) => lines.push(format!("{indent}- {difference}")),
+                Node::Parent { name, tree } => {
+                    lines.push(format!("{indent}- {name}"));
+                    tree.write_lines(depth + 1, lines);
+                }
+            }
+        }
+    }
+}
+
+impl FromIterator<Difference> for DiffTree {
+    fn from_iter<I: IntoIterator<Item = Difference>>(differences: I) -> Self {
+        Self::new(differences)
+    }
+}
+
+impl Display for DiffTree {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut lines = Vec::new();
+        self.write_lines(0, &mut lines);
+        f.write_str(&lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parents_keep_first_order() {
+        let tree = DiffTree::new([
+            Difference::changed("version", "3.3.0", "3.4.0").with_parent("ruby"),
+            Difference::message("Stack changed"),
+            Difference::changed("bundler", "2.4.0", "2.5.0").with_parent("ruby"),
+        ]);
+        assert_eq!(tree.len(), 3);
+        assert_eq!(tree.differences().count(), 1);
+        assert_eq!(tree.child("ruby").map(DiffTree::len), Some(2));
+        // Values are styled with the default `DiffStyle`, which colors them with `bullet_stream`
+        assert_eq!(
+            crate::strip_ansi(&tree.to_string()),
+            "- ruby\n  - version (`3.3.0` to `3.4.0`)\n  - bundler (`2.4.0` to `2.5.0`)\n- Stack changed"
+        );
+    }
+
+    #[test]
+    fn test_empty() {
+        let tree = DiffTree::new([]);
+        assert!(tree.is_empty());
+        assert_eq!(tree.to_string(), "");
+    }
+}
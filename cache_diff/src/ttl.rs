@@ -0,0 +1,184 @@
+//! Expire a cache after a duration with `#[cache_diff(ttl = "30d")]`
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A point in time stored in a field marked `#[cache_diff(ttl = "...")]`
+///
+/// Implemented for [SystemTime] and for seconds since the unix epoch as `u64` or `i64`.
+/// Implement it for other timestamp types to use them with `ttl`.
+///
+/// ```rust
+/// use cache_diff::CacheDiff;
+/// use std::time::{Duration, SystemTime};
+///
+/// #[derive(CacheDiff)]
+/// struct Metadata {
+///     version: String,
+///     #[cache_diff(ttl = "30d")]
+///     created_at: SystemTime,
+/// }
+/// let created_at = SystemTime::now() - Duration::from_secs(45 * 24 * 60 * 60);
+/// let old = Metadata { version: "3.4.0".to_string(), created_at };
+/// let now = Metadata { version: "3.4.0".to_string(), created_at: SystemTime::now() };
+///
+/// assert_eq!(
+///     now.diff(&old),
+///     vec!["created at expired (created 45 days ago, limit 30 days)".to_string()]
+/// );
+/// ```
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` cannot be used with `#[cache_diff(ttl = \"...\")]`",
+    label = "not a timestamp",
+    note = "use a `SystemTime`, seconds since the unix epoch as `u64` or `i64`, or implement `cache_diff::Timestamp` for `{Self}`"
+)]
+pub trait Timestamp {
+    /// Seconds since the unix epoch, `None` when the time cannot be represented
+    fn unix_seconds(&self) -> Option<i64>;
+}
+
+impl Timestamp for SystemTime {
+    fn unix_seconds(&self) -> Option<i64> {
+        match self.duration_since(UNIX_EPOCH) {
+            Ok(duration) => i64::try_from(duration.as_secs()).ok(),
+            Err(before) => i64::try_from(before.duration().as_secs()).ok().map(|s| -s),
+        }
+    }
+}
+
+impl Timestamp for u64 {
+    fn unix_seconds(&self) -> Option<i64> {
+        i64::try_from(*self).ok()
+    }
+}
+
+impl Timestamp for i64 {
+    fn unix_seconds(&self) -> Option<i64> {
+        Some(*self)
+    }
+}
+
+impl<T: Timestamp + ?Sized> Timestamp for &T {
+    fn unix_seconds(&self) -> Option<i64> {
+        (**self).unix_seconds()
+    }
+}
+
+/// Used by generated code, returns `true` when the timestamp is older than the limit
+#[doc(hidden)]
+pub fn ttl_expired<T: Timestamp + ?Sized>(created: &T, limit_seconds: u64) -> bool {
+    age(created, current_seconds()).is_some_and(|age| age > limit_seconds)
+}
+
+/// Used by generated code, describes the expired timestamp of the field `name`
+#[doc(hidden)]
+pub fn ttl_message<T: Timestamp + ?Sized>(name: &str, created: &T, limit_seconds: u64) -> String {
+    message(name, age(created, current_seconds()), limit_seconds)
+}
+
+/// Used by generated code to display a timestamp that doesn't implement Display
+#[doc(hidden)]
+pub fn ttl_display<T: Timestamp + ?Sized>(value: &T) -> String {
+    match value.unix_seconds().map(u64::try_from) {
+        Some(Ok(seconds)) => format_timestamp(seconds),
+        Some(Err(_)) | None => "unknown".to_string(),
+    }
+}
+
+/// RFC 3339 UTC time i.e. `2024-06-01T12:00:00Z`
+pub(crate) fn format_timestamp(timestamp: u64) -> String {
+    let (days, seconds) = (timestamp / 86_400, timestamp % 86_400);
+    // Civil date from days since the epoch: https://howardhinnant.github.io/date_algorithms.html
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        seconds / 3_600,
+        seconds % 3_600 / 60,
+        seconds % 60
+    )
+}
+
+fn current_seconds() -> i64 {
+    SystemTime::now().unix_seconds().unwrap_or(i64::MAX)
+}
+
+/// Seconds between the timestamp and now, `None` for times in the future
+fn age<T: Timestamp + ?Sized>(created: &T, now: i64) -> Option<u64> {
+    created
+        .unix_seconds()
+        .and_then(|created| u64::try_from(now.checked_sub(created)?).ok())
+}
+
+fn message(name: &str, age: Option<u64>, limit_seconds: u64) -> String {
+    let limit = format_duration(limit_seconds);
+    match age {
+        Some(age) => format!(
+            "{name} expired (created {} ago, limit {limit})",
+            format_duration(age)
+        ),
+        None => format!("{name} expired (limit {limit})"),
+    }
+}
+
+/// The largest whole unit i.e. `45 days` or `1 hour`
+fn format_duration(seconds: u64) -> String {
+    let (count, unit) = [(86_400, "day"), (3_600, "hour"), (60, "minute")]
+        .into_iter()
+        .find(|(size, _)| seconds >= *size)
+        .map_or((seconds, "second"), |(size, unit)| (seconds / size, unit));
+    match count {
+        1 => format!("1 {unit}"),
+        _ => format!("{count} {unit}s"),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_timestamp(1_717_243_200), "2024-06-01T12:00:00Z");
+        assert_eq!(format_timestamp(951_782_400), "2000-02-29T00:00:00Z");
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(0), "0 seconds");
+        assert_eq!(format_duration(1), "1 second");
+        assert_eq!(format_duration(90 * 60), "1 hour");
+        assert_eq!(format_duration(30 * 86_400), "30 days");
+    }
+
+    #[test]
+    fn test_age() {
+        assert_eq!(age(&100_u64, 160), Some(60));
+        assert_eq!(age(&200_i64, 160), None);
+        assert_eq!(
+            age(&(UNIX_EPOCH + std::time::Duration::from_secs(10)), 15),
+            Some(5)
+        );
+    }
+
+    #[test]
+    fn test_message() {
+        assert_eq!(
+            message("created at", Some(45 * 86_400 + 10), 30 * 86_400),
+            "created at expired (created 45 days ago, limit 30 days)"
+        );
+        assert_eq!(
+            message("Last checked", None, 3_600),
+            "Last checked expired (limit 1 hour)"
+        );
+    }
+}
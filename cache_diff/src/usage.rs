@@ -0,0 +1,130 @@
+//! Invalidate a cache after it has been reused a number of times with `#[cache_diff(max_uses = N)]`
+
+/// A counter stored in a field marked `#[cache_diff(max_uses = N)]`
+///
+/// Implemented for the primitive integer and float types. The stored count is the number of builds
+/// that reused the cache, once it reaches the limit the next build invalidates it. Call
+/// [CacheDiff::record_reuse](crate::CacheDiff::record_reuse) when the cache is kept to carry the
+/// count forward:
+///
+/// ```rust
+/// use cache_diff::CacheDiff;
+///
+/// #[derive(CacheDiff)]
+/// struct Metadata {
+///     version: String,
+///     #[cache_diff(max_uses = 2)]
+///     cache_usage_count: u32,
+/// }
+/// let old = Metadata { version: "3.4.0".to_string(), cache_usage_count: 1 };
+/// let mut now = Metadata { version: "3.4.0".to_string(), cache_usage_count: 0 };
+///
+/// assert!(now.diff(&old).is_empty());
+/// now.record_reuse(&old);
+/// assert_eq!(now.cache_usage_count, 2);
+///
+/// let old = now;
+/// let now = Metadata { version: "3.4.0".to_string(), cache_usage_count: 0 };
+/// assert_eq!(
+///     now.diff(&old),
+///     vec!["cache usage count reached its limit (used 2 times, limit 2)".to_string()]
+/// );
+/// ```
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` cannot be used with `#[cache_diff(max_uses = N)]`",
+    label = "not a counter",
+    note = "use an integer or float to count uses, or implement `cache_diff::UsageCount` for `{Self}`"
+)]
+pub trait UsageCount {
+    /// The number of recorded uses
+    fn uses(&self) -> u64;
+
+    /// The count after one more use
+    fn incremented(&self) -> Self;
+}
+
+macro_rules! usage_count_int {
+    ($($int:ty),*) => {
+        $(impl UsageCount for $int {
+            fn uses(&self) -> u64 {
+                u64::try_from(*self).unwrap_or(0)
+            }
+
+            fn incremented(&self) -> Self {
+                self.saturating_add(1)
+            }
+        })*
+    };
+}
+
+usage_count_int!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+macro_rules! usage_count_float {
+    ($($float:ty),*) => {
+        $(impl UsageCount for $float {
+            fn uses(&self) -> u64 {
+                // Saturates, negative and NaN counts are zero
+                *self as u64
+            }
+
+            fn incremented(&self) -> Self {
+                self + 1.0
+            }
+        })*
+    };
+}
+
+usage_count_float!(f32, f64);
+
+/// Used by generated code, returns `true` when the old cache was used at least `limit` times
+#[doc(hidden)]
+pub fn uses_reached<T: UsageCount + ?Sized>(count: &T, limit: u64) -> bool {
+    count.uses() >= limit
+}
+
+/// Used by generated code to describe a counter that reached its limit
+#[doc(hidden)]
+pub fn uses_message<T: UsageCount + ?Sized>(name: &str, count: &T, limit: u64) -> String {
+    format!(
+        "{name} reached its limit (used {} times, limit {limit})",
+        count.uses()
+    )
+}
+
+/// Used by generated code to continue counting from the old cache
+#[doc(hidden)]
+pub fn increment_uses<T: UsageCount>(count: &mut T, old: &T) {
+    *count = old.incremented();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_counts() {
+        assert_eq!(3_u8.incremented(), 4);
+        assert_eq!(u8::MAX.incremented(), u8::MAX);
+        assert_eq!((-1_i32).uses(), 0);
+        assert_eq!(2.5_f32.incremented(), 3.5);
+        assert_eq!(f64::NAN.uses(), 0);
+    }
+
+    #[test]
+    fn test_uses_reached() {
+        assert!(!uses_reached(&199_usize, 200));
+        assert!(uses_reached(&200_usize, 200));
+        assert!(uses_reached(&200.0_f32, 200));
+        assert_eq!(
+            uses_message("cache usage count", &205_u32, 200),
+            "cache usage count reached its limit (used 205 times, limit 200)"
+        );
+    }
+
+    #[test]
+    fn test_increment_uses() {
+        let mut count = 0_u32;
+        increment_uses(&mut count, &41);
+        assert_eq!(count, 42);
+    }
+}
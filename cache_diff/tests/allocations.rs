@@ -0,0 +1,70 @@
+//! Diffing identical structs must not touch the heap, this is the common warm build case
+use cache_diff::CacheDiff;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn allocations(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.with(Cell::get);
+    f();
+    ALLOCATIONS.with(Cell::get) - before
+}
+
+#[derive(CacheDiff)]
+#[cache_diff(custom = diff_usage)]
+struct Metadata {
+    #[cache_diff(rename = "Ruby version", category = "Runtime")]
+    version: String,
+    distro: String,
+    #[cache_diff(ignore = "custom")]
+    usage: u32,
+}
+
+fn diff_usage(old: &Metadata, now: &Metadata) -> Vec<String> {
+    if now.usage > 200 && old.usage <= 200 {
+        vec!["Usage exceeded".to_string()]
+    } else {
+        Vec::new()
+    }
+}
+
+#[test]
+fn test_unchanged_does_not_allocate() {
+    let now = Metadata {
+        version: "3.4.0".to_string(),
+        distro: "Ubuntu".to_string(),
+        usage: 1,
+    };
+    let old = Metadata {
+        version: "3.4.0".to_string(),
+        distro: "Ubuntu".to_string(),
+        usage: 1,
+    };
+
+    assert_eq!(allocations(|| assert!(now.diff(&old).is_empty())), 0);
+    assert_eq!(
+        allocations(|| assert!(now.diff_structured(&old).is_empty())),
+        0
+    );
+    assert_eq!(allocations(|| assert!(now.changes(&old).is_none())), 0);
+    assert_eq!(allocations(|| assert!(!now.differs(&old))), 0);
+}
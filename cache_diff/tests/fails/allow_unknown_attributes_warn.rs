@@ -0,0 +1,17 @@
+#![deny(deprecated)]
+use cache_diff::CacheDiff;
+
+#[derive(CacheDiff)]
+#[cache_diff(allow_unknown_attributes = "warn", from_the_future)]
+struct Metadata {
+    #[cache_diff(compare_with = some::function)]
+    version: String,
+}
+
+#[derive(CacheDiff)]
+#[cache_diff(allow_unknown_attributes = "loud")]
+struct Invalid {
+    version: String,
+}
+
+fn main() {}
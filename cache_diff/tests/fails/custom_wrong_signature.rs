@@ -0,0 +1,15 @@
+use cache_diff::CacheDiff;
+
+#[derive(CacheDiff)]
+#[cache_diff(custom = diff_usage)]
+struct Metadata {
+    version: String,
+    #[cache_diff(ignore = "custom")]
+    usage: u32,
+}
+
+fn diff_usage(old: &Metadata, now: &Metadata) -> bool {
+    old.usage != now.usage
+}
+
+fn main() {}
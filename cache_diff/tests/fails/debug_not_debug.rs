@@ -0,0 +1,12 @@
+use cache_diff::CacheDiff;
+
+#[derive(PartialEq)]
+struct Arch;
+
+#[derive(CacheDiff)]
+struct Metadata {
+    #[cache_diff(debug)]
+    arch: Arch,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use cache_diff::CacheDiff;
+
+#[derive(CacheDiff)]
+#[cache_diff(deny_float_eq)]
+struct Metadata {
+    version: String,
+    cpu_ratio: f32,
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use cache_diff::CacheDiff;
+
+fn pair(old: &u32, now: &u32) -> u32 {
+    now - old
+}
+
+#[derive(CacheDiff)]
+struct Example {
+    #[cache_diff(display_pair = pair)]
+    count: u32,
+}
+
+fn main() {}
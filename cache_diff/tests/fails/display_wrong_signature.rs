@@ -0,0 +1,21 @@
+use cache_diff::CacheDiff;
+
+struct NotDisplay;
+
+#[derive(CacheDiff)]
+struct Metadata {
+    #[cache_diff(display = wrong_argument)]
+    version: String,
+    #[cache_diff(display = not_display)]
+    distro: String,
+}
+
+fn wrong_argument(value: &u32) -> String {
+    value.to_string()
+}
+
+fn not_display(_value: &String) -> NotDisplay {
+    NotDisplay
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use cache_diff::CacheDiff;
+
+#[derive(CacheDiff)]
+struct Metadata {
+    #[cache_diff(rename = "Ruby version")]
+    #[cache_diff(rename = "oops")]
+    version: String,
+    #[cache_diff(ignore, category = "Runtime")]
+    distro: String,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use cache_diff::CacheDiff;
+
+#[derive(CacheDiff)]
+struct Metadata {
+    #[cache_diff(rename = "version")]
+    ruby_version: String,
+    version: String,
+}
+
+fn main() {}
@@ -0,0 +1,17 @@
+use cache_diff::CacheDiff;
+
+#[derive(CacheDiff)]
+struct Metadata {
+    #[cache_diff(empty_is_none, compare = same_url)]
+    url: Option<String>,
+    #[cache_diff(empty_is_none, ttl = "30d")]
+    created_at: u64,
+    #[cache_diff(empty_is_none, semver = "minor")]
+    node_version: String,
+}
+
+fn same_url(old: &Option<String>, now: &Option<String>) -> bool {
+    old == now
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use cache_diff::CacheDiff;
+
+#[derive(CacheDiff)]
+struct Metadata {
+    #[cache_diff(custom = diff_version)]
+    version: String,
+}
+
+fn diff_version(old: &String, now: &String) -> bool {
+    old != now
+}
+
+fn main() {}
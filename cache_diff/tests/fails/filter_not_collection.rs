@@ -0,0 +1,13 @@
+use cache_diff::CacheDiff;
+
+fn keep(_: &String) -> bool {
+    true
+}
+
+#[derive(CacheDiff)]
+struct Metadata {
+    #[cache_diff(filter = keep)]
+    version: String,
+}
+
+fn main() {}
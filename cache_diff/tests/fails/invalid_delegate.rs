@@ -0,0 +1,13 @@
+use cache_diff::CacheDiff;
+
+struct Versioned<T> {
+    inner: T,
+}
+
+#[derive(CacheDiff)]
+struct Example {
+    #[cache_diff(delegate = "inner + 1")]
+    version: Versioned<u32>,
+}
+
+fn main() {}
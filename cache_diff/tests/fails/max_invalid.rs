@@ -0,0 +1,11 @@
+use cache_diff::CacheDiff;
+
+#[derive(CacheDiff)]
+struct Metadata {
+    #[cache_diff(max = "200")]
+    cache_usage_count: usize,
+    #[cache_diff(max = 200, display_pair = pair)]
+    build_count: usize,
+}
+
+fn main() {}
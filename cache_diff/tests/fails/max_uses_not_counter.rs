@@ -0,0 +1,9 @@
+use cache_diff::CacheDiff;
+
+#[derive(CacheDiff)]
+struct Metadata {
+    #[cache_diff(max_uses = 200)]
+    cache_usage_count: String,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use cache_diff::CacheDiff;
+
+#[derive(CacheDiff)]
+struct Metadata {
+    #[cache_diff(max = 200)]
+    ratio: f32,
+}
+
+fn main() {}
@@ -0,0 +1,17 @@
+use cache_diff::CacheDiff;
+
+#[derive(Debug)]
+struct NotPartialEq(String);
+
+impl std::fmt::Display for NotPartialEq {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(CacheDiff)]
+struct Metadata {
+    version: NotPartialEq,
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use cache_diff::CacheDiff;
+
+#[derive(CacheDiff)]
+struct Metadata {
+    #[cache_diff(renmae = "Ruby version")]
+    version: String,
+    #[cache_diff(ignore = "custom")]
+    distro: String,
+    #[cache_diff(rename = 1)]
+    arch: String,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use cache_diff::CacheDiff;
+
+#[derive(CacheDiff)]
+struct Metadata {
+    #[cache_diff(renmae = "Ruby version", categroy = "Runtime")]
+    version: String,
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+use cache_diff::CacheDiff;
+
+#[derive(PartialEq)]
+struct Ruby {
+    version: String,
+}
+
+#[derive(CacheDiff)]
+struct Metadata {
+    #[cache_diff(nested)]
+    ruby: Ruby,
+}
+
+fn main() {}
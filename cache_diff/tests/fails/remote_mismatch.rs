@@ -0,0 +1,17 @@
+use cache_diff::CacheDiff;
+
+mod other {
+    pub struct Ruby {
+        pub version: String,
+        pub distro: String,
+    }
+}
+
+#[derive(CacheDiff)]
+#[cache_diff(remote = "other::Ruby")]
+struct RubyDiff {
+    version: u32,
+    os: String,
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use cache_diff::CacheDiff;
+
+#[derive(CacheDiff)]
+struct Metadata {
+    #[cache_diff(skip_if = is_latest)]
+    version: String,
+}
+
+fn is_latest(version: &str) -> bool {
+    version == "latest"
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use cache_diff::CacheDiff;
+use std::time::SystemTime;
+
+#[derive(CacheDiff)]
+struct Metadata {
+    #[cache_diff(ttl = "30 days")]
+    created_at: SystemTime,
+    #[cache_diff(ttl = "1d", display_pair = pair)]
+    checked_at: SystemTime,
+}
+
+fn main() {}
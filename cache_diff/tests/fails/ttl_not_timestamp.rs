@@ -0,0 +1,9 @@
+use cache_diff::CacheDiff;
+
+#[derive(CacheDiff)]
+struct Metadata {
+    #[cache_diff(ttl = "1d")]
+    created_at: String,
+}
+
+fn main() {}
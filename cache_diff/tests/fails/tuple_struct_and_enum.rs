@@ -0,0 +1,12 @@
+use cache_diff::CacheDiff;
+
+#[derive(CacheDiff)]
+struct Metadata(String);
+
+#[derive(CacheDiff)]
+enum Distro {
+    Ubuntu,
+    Alpine,
+}
+
+fn main() {}
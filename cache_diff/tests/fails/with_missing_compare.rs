@@ -0,0 +1,15 @@
+use cache_diff::CacheDiff;
+
+mod versions {
+    pub(crate) fn display(version: &String) -> String {
+        format!("v{version}")
+    }
+}
+
+#[derive(CacheDiff)]
+struct Metadata {
+    #[cache_diff(with = versions)]
+    version: String,
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+use cache_diff::CacheDiff;
+
+#[derive(CacheDiff)]
+struct Metadata {
+    #[cache_diff(alias = "ruby_version")]
+    version: String,
+    #[cache_diff(alias = "os")]
+    distro: String,
+}
+
+fn main() {
+    assert_eq!(
+        Metadata::FIELD_ALIASES,
+        &[("ruby_version", "version"), ("os", "distro")]
+    );
+}
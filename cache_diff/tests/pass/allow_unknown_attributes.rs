@@ -0,0 +1,33 @@
+use cache_diff::CacheDiff;
+
+#[derive(CacheDiff)]
+#[cache_diff(allow_unknown_attributes, from_the_future, style = cache_diff::DiffStyle::Backticks)]
+struct Example {
+    #[cache_diff(rename = "Ruby version", compare_with = some::function, renamed(to = "x"))]
+    version: String,
+    #[cache_diff(ignore, future_flag)]
+    changed_by: String,
+}
+
+#[derive(CacheDiff)]
+#[cache_diff(allow_unknown_attributes = "warn", style = cache_diff::DiffStyle::Backticks)]
+#[allow(deprecated)]
+struct Warned {
+    #[cache_diff(future_flag)]
+    version: String,
+}
+
+fn main() {
+    let now = Example {
+        version: "3.4.0".to_string(),
+        changed_by: "CI".to_string(),
+    };
+    let old = Example {
+        version: "3.3.0".to_string(),
+        changed_by: "Schneems".to_string(),
+    };
+    assert_eq!(now.diff(&old), vec!["Ruby version (`3.3.0` to `3.4.0`)"]);
+
+    let now = Warned { version: "3.4.0".to_string() };
+    assert!(now.diff(&Warned { version: "3.4.0".to_string() }).is_empty());
+}
@@ -0,0 +1,32 @@
+use cache_diff::CacheDiff;
+
+#[derive(CacheDiff)]
+#[cache_diff(deny_float_eq, style = cache_diff::DiffStyle::Backticks)]
+struct Metadata {
+    #[cache_diff(compare = same_minor)]
+    version: String,
+    #[cache_diff(compare = close_enough, rename = "CPU load")]
+    load: f64,
+}
+
+fn same_minor(old: &String, now: &String) -> bool {
+    let minor = |version: &str| version.rsplit_once('.').map(|(minor, _)| minor.to_string());
+    minor(old) == minor(now)
+}
+
+fn close_enough(old: &f64, now: &f64) -> bool {
+    (old - now).abs() < 0.01
+}
+
+fn main() {
+    let metadata = |version: &str, load: f64| Metadata {
+        version: version.to_string(),
+        load,
+    };
+    let old = metadata("3.4.0", 0.5);
+    assert!(metadata("3.4.1", 0.501).diff(&old).is_empty());
+    assert_eq!(
+        metadata("3.5.0", 0.7).diff(&old),
+        vec!["version (`3.4.0` to `3.5.0`)", "CPU load (`0.5` to `0.7`)"]
+    );
+}
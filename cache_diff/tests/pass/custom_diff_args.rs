@@ -0,0 +1,45 @@
+use cache_diff::{CacheDiff, DiffArgs};
+
+#[derive(CacheDiff)]
+#[cache_diff(custom = diff_usage, custom = diff_name)]
+struct Metadata {
+    version: String,
+    #[cache_diff(ignore = "custom")]
+    usage: u32,
+    #[cache_diff(ignore = "custom")]
+    name: String,
+}
+
+fn diff_usage(DiffArgs { old, now }: DiffArgs<Metadata>) -> Vec<String> {
+    if now.usage > old.usage {
+        vec![format!("usage ({} to {})", old.usage, now.usage)]
+    } else {
+        Vec::new()
+    }
+}
+
+fn diff_name(old: &Metadata, now: &Metadata) -> Vec<String> {
+    if old.name != now.name {
+        vec![format!("name ({} to {})", old.name, now.name)]
+    } else {
+        Vec::new()
+    }
+}
+
+fn main() {
+    let old = Metadata {
+        version: "3.4.0".to_string(),
+        usage: 1,
+        name: "ruby".to_string(),
+    };
+    let now = Metadata {
+        version: "3.4.0".to_string(),
+        usage: 2,
+        name: "jruby".to_string(),
+    };
+    assert_eq!(
+        now.diff(&old),
+        vec!["usage (1 to 2)".to_string(), "name (ruby to jruby)".to_string()]
+    );
+    assert!(now.diff(&now).is_empty());
+}
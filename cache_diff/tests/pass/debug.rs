@@ -0,0 +1,34 @@
+use cache_diff::CacheDiff;
+
+#[derive(Debug, PartialEq)]
+enum Arch {
+    Amd64,
+    Arm64,
+}
+
+#[derive(CacheDiff)]
+#[cache_diff(style = cache_diff::DiffStyle::Backticks)]
+struct Metadata {
+    #[cache_diff(debug)]
+    arch: Arch,
+    #[cache_diff(debug, rename = "features")]
+    yjit: Option<bool>,
+}
+
+fn main() {
+    let old = Metadata {
+        arch: Arch::Amd64,
+        yjit: None,
+    };
+    let now = Metadata {
+        arch: Arch::Arm64,
+        yjit: Some(true),
+    };
+    assert_eq!(
+        now.diff(&old),
+        vec![
+            "arch (`Amd64` to `Arm64`)",
+            "features (`None` to `Some(true)`)",
+        ]
+    );
+}
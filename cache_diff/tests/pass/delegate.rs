@@ -0,0 +1,46 @@
+use cache_diff::CacheDiff;
+use std::path::PathBuf;
+
+#[derive(Hash)]
+struct Versioned<T> {
+    inner: T,
+    #[allow(dead_code)]
+    source: &'static str,
+}
+
+impl<T> Versioned<T> {
+    fn get(&self) -> &T {
+        &self.inner
+    }
+}
+
+#[derive(CacheDiff)]
+#[cache_diff(content_hash, style = cache_diff::DiffStyle::Backticks)]
+struct Example {
+    #[cache_diff(delegate = "inner")]
+    version: Versioned<String>,
+    #[cache_diff(delegate = "get()", display = std::path::Path::display)]
+    path: Versioned<PathBuf>,
+}
+
+fn main() {
+    let example = |version: &str, source| Example {
+        version: Versioned {
+            inner: version.to_string(),
+            source,
+        },
+        path: Versioned {
+            inner: PathBuf::from("/layers/ruby"),
+            source,
+        },
+    };
+    assert!(example("3.4.0", "a").diff(&example("3.4.0", "b")).is_empty());
+    assert_eq!(
+        example("3.4.0", "a").diff(&example("3.3.0", "a")),
+        vec!["version (`3.3.0` to `3.4.0`)"]
+    );
+    assert_eq!(
+        example("3.4.0", "a").content_hash(),
+        example("3.4.0", "b").content_hash()
+    );
+}
@@ -0,0 +1,21 @@
+use cache_diff::CacheDiff;
+
+#[derive(CacheDiff)]
+#[cache_diff(deny_float_eq, style = cache_diff::DiffStyle::Backticks)]
+struct Metadata {
+    version: String,
+    #[cache_diff(delegate = "round()")]
+    cpu_ratio: f64,
+}
+
+fn main() {
+    let metadata = |cpu_ratio| Metadata {
+        version: "3.4.0".to_string(),
+        cpu_ratio,
+    };
+    assert!(metadata(1.2).diff(&metadata(1.4)).is_empty());
+    assert_eq!(
+        metadata(2.1).diff(&metadata(1.4)),
+        vec!["cpu ratio (`1` to `2`)"]
+    );
+}
@@ -0,0 +1,33 @@
+use cache_diff::CacheDiff;
+
+fn as_phrase(old: &u32, now: &u32) -> String {
+    format!("Count went from {old} to {now}")
+}
+
+fn as_values(old: &String, now: &String) -> (&'static str, String) {
+    ("previous", format!("{old} => {now}"))
+}
+
+#[derive(CacheDiff)]
+#[cache_diff(style = cache_diff::DiffStyle::Backticks)]
+struct Example {
+    #[cache_diff(display_pair = as_phrase)]
+    count: u32,
+    #[cache_diff(display_pair = as_values)]
+    name: String,
+}
+
+fn main() {
+    let diff = Example {
+        count: 2,
+        name: "b".to_string(),
+    }
+    .diff(&Example {
+        count: 1,
+        name: "a".to_string(),
+    });
+    assert_eq!(
+        diff,
+        vec!["Count went from 1 to 2", "name (`previous` to `a => b`)"]
+    );
+}
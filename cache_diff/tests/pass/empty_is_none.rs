@@ -0,0 +1,31 @@
+use cache_diff::CacheDiff;
+
+fn or_none(value: &Option<String>) -> String {
+    value.clone().unwrap_or_else(|| "none".to_string())
+}
+
+#[derive(CacheDiff)]
+#[cache_diff(content_hash, style = cache_diff::DiffStyle::Backticks)]
+struct Example {
+    #[cache_diff(empty_is_none, display = or_none)]
+    mirror: Option<String>,
+    #[cache_diff(empty_is_none)]
+    version: String,
+}
+
+fn main() {
+    let example = |mirror: Option<&str>, version: &str| Example {
+        mirror: mirror.map(str::to_string),
+        version: version.to_string(),
+    };
+    let now = example(None, "3.4.0");
+    assert!(now.diff(&example(Some(""), "3.4.0")).is_empty());
+    assert_eq!(now.content_hash(), example(Some(""), "3.4.0").content_hash());
+    assert_eq!(
+        now.diff(&example(Some("https://example.com"), "")),
+        vec![
+            "mirror (`https://example.com` to `none`)",
+            "version (`` to `3.4.0`)",
+        ]
+    );
+}
@@ -0,0 +1,69 @@
+use cache_diff::{CacheDiff, RedactionPolicy};
+
+#[derive(CacheDiff)]
+#[cache_diff(style = cache_diff::DiffStyle::Backticks)]
+struct Metadata {
+    #[cache_diff(custom = diff_version, category = "Runtime")]
+    version: String,
+    #[cache_diff(custom = diff_gems, display = gem_count)]
+    gems: Vec<String>,
+    distro: String,
+}
+
+#[derive(CacheDiff)]
+#[cache_diff(style = cache_diff::DiffStyle::Backticks)]
+struct Credentials {
+    #[cache_diff(custom = diff_token)]
+    api_token: String,
+}
+
+fn diff_token(old: &String, now: &String) -> Option<String> {
+    (old != now).then(|| format!("Token rotated from {old} to {now}"))
+}
+
+fn diff_version(old: &String, now: &String) -> Option<String> {
+    (old != now).then(|| format!("Ruby version changed from {old} to {now}"))
+}
+
+fn diff_gems(old: &Vec<String>, now: &Vec<String>) -> Vec<String> {
+    now.iter()
+        .filter(|gem| !old.contains(gem))
+        .map(|gem| format!("Gem {gem} added"))
+        .collect()
+}
+
+fn gem_count(gems: &Vec<String>) -> usize {
+    gems.len()
+}
+
+fn main() {
+    let metadata = |version: &str, gems: &[&str], distro: &str| Metadata {
+        version: version.to_string(),
+        gems: gems.iter().map(ToString::to_string).collect(),
+        distro: distro.to_string(),
+    };
+    let old = metadata("3.3.0", &["rake"], "Ubuntu");
+    let now = metadata("3.4.0", &["rake", "rails"], "Alpine");
+    assert_eq!(
+        now.diff(&old),
+        vec![
+            "Ruby version changed from 3.3.0 to 3.4.0",
+            "Gem rails added",
+            "distro (`Ubuntu` to `Alpine`)",
+        ]
+    );
+    let structured = now.diff_structured(&old);
+    assert_eq!(structured[0].identifier(), Some("version"));
+    assert_eq!(structured[0].category(), Some("Runtime"));
+    assert!(now.differs(&old));
+    assert!(!now.differs(&metadata("3.4.0", &["rake", "rails"], "Alpine")));
+
+    RedactionPolicy::new().pattern("*token*").install();
+    let credentials = |token: &str| Credentials {
+        api_token: token.to_string(),
+    };
+    assert_eq!(
+        credentials("secret").diff(&credentials("hunter2")),
+        vec!["api token (`[REDACTED]` to `[REDACTED]`)"]
+    );
+}
@@ -0,0 +1,50 @@
+use cache_diff::CacheDiff;
+use std::collections::BTreeMap;
+
+fn runtime(gem: &String) -> bool {
+    !gem.starts_with("dev:")
+}
+
+fn no_build_id(key: &String, _value: &String) -> bool {
+    key != "BUILD_ID"
+}
+
+fn list(gems: &Vec<String>) -> String {
+    gems.join(", ")
+}
+
+fn env(env: &BTreeMap<String, String>) -> String {
+    env.iter()
+        .map(|(key, value)| format!("{key}={value}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[derive(CacheDiff)]
+#[cache_diff(content_hash, style = cache_diff::DiffStyle::Backticks)]
+struct Example {
+    #[cache_diff(filter = runtime, display = list)]
+    gems: Vec<String>,
+    #[cache_diff(filter = no_build_id, display = env)]
+    env: BTreeMap<String, String>,
+}
+
+fn main() {
+    let example = |gems: &[&str], env: &[(&str, &str)]| Example {
+        gems: gems.iter().map(|gem| gem.to_string()).collect(),
+        env: env
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect(),
+    };
+    let now = example(&["rails", "dev:rspec"], &[("PATH", "/bin"), ("BUILD_ID", "2")]);
+    let old = example(&["rails", "dev:minitest"], &[("PATH", "/bin"), ("BUILD_ID", "1")]);
+    assert!(now.diff(&old).is_empty());
+    assert_eq!(now.content_hash(), old.content_hash());
+
+    let old = example(&["rails", "puma"], &[("PATH", "/usr/bin"), ("BUILD_ID", "1")]);
+    assert_eq!(
+        now.diff(&old),
+        vec!["gems (`rails, puma` to `rails`)", "env (`PATH=/usr/bin` to `PATH=/bin`)"]
+    );
+}
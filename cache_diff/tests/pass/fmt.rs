@@ -0,0 +1,27 @@
+use cache_diff::CacheDiff;
+
+#[derive(CacheDiff)]
+#[cache_diff(template = "{name}: {old} => {new}", style = cache_diff::DiffStyle::Backticks)]
+struct Metadata {
+    #[cache_diff(fmt = "{name} changed from {old} to {new}", rename = "Stack")]
+    stack: String,
+    version: String,
+}
+
+fn main() {
+    let old = Metadata {
+        stack: "heroku-22".to_string(),
+        version: "3.3.0".to_string(),
+    };
+    let now = Metadata {
+        stack: "heroku-24".to_string(),
+        version: "3.4.0".to_string(),
+    };
+    assert_eq!(
+        now.diff(&old),
+        vec![
+            "Stack changed from `heroku-22` to `heroku-24`",
+            "version: `3.3.0` => `3.4.0`",
+        ]
+    );
+}
@@ -0,0 +1,32 @@
+use cache_diff::CacheDiff;
+
+#[derive(CacheDiff)]
+#[cache_diff(style = cache_diff::DiffStyle::Backticks)]
+struct Metadata {
+    #[cache_diff(group = "OS")]
+    os_distribution: String,
+    ruby_version: String,
+    #[cache_diff(group = "OS", code = "os_version")]
+    os_version: String,
+}
+
+fn main() {
+    let metadata = |os_version: &str, ruby_version: &str| Metadata {
+        os_distribution: "ubuntu".to_string(),
+        ruby_version: ruby_version.to_string(),
+        os_version: os_version.to_string(),
+    };
+    let old = metadata("22.04", "3.3.0");
+    assert_eq!(
+        metadata("24.04", "3.4.0").diff(&old),
+        vec![
+            "OS (`ubuntu-22.04` to `ubuntu-24.04`)",
+            "ruby version (`3.3.0` to `3.4.0`)"
+        ]
+    );
+    assert!(metadata("24.04", "3.3.0").differs(&old));
+    let differences = metadata("24.04", "3.3.0").diff_structured(&old);
+    assert_eq!(differences[0].identifier(), Some("OS"));
+    assert_eq!(differences[0].code(), Some("os_version"));
+    assert!(metadata("22.04", "3.3.0").diff(&old).is_empty());
+}
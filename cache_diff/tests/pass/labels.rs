@@ -0,0 +1,31 @@
+use cache_diff::CacheDiff;
+
+#[derive(CacheDiff)]
+#[cache_diff(style = cache_diff::DiffStyle::Backticks)]
+struct Metadata {
+    #[cache_diff(labels = "was/now")]
+    stack: String,
+    version: String,
+}
+
+fn main() {
+    let now = Metadata {
+        stack: "heroku-24".to_string(),
+        version: "3.4.0".to_string(),
+    };
+    let old = Metadata {
+        stack: "heroku-22".to_string(),
+        version: "3.3.0".to_string(),
+    };
+    assert_eq!(
+        now.diff(&old),
+        vec![
+            "stack (was `heroku-22`, now `heroku-24`)",
+            "version (`3.3.0` to `3.4.0`)"
+        ]
+    );
+    assert_eq!(
+        now.diff_structured(&old)[0].labels(),
+        Some(("was", "now"))
+    );
+}
@@ -0,0 +1,35 @@
+use cache_diff::CacheDiff;
+
+#[derive(CacheDiff)]
+#[cache_diff(content_hash)]
+struct Example {
+    version: String,
+    #[cache_diff(max = 200, rename = "Cache count")]
+    cache_usage_count: usize,
+    #[cache_diff(max = 1.5)]
+    ratio: f64,
+}
+
+fn main() {
+    let example = |cache_usage_count, ratio| Example {
+        version: "3.4.0".to_string(),
+        cache_usage_count,
+        ratio,
+    };
+    let now = example(200, 1.5);
+    assert!(!now.differs(&example(199, 0.1)));
+    assert!(now.diff(&example(199, 0.1)).is_empty());
+    assert_eq!(now.content_hash(), example(1, 0.1).content_hash());
+    assert!(!now.differs_from_hash(&example(1, 0.1), example(1, 0.1).content_hash()));
+
+    let now = example(205, 2.0);
+    assert!(now.differs(&example(204, 2.0)));
+    assert!(now.differs_from_hash(&example(204, 2.0), example(204, 2.0).content_hash()));
+    assert_eq!(
+        now.diff(&example(204, 2.0)),
+        vec![
+            "Cache count (205) exceeded limit 200",
+            "ratio (2) exceeded limit 1.5",
+        ]
+    );
+}
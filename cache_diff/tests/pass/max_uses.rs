@@ -0,0 +1,40 @@
+use cache_diff::CacheDiff;
+
+#[derive(CacheDiff)]
+#[cache_diff(content_hash)]
+struct Example {
+    version: String,
+    #[cache_diff(max_uses = 3, rename = "Cache")]
+    cache_usage_count: f32,
+    #[cache_diff(max_uses = 10)]
+    builds: u8,
+}
+
+fn main() {
+    let example = |cache_usage_count, builds| Example {
+        version: "3.4.0".to_string(),
+        cache_usage_count,
+        builds,
+    };
+    let mut now = example(0.0, 0);
+    let old = example(2.0, 9);
+    assert!(!now.differs(&old));
+    assert!(now.diff(&old).is_empty());
+    assert_eq!(now.content_hash(), old.content_hash());
+    assert!(!now.differs_from_hash(&old, old.content_hash()));
+
+    now.record_reuse(&old);
+    assert_eq!((now.cache_usage_count, now.builds), (3.0, 10));
+
+    let old = now;
+    let now = example(0.0, 0);
+    assert!(now.differs(&old));
+    assert!(now.differs_from_hash(&old, old.content_hash()));
+    assert_eq!(
+        now.diff(&old),
+        vec![
+            "Cache reached its limit (used 3 times, limit 3)",
+            "builds reached its limit (used 10 times, limit 10)",
+        ]
+    );
+}
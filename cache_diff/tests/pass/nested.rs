@@ -0,0 +1,53 @@
+use cache_diff::CacheDiff;
+
+#[derive(CacheDiff)]
+#[cache_diff(style = cache_diff::DiffStyle::Backticks)]
+struct Ruby {
+    version: String,
+    #[cache_diff(rename = "Bundler")]
+    bundler_version: String,
+}
+
+#[derive(CacheDiff)]
+#[cache_diff(style = cache_diff::DiffStyle::Backticks)]
+struct Metadata {
+    #[cache_diff(nested, category = "Runtime")]
+    ruby: Ruby,
+    distro: String,
+}
+
+fn main() {
+    let now = Metadata {
+        ruby: Ruby {
+            version: "3.4.0".to_string(),
+            bundler_version: "2.5.0".to_string(),
+        },
+        distro: "Ubuntu".to_string(),
+    };
+    let old = Metadata {
+        ruby: Ruby {
+            version: "3.3.0".to_string(),
+            bundler_version: "2.5.0".to_string(),
+        },
+        distro: "Alpine".to_string(),
+    };
+    assert!(now.differs(&old));
+    assert_eq!(
+        now.diff(&old),
+        vec!["version (`3.3.0` to `3.4.0`)", "distro (`Alpine` to `Ubuntu`)"]
+    );
+    let paths = now
+        .diff_paths(&old)
+        .into_iter()
+        .map(|(path, _)| path.join("."))
+        .collect::<Vec<_>>();
+    assert_eq!(paths, vec!["ruby.version", "distro"]);
+    assert_eq!(now.diff_structured(&old)[0].category(), Some("Runtime"));
+    assert_eq!(
+        now.initial_message(),
+        vec![
+            "ruby `version `3.4.0`, Bundler `2.5.0``",
+            "distro `Ubuntu`"
+        ]
+    );
+}
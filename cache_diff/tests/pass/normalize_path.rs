@@ -0,0 +1,29 @@
+use cache_diff::CacheDiff;
+use std::path::PathBuf;
+
+#[derive(CacheDiff)]
+#[cache_diff(style = cache_diff::DiffStyle::Backticks)]
+struct Metadata {
+    #[cache_diff(normalize_path = "/layers/ruby")]
+    install_dir: PathBuf,
+    #[cache_diff(normalize_path)]
+    gem_home: String,
+}
+
+fn main() {
+    let old = Metadata {
+        install_dir: PathBuf::from("/layers/ruby/3.3.0"),
+        gem_home: r"C:\gems\3.3.0".to_string(),
+    };
+    let now = Metadata {
+        install_dir: PathBuf::from("/layers/ruby/3.4.0"),
+        gem_home: r"C:\gems\3.4.0".to_string(),
+    };
+    assert_eq!(
+        now.diff(&old),
+        vec![
+            "install dir (`3.3.0` to `3.4.0`)".to_string(),
+            "gem home (`C:/gems/3.3.0` to `C:/gems/3.4.0`)".to_string(),
+        ]
+    );
+}
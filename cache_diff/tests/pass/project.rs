@@ -0,0 +1,44 @@
+use cache_diff::CacheDiff;
+use std::path::{Path, PathBuf};
+
+fn major_minor(version: &String) -> String {
+    version.rsplit_once('.').map_or(version.clone(), |(major_minor, _)| major_minor.to_string())
+}
+
+fn file_name(path: &PathBuf) -> String {
+    path.file_name().map_or_else(String::new, |name| name.to_string_lossy().to_string())
+}
+
+fn rounded(ratio: &f64) -> i64 {
+    (ratio * 100.0).round() as i64
+}
+
+#[derive(CacheDiff)]
+#[cache_diff(content_hash, deny_float_eq, style = cache_diff::DiffStyle::Backticks)]
+struct Example {
+    #[cache_diff(project = major_minor)]
+    version: String,
+    #[cache_diff(project = file_name)]
+    lockfile: PathBuf,
+    #[cache_diff(project = rounded)]
+    ratio: f64,
+}
+
+fn main() {
+    let example = |version: &str, lockfile: &str, ratio| Example {
+        version: version.to_string(),
+        lockfile: Path::new(lockfile).to_path_buf(),
+        ratio,
+    };
+    let now = example("3.4.1", "/app/Gemfile.lock", 0.501);
+    assert!(now.diff(&example("3.4.0", "/tmp/Gemfile.lock", 0.502)).is_empty());
+    assert_eq!(now.content_hash(), example("3.4.0", "/x/Gemfile.lock", 0.499).content_hash());
+    assert_eq!(
+        now.diff(&example("3.3.9", "/app/gems.locked", 0.4)),
+        vec![
+            "version (`3.3` to `3.4`)",
+            "lockfile (`gems.locked` to `Gemfile.lock`)",
+            "ratio (`40` to `50`)",
+        ]
+    );
+}
@@ -0,0 +1,36 @@
+use cache_diff::CacheDiff;
+
+mod other {
+    #[derive(Clone)]
+    pub struct Ruby {
+        pub version: String,
+        pub distro: String,
+        pub installed_at: u64,
+    }
+}
+
+#[derive(CacheDiff)]
+#[cache_diff(remote = "other::Ruby", style = cache_diff::DiffStyle::Backticks)]
+struct RubyDiff {
+    #[cache_diff(rename = "Ruby version")]
+    version: String,
+    distro: String,
+    #[cache_diff(ignore)]
+    installed_at: u64,
+}
+
+fn main() {
+    let ruby = |version: &str, installed_at| other::Ruby {
+        version: version.to_string(),
+        distro: "ubuntu".to_string(),
+        installed_at,
+    };
+    let now = ruby("3.4.0", 2);
+    assert!(RubyDiff::diff_remote(&now, &ruby("3.4.0", 1)).is_empty());
+    assert_eq!(
+        RubyDiff::diff_remote(&now, &ruby("3.3.0", 1)),
+        vec!["Ruby version (`3.3.0` to `3.4.0`)"]
+    );
+    assert_eq!(RubyDiff::diff_structured_remote(&now, &ruby("3.3.0", 1)).len(), 1);
+    assert!(RubyDiff::from_remote(&now).diff(&RubyDiff::from_remote(&now)).is_empty());
+}
@@ -0,0 +1,26 @@
+use cache_diff::CacheDiff;
+
+#[derive(CacheDiff)]
+#[cache_diff(style = cache_diff::DiffStyle::Backticks)]
+struct Metadata {
+    #[cache_diff(semver = "major", rename = "Node.js version")]
+    node_version: String,
+    #[cache_diff(semver = "minor")]
+    npm_version: &'static str,
+}
+
+fn main() {
+    let metadata = |node_version: &str, npm_version| Metadata {
+        node_version: node_version.to_string(),
+        npm_version,
+    };
+    let old = metadata("20.11.0", "10.2.4");
+    assert!(metadata("20.18.1", "10.2.5").diff(&old).is_empty());
+    assert_eq!(
+        metadata("22.0.0", "10.3.0").diff(&old),
+        vec![
+            "Node.js version (`20.11.0` to `22.0.0`)",
+            "npm version (`10.2.4` to `10.3.0`)",
+        ]
+    );
+}
@@ -0,0 +1,29 @@
+use cache_diff::CacheDiff;
+
+#[derive(CacheDiff)]
+#[cache_diff(style = cache_diff::DiffStyle::Backticks)]
+struct Metadata {
+    url: String,
+    #[cache_diff(skip_if = url_changed)]
+    checksum: String,
+}
+
+fn url_changed(old: &Metadata, now: &Metadata) -> bool {
+    old.url != now.url
+}
+
+fn main() {
+    let metadata = |url: &str, checksum: &str| Metadata {
+        url: url.to_string(),
+        checksum: checksum.to_string(),
+    };
+    let old = metadata("https://example.com/ruby-3.3.0.tgz", "abc");
+    assert_eq!(
+        metadata("https://example.com/ruby-3.4.0.tgz", "def").diff(&old),
+        vec!["url (`https://example.com/ruby-3.3.0.tgz` to `https://example.com/ruby-3.4.0.tgz`)"]
+    );
+    let now = metadata("https://example.com/ruby-3.3.0.tgz", "def");
+    assert_eq!(now.diff(&old), vec!["checksum (`abc` to `def`)"]);
+    assert!(now.differs(&old));
+    assert!(!metadata("https://example.com/ruby-3.3.0.tgz", "abc").differs(&old));
+}
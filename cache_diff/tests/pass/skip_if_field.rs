@@ -0,0 +1,28 @@
+use cache_diff::CacheDiff;
+
+#[derive(CacheDiff)]
+#[cache_diff(style = cache_diff::DiffStyle::Backticks)]
+struct Metadata {
+    version: String,
+    #[cache_diff(skip_if = either_missing)]
+    checksum: String,
+}
+
+/// Older metadata was written before checksums were recorded
+fn either_missing(old: &String, now: &String) -> bool {
+    old.is_empty() || now.is_empty()
+}
+
+fn main() {
+    let metadata = |checksum: &str| Metadata {
+        version: "3.4.0".to_string(),
+        checksum: checksum.to_string(),
+    };
+    assert!(metadata("abc").diff(&metadata("")).is_empty());
+    assert!(!metadata("abc").differs(&metadata("")));
+    assert_eq!(
+        metadata("def").diff(&metadata("abc")),
+        vec!["checksum (`abc` to `def`)"]
+    );
+    assert!(metadata("def").differs(&metadata("abc")));
+}
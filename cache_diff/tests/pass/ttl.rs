@@ -0,0 +1,47 @@
+use cache_diff::CacheDiff;
+use std::time::{Duration, SystemTime};
+
+#[derive(CacheDiff)]
+#[cache_diff(content_hash)]
+struct Example {
+    version: String,
+    #[cache_diff(ttl = "2h")]
+    created_at: SystemTime,
+    #[cache_diff(ttl = "1w", rename = "Last checked")]
+    checked_at: u64,
+}
+
+fn main() {
+    let ago = |seconds| SystemTime::now() - Duration::from_secs(seconds);
+    let unix = |time: SystemTime| time.duration_since(SystemTime::UNIX_EPOCH).unwrap().as_secs();
+    let now = Example {
+        version: "3.4.0".to_string(),
+        created_at: SystemTime::now(),
+        checked_at: unix(SystemTime::now()),
+    };
+    let old = Example {
+        version: "3.4.0".to_string(),
+        created_at: ago(60 * 60),
+        checked_at: unix(ago(6 * 24 * 60 * 60)),
+    };
+    assert!(!now.differs(&old));
+    assert!(now.diff(&old).is_empty());
+    assert_eq!(now.content_hash(), old.content_hash());
+    assert!(!now.differs_from_hash(&old, old.content_hash()));
+
+    let old = Example {
+        created_at: ago(3 * 60 * 60),
+        checked_at: unix(ago(8 * 24 * 60 * 60)),
+        ..old
+    };
+    assert!(now.differs(&old));
+    assert_eq!(now.content_hash(), old.content_hash());
+    assert!(now.differs_from_hash(&old, old.content_hash()));
+    assert_eq!(
+        now.diff(&old),
+        vec![
+            "created at expired (created 3 hours ago, limit 2 hours)",
+            "Last checked expired (created 8 days ago, limit 7 days)",
+        ]
+    );
+}
@@ -0,0 +1,49 @@
+use cache_diff::CacheDiff;
+
+/// Only the major and minor version invalidate the cache
+mod major_minor {
+    fn prefix(version: &str) -> &str {
+        match version.rmatch_indices('.').next() {
+            Some((index, _)) => &version[..index],
+            None => version,
+        }
+    }
+
+    pub(crate) fn compare(old: &String, now: &String) -> bool {
+        prefix(old) == prefix(now)
+    }
+
+    pub(crate) fn display(version: &String) -> String {
+        format!("{}.x", prefix(version))
+    }
+}
+
+#[derive(CacheDiff)]
+#[cache_diff(style = cache_diff::DiffStyle::Backticks)]
+struct Metadata {
+    #[cache_diff(with = major_minor)]
+    version: String,
+    #[cache_diff(with = self::major_minor, rename = "Bundler")]
+    bundler_version: String,
+}
+
+fn main() {
+    let now = Metadata {
+        version: "3.4.1".to_string(),
+        bundler_version: "2.5.0".to_string(),
+    };
+    let old = Metadata {
+        version: "3.4.0".to_string(),
+        bundler_version: "2.5.3".to_string(),
+    };
+    assert!(now.diff(&old).is_empty());
+
+    let old = Metadata {
+        version: "3.3.9".to_string(),
+        bundler_version: "2.4.0".to_string(),
+    };
+    assert_eq!(
+        now.diff(&old),
+        vec!["version (`3.3.x` to `3.4.x`)", "Bundler (`2.4.x` to `2.5.x`)"]
+    );
+}
@@ -0,0 +1,271 @@
+//! Reproduce "why did this layer rebuild?" from captured metadata
+//!
+//! ```text
+//! $ cache-diff old.toml new.toml --config fields.toml
+//! 1 change detected:
+//! - Ruby version (`3.3.0` to `3.4.0`)
+//! ```
+//!
+//! The optional field config is a TOML file keyed by the dotted path of a value:
+//!
+//! ```toml
+//! [fields."ruby.version"]
+//! rename = "Ruby version"
+//!
+//! [fields.changed_by]
+//! ignore = true
+//! ```
+//!
+//! Ignoring a table also ignores every key in it, i.e. `[fields.env]` with `ignore = true` hides
+//! `env.PATH`.
+//!
+//! Exits with `0` when the files are the same, `1` when they differ, and `2` on error. `--help` prints
+//! the usage to stdout and exits with `0`.
+
+use cache_diff::Difference;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+const USAGE: &str = "Usage: cache-diff <old.toml> <new.toml> [--config <fields.toml>]";
+
+fn main() -> ExitCode {
+    let args = match Command::parse(std::env::args().skip(1)) {
+        Ok(Command::Help) => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Ok(Command::Diff(args)) => args,
+        Err(error) => {
+            eprintln!("{error}");
+            return ExitCode::from(2);
+        }
+    };
+    match run(&args) {
+        Ok(output) if output.is_empty() => ExitCode::SUCCESS,
+        Ok(output) => {
+            println!("{output}");
+            ExitCode::from(1)
+        }
+        Err(error) => {
+            eprintln!("{error}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Command {
+    /// Print the usage to stdout
+    Help,
+    Diff(Args),
+}
+
+#[derive(Debug, PartialEq)]
+struct Args {
+    old: PathBuf,
+    now: PathBuf,
+    config: Option<PathBuf>,
+}
+
+impl Command {
+    fn parse(args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut paths = Vec::new();
+        let mut config = None;
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-h" | "--help" => return Ok(Command::Help),
+                "--config" => {
+                    config =
+                        Some(PathBuf::from(args.next().ok_or_else(|| {
+                            format!("Missing value for `--config`\n{USAGE}")
+                        })?))
+                }
+                _ => paths.push(PathBuf::from(arg)),
+            }
+        }
+
+        match <[PathBuf; 2]>::try_from(paths) {
+            Ok([old, now]) => Ok(Command::Diff(Args { old, now, config })),
+            Err(_) => Err(format!("Expected two TOML files\n{USAGE}")),
+        }
+    }
+}
+
+/// Per field options from the `--config` file
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+struct FieldConfig {
+    #[serde(default)]
+    fields: BTreeMap<String, Field>,
+}
+
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+struct Field {
+    rename: Option<String>,
+    #[serde(default)]
+    ignore: bool,
+}
+
+impl FieldConfig {
+    /// Whether the dotted key, or a table containing it, is ignored
+    fn is_ignored(&self, key: &str) -> bool {
+        self.fields.iter().any(|(path, field)| {
+            field.ignore
+                && key
+                    .strip_prefix(path.as_str())
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+        })
+    }
+
+    /// Drop ignored differences and rename the rest
+    ///
+    /// Changed values are keyed by their name, messages such as an added or removed key in
+    /// `diff_schema` by their code.
+    fn apply(&self, differences: Vec<Difference>) -> Vec<Difference> {
+        differences
+            .into_iter()
+            .filter(|difference| {
+                !difference
+                    .name()
+                    .or(difference.code())
+                    .is_some_and(|key| self.is_ignored(key))
+            })
+            .map(|difference| {
+                let (Some(name), Some(old), Some(now)) =
+                    (difference.name(), difference.old(), difference.now())
+                else {
+                    return difference;
+                };
+                match self.fields.get(name) {
+                    Some(Field {
+                        rename: Some(rename),
+                        ..
+                    }) => Difference::changed(rename.clone(), old, now).with_code(name.to_string()),
+                    _ => difference,
+                }
+            })
+            .collect()
+    }
+}
+
+fn run(args: &Args) -> Result<String, String> {
+    let config = match &args.config {
+        Some(path) => toml::from_str::<FieldConfig>(&read(path)?)
+            .map_err(|error| format!("Invalid config {}: {error}", path.display()))?,
+        None => FieldConfig::default(),
+    };
+    let old = parse(&args.old)?;
+    let now = parse(&args.now)?;
+
+    let differences = config.apply(cache_diff::toml::diff_values_structured(&old, &now));
+    Ok(cache_diff::Renderer::new()
+        .summary(true)
+        .render(&differences))
+}
+
+fn read(path: &Path) -> Result<String, String> {
+    std::fs::read_to_string(path)
+        .map_err(|error| format!("Cannot read {}: {error}", path.display()))
+}
+
+fn parse(path: &Path) -> Result<toml::Value, String> {
+    toml::from_str(&read(path)?)
+        .map_err(|error| format!("Invalid TOML {}: {error}", path.display()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    fn args(args: &[&str]) -> Result<Command, String> {
+        Command::parse(args.iter().map(ToString::to_string))
+    }
+
+    #[test]
+    fn test_parse_args() {
+        assert_eq!(
+            args(&["old.toml", "--config", "fields.toml", "new.toml"]),
+            Ok(Command::Diff(Args {
+                old: PathBuf::from("old.toml"),
+                now: PathBuf::from("new.toml"),
+                config: Some(PathBuf::from("fields.toml")),
+            }))
+        );
+        assert_eq!(args(&["old.toml", "--help"]), Ok(Command::Help));
+        assert!(args(&["old.toml"]).is_err());
+        assert!(args(&["old.toml", "new.toml", "--config"]).is_err());
+    }
+
+    #[test]
+    fn test_config_renames_and_ignores() {
+        let config: FieldConfig = toml::from_str(indoc! {r#"
+            [fields."ruby.version"]
+            rename = "Ruby version"
+
+            [fields.changed_by]
+            ignore = true
+        "#})
+        .unwrap();
+
+        let differences = config.apply(vec![
+            Difference::changed("ruby.version", "3.3.0", "3.4.0"),
+            Difference::changed("changed_by", "Alice", "Bob"),
+            Difference::changed("arch", "amd64", "arm64"),
+        ]);
+
+        assert_eq!(
+            cache_diff::render_plain(&differences),
+            indoc! {"
+                - Ruby version (`3.3.0` to `3.4.0`)
+                - arch (`amd64` to `arm64`)"}
+        );
+    }
+
+    #[test]
+    fn test_config_ignores_added_and_removed_keys() {
+        let config: FieldConfig = toml::from_str(indoc! {r#"
+            [fields.env]
+            ignore = true
+
+            [fields.changed_by]
+            ignore = true
+        "#})
+        .unwrap();
+
+        let old = toml::from_str(indoc! {r#"
+            version = "3.4.0"
+            changed_by = "Alice"
+
+            [env]
+            PATH = "/bin"
+        "#})
+        .unwrap();
+        let now = toml::from_str(indoc! {r#"
+            version = "3.4.0"
+            environment = "production"
+
+            [env]
+            LANG = "C"
+        "#})
+        .unwrap();
+
+        let differences = config.apply(cache_diff::toml::diff_schema(&old, &now));
+        assert_eq!(
+            cache_diff::render_plain(&differences),
+            "- Field `environment` added"
+        );
+        let differences = config.apply(cache_diff::toml::diff_values_structured(&old, &now));
+        assert_eq!(
+            cache_diff::render_plain(&differences),
+            "- environment (`(not set)` to `production`)"
+        );
+        assert!(!config.is_ignored("environment"));
+        assert!(!config.is_ignored("envelope"));
+    }
+}
@@ -0,0 +1,43 @@
+use std::path::PathBuf;
+use std::process::Command;
+
+fn cache_diff(args: &[&str]) -> std::process::Output {
+    Command::new(env!("CARGO_BIN_EXE_cache-diff"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+/// Write the contents to a file named for the test, so tests can run in parallel
+fn write(name: &str, contents: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("cache_diff_cli_{}_{name}", std::process::id()));
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+#[test]
+fn test_help_prints_to_stdout() {
+    let output = cache_diff(&["--help"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(String::from_utf8_lossy(&output.stdout).starts_with("Usage: cache-diff"));
+    assert!(output.stderr.is_empty());
+}
+
+#[test]
+fn test_exit_codes() {
+    let old = write("old.toml", "version = \"3.3.0\"\n");
+    let now = write("now.toml", "version = \"3.4.0\"\n");
+    let (old, now) = (old.to_str().unwrap(), now.to_str().unwrap());
+
+    let output = cache_diff(&[old, now]);
+    assert_eq!(output.status.code(), Some(1));
+    // Values are colored when another workspace crate enables the `bullet_stream` feature
+    let stdout = cache_diff::strip_ansi(&String::from_utf8_lossy(&output.stdout));
+    assert!(stdout.contains("version (`3.3.0` to `3.4.0`)"));
+
+    assert_eq!(cache_diff(&[old, old]).status.code(), Some(0));
+
+    let output = cache_diff(&[old]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(output.stdout.is_empty());
+}
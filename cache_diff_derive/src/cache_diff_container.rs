@@ -24,71 +24,235 @@
 //! is brought together to create a fully formed [CacheDiffContainer].
 
 use crate::cache_diff_field::{ActiveField, ParsedField};
+use crate::{AttributeKey, MaybeKnown, WithSpan};
 use std::str::FromStr;
-use syn::parse::Parse;
+use syn::punctuated::Punctuated;
 use syn::Data::Struct;
 use syn::Fields::Named;
 use syn::{DataStruct, FieldsNamed, Ident};
 
 /// Represents the fully parsed Struct, it's attributes and all of it's parsed fields
 #[derive(Debug, PartialEq)]
-pub(crate) struct CacheDiffContainer {
+pub(crate) struct CacheDiffContainer<'a> {
     /// The identifier of a struct e.g. `struct Metadata {version: String}` would be `Metadata`
-    pub(crate) identifier: Ident,
+    pub(crate) identifier: &'a Ident,
     /// Info about generics, lifetimes and where clauses i.e. `struct Metadata<T> { name: T }`
-    pub(crate) generics: syn::Generics,
-    /// An optional path to a custom diff function
-    pub(crate) custom: Option<syn::Path>, // #[cache_diff(custom = <function>)]
+    pub(crate) generics: &'a syn::Generics,
+    /// Zero or more paths to custom diff functions, called in order
+    pub(crate) custom: Vec<syn::Path>, // #[cache_diff(custom = <function>)]
+    /// Evaluate custom diff functions concurrently on scoped threads
+    pub(crate) parallel: bool, // #[cache_diff(parallel)]
+    /// Generate `sample_changed()` for tests, requires the struct to be `Clone`
+    pub(crate) sample_changed: bool, // #[cache_diff(sample_changed)]
+    /// An optional path to a `cache_diff::DiffStyle` used when displaying values
+    pub(crate) style: Option<syn::Path>, // #[cache_diff(style = <path>)]
+    /// An optional runtime template for each changed field i.e. `"{name}: {old} => {new}"`
+    pub(crate) template: Option<String>, // #[cache_diff(template = "...")]
+    /// What the cache holds, used in restore and invalidate messages i.e. `"Ruby install"`
+    pub(crate) description: Option<String>, // #[cache_diff(description = "...")]
+    /// A type from another crate this struct mirrors field for field i.e. `other::Metadata`
+    pub(crate) remote: Option<syn::Type>, // #[cache_diff(remote = "...")]
+    /// Every field on the struct including ignored ones, copied from the remote type
+    pub(crate) all_fields: Vec<&'a Ident>,
+    /// Fields ignored with `ignore = "custom"`, compared by the custom functions
+    pub(crate) custom_fields: Vec<&'a Ident>,
+    /// Use `#[serde(rename = "...")]` as the field name when there's no cache_diff rename
+    pub(crate) serde_rename: bool, // #[cache_diff(serde_rename)]
+    /// Generate `content_hash()` over all compared fields
+    pub(crate) content_hash: bool, // #[cache_diff(content_hash)]
+    /// Attribute keys skipped by `allow_unknown_attributes = "warn"`, each one is reported with a warning
+    pub(crate) unknown_attributes: Vec<Ident>, // #[cache_diff(allow_unknown_attributes = "warn")]
     /// One or more named fields
     pub(crate) fields: Vec<ActiveField>,
 }
 
-impl CacheDiffContainer {
-    pub(crate) fn from_ast(input: &syn::DeriveInput) -> syn::Result<Self> {
-        let identifier = input.ident.clone();
-        let generics = input.generics.clone();
-        let mut container_custom = None;
+impl<'a> CacheDiffContainer<'a> {
+    pub(crate) fn from_ast(input: &'a syn::DeriveInput) -> syn::Result<Self> {
+        let identifier = &input.ident;
+        let generics = &input.generics;
+        let mut container_custom = Vec::new();
+        let mut parallel = false;
+        let mut sample_changed = false;
+        let mut container_style = None;
+        let mut container_template = None;
+        let mut description = None;
+        let mut remote = None;
+        let mut serde_rename = false;
+        let mut content_hash = false;
+        let mut deny_float_eq = false;
+        let mut allow_unknown = None;
+        let mut unknown = Vec::new();
+        // Every problem is reported in one compile instead of stopping at the first
+        let mut errors: Option<syn::Error> = None;
+        let mut defined: Vec<(KnownAttribute, proc_macro2::Span)> = Vec::new();
 
         for attribute in input
             .attrs
             .iter()
             .filter(|attr| attr.path().is_ident("cache_diff"))
         {
-            match attribute.parse_args_with(ParsedAttribute::parse)? {
-                ParsedAttribute::custom(path) => container_custom = Some(path),
+            let parsed_attributes = match attribute.parse_args_with(
+                Punctuated::<WithSpan<MaybeKnown<ParsedAttribute>>, syn::Token![,]>::parse_terminated,
+            ) {
+                Ok(parsed_attributes) => parsed_attributes,
+                Err(error) => {
+                    crate::push_error(&mut errors, error);
+                    continue;
+                }
+            };
+            for WithSpan(parsed, span) in parsed_attributes {
+                let parsed = match parsed {
+                    MaybeKnown::Known(parsed) => parsed,
+                    MaybeKnown::Unknown { key, error } => {
+                        unknown.push((key, error));
+                        continue;
+                    }
+                };
+                let key = KnownAttribute::from(&parsed);
+                // `custom` can be repeated to call several functions
+                if key != KnownAttribute::custom {
+                    if let Some((_, first)) = defined.iter().find(|(k, _)| *k == key) {
+                        crate::push_error(&mut errors, crate::duplicate_error(key, *first, span));
+                        continue;
+                    }
+                    defined.push((key, span));
+                }
+                match parsed {
+                    ParsedAttribute::custom(path) => container_custom.push(path),
+                    ParsedAttribute::parallel => parallel = true,
+                    ParsedAttribute::sample_changed => sample_changed = true,
+                    ParsedAttribute::style(path) => container_style = Some(path),
+                    ParsedAttribute::template(template) => container_template = Some(template),
+                    ParsedAttribute::description(value) => description = Some(value),
+                    ParsedAttribute::remote(ty) => remote = Some(ty),
+                    ParsedAttribute::serde_rename => serde_rename = true,
+                    ParsedAttribute::content_hash => content_hash = true,
+                    ParsedAttribute::deny_float_eq => deny_float_eq = true,
+                    ParsedAttribute::allow_unknown_attributes(allow) => allow_unknown = Some(allow),
+                }
             }
         }
 
+        // Keys skipped on the container and its fields, only collected when they are allowed
+        let mut unknown_attributes = Vec::new();
+        match allow_unknown {
+            Some(_) => unknown_attributes.extend(unknown.into_iter().map(|(key, _)| key)),
+            None => unknown
+                .into_iter()
+                .for_each(|(_, error)| crate::push_error(&mut errors, error)),
+        }
+
+        if content_hash && !container_custom.is_empty() {
+            crate::push_error(&mut errors, syn::Error::new(
+                identifier.span(),
+                "`content_hash` cannot be combined with `custom = <function>` because fields compared by the custom function are not hashed",
+            ));
+        }
+
         let mut fields = Vec::new();
-        for ast_field in match input.data {
+        let mut custom_fields = Vec::new();
+        let named = match &input.data {
             Struct(DataStruct {
-                fields: Named(FieldsNamed { ref named, .. }),
+                fields: Named(FieldsNamed { named, .. }),
                 ..
             }) => named,
-            _ => unimplemented!("CacheDiff derive macro can only be used on named structs"),
-        }
-        .to_owned()
-        .iter()
-        {
-            match ParsedField::from_field(ast_field)? {
-                ParsedField::IgnoredCustom => {
-                    if container_custom.is_none() {
-                        return Err(syn::Error::new(
+            Struct(DataStruct { fields, .. }) => {
+                return Err(syn::Error::new_spanned(
+                    fields,
+                    format!(
+                        "CacheDiff can only be derived for structs with named fields, `{identifier}` is a {kind} struct\n\
+                        Use named fields i.e. `struct {identifier} {{ version: String }}` or implement `cache_diff::CacheDiff` manually"
+                    , kind = if matches!(fields, syn::Fields::Unit) { "unit" } else { "tuple" }),
+                ))
+            }
+            syn::Data::Enum(data) => {
+                return Err(syn::Error::new_spanned(
+                    data.enum_token,
+                    format!(
+                        "CacheDiff cannot be derived for enums, `{identifier}` is an enum\n\
+                        Wrap it in a struct with a named field and a `#[cache_diff(display = <function>)]` or implement `cache_diff::CacheDiff` manually"
+                    ),
+                ))
+            }
+            syn::Data::Union(data) => {
+                return Err(syn::Error::new_spanned(
+                    data.union_token,
+                    format!("CacheDiff cannot be derived for unions, `{identifier}` is a union"),
+                ))
+            }
+        };
+        for ast_field in named {
+            match ParsedField::from_field(
+                ast_field,
+                serde_rename,
+                allow_unknown.is_some().then_some(&mut unknown_attributes),
+            ) {
+                Err(error) => crate::push_error(&mut errors, error),
+                Ok(ParsedField::IgnoredCustom) => {
+                    custom_fields.extend(ast_field.ident.as_ref());
+                    if container_custom.is_empty() {
+                        crate::push_error(&mut errors, syn::Error::new(
                             identifier.span(),
                             format!(
                                 "field `{field}` on {container} marked ignored as custom, but no `#[cache_diff(custom = <function>)]` found on `{container}`",
-                                field = ast_field.clone().ident.expect("named structs only"),
-                                container = &identifier,
+                                field = ast_field.ident.as_ref().expect("named structs only"),
+                                container = identifier,
                             )
                         ));
                     }
                 }
-                ParsedField::IgnoredOther => {}
-                ParsedField::Active(active_field) => fields.push(active_field),
+                Ok(ParsedField::IgnoredOther) => {}
+                Ok(ParsedField::Active(active_field)) => {
+                    // The type a `delegate` or `project` returns isn't visible to the derive, trust it to round the value
+                    if deny_float_eq
+                        && active_field.compared_with_ne()
+                        && active_field.delegate.is_none()
+                        && active_field.project.is_none()
+                        && active_field.compare.is_none()
+                    {
+                        if let Some(float) = crate::cache_diff_field::float_type(&ast_field.ty) {
+                            crate::push_error(&mut errors, syn::Error::new(
+                                active_field.field_identifier.span(),
+                                format!(
+                                    "Field `{field}` is an `{float}` compared with `!=`, tiny rounding differences will invalidate the cache\n\
+                                    Compare a rounded value with `project = <function>` or `delegate = \"...\"`, compare it with `compare = <function>` or a `with = <module>`, handle it in a `custom = <function>` with `ignore = \"custom\"`, or remove `deny_float_eq` from `{identifier}`",
+                                    field = active_field.field_identifier,
+                                ),
+                            ));
+                        }
+                    }
+                    fields.push(active_field)
+                }
+            }
+        }
+
+        // Two fields shown with the same name would make the output ambiguous
+        for (index, field) in fields.iter().enumerate() {
+            if let Some(first) = fields[..index]
+                .iter()
+                .find(|first| first.name == field.name)
+            {
+                let mut error = syn::Error::new(
+                    field.field_identifier.span(),
+                    format!(
+                        "Field `{field}` is displayed as `{name}` which is already used by `{first}`, differences would be ambiguous\n\
+                        Give one a unique name with `#[cache_diff(rename = \"...\")]`",
+                        field = field.field_identifier,
+                        name = field.name,
+                        first = first.field_identifier,
+                    ),
+                );
+                error.combine(syn::Error::new(
+                    first.field_identifier.span(),
+                    format!("`{}` first used here", field.name),
+                ));
+                crate::push_error(&mut errors, error);
             }
         }
 
-        if fields.is_empty() {
+        if let Some(errors) = errors {
+            Err(errors)
+        } else if fields.is_empty() {
             Err(syn::Error::new(
             identifier.span(),
             "No fields to compare for CacheDiff, ensure struct has at least one named field that isn't `cache_diff(ignore)`-d",
@@ -98,6 +262,23 @@ impl CacheDiffContainer {
                 identifier,
                 generics,
                 custom: container_custom,
+                parallel,
+                sample_changed,
+                style: container_style,
+                template: container_template,
+                description,
+                remote,
+                all_fields: named
+                    .iter()
+                    .filter_map(|field| field.ident.as_ref())
+                    .collect(),
+                custom_fields,
+                serde_rename,
+                content_hash,
+                unknown_attributes: match allow_unknown {
+                    Some(AllowUnknown::Warn) => unknown_attributes,
+                    Some(AllowUnknown::Ignore) | None => Vec::new(),
+                },
                 fields,
             })
         }
@@ -116,6 +297,41 @@ impl CacheDiffContainer {
 enum ParsedAttribute {
     #[allow(non_camel_case_types)]
     custom(syn::Path),
+    #[allow(non_camel_case_types)]
+    style(syn::Path),
+    #[allow(non_camel_case_types)]
+    template(String),
+    #[allow(non_camel_case_types)]
+    serde_rename,
+    #[allow(non_camel_case_types)]
+    content_hash,
+    #[allow(non_camel_case_types)]
+    parallel,
+    #[allow(non_camel_case_types)]
+    sample_changed,
+    #[allow(non_camel_case_types)]
+    deny_float_eq,
+    #[allow(non_camel_case_types)]
+    allow_unknown_attributes(AllowUnknown),
+    #[allow(non_camel_case_types)]
+    description(String),
+    #[allow(non_camel_case_types)]
+    remote(syn::Type),
+}
+
+/// How `allow_unknown_attributes` treats keys it skips
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum AllowUnknown {
+    /// `#[cache_diff(allow_unknown_attributes)]`
+    Ignore,
+    /// `#[cache_diff(allow_unknown_attributes = "warn")]`
+    Warn,
+}
+
+impl AttributeKey for ParsedAttribute {
+    fn is_known(key: &str) -> bool {
+        KnownAttribute::from_str(key).is_ok()
+    }
 }
 
 /// List all valid attributes for a field, mostly for error messages
@@ -133,10 +349,15 @@ impl syn::parse::Parse for ParsedAttribute {
         let name: Ident = input.parse()?;
         let name_str = name.to_string();
         match KnownAttribute::from_str(&name_str).map_err(|_| {
+            use strum::IntoEnumIterator;
+
+            let extra = crate::did_you_mean(&name_str, KnownAttribute::iter().map(|k| k.to_string()))
+                .map(|suggestion| format!("\nDid you mean `{suggestion}`?"))
+                .unwrap_or_default();
             syn::Error::new(
                 name.span(),
                 format!(
-                    "Unknown cache_diff attribute: `{name_str}`. Must be one of {valid_keys}",
+                    "Unknown cache_diff attribute: `{name_str}`. Must be one of {valid_keys}{extra}",
                     valid_keys = known_attributes()
                 ),
             )
@@ -145,10 +366,93 @@ impl syn::parse::Parse for ParsedAttribute {
                 input.parse::<syn::Token![=]>()?;
                 Ok(ParsedAttribute::custom(input.parse()?))
             }
+            KnownAttribute::style => {
+                input.parse::<syn::Token![=]>()?;
+                Ok(ParsedAttribute::style(input.parse()?))
+            }
+            KnownAttribute::serde_rename => Ok(ParsedAttribute::serde_rename),
+            KnownAttribute::content_hash => Ok(ParsedAttribute::content_hash),
+            KnownAttribute::parallel => Ok(ParsedAttribute::parallel),
+            KnownAttribute::sample_changed => Ok(ParsedAttribute::sample_changed),
+            KnownAttribute::deny_float_eq => Ok(ParsedAttribute::deny_float_eq),
+            KnownAttribute::description => {
+                input.parse::<syn::Token![=]>()?;
+                Ok(ParsedAttribute::description(
+                    input.parse::<syn::LitStr>()?.value(),
+                ))
+            }
+            KnownAttribute::remote => {
+                input.parse::<syn::Token![=]>()?;
+                let value = input.parse::<syn::LitStr>()?;
+                Ok(ParsedAttribute::remote(value.parse().map_err(|_| {
+                    syn::Error::new(
+                        value.span(),
+                        format!("Invalid cache_diff `remote` value `{}`, expected a type i.e. `other_crate::Metadata`", value.value()),
+                    )
+                })?))
+            }
+            KnownAttribute::allow_unknown_attributes => {
+                if input.peek(syn::Token![=]) {
+                    input.parse::<syn::Token![=]>()?;
+                    let value = input.parse::<syn::LitStr>()?;
+                    match value.value().as_str() {
+                        "warn" => Ok(ParsedAttribute::allow_unknown_attributes(AllowUnknown::Warn)),
+                        other => Err(syn::Error::new(
+                            value.span(),
+                            format!("Invalid cache_diff `allow_unknown_attributes` value `{other}`, expected `\"warn\"` or no value"),
+                        )),
+                    }
+                } else {
+                    Ok(ParsedAttribute::allow_unknown_attributes(AllowUnknown::Ignore))
+                }
+            }
+            KnownAttribute::template => {
+                input.parse::<syn::Token![=]>()?;
+                let template = input.parse::<syn::LitStr>()?;
+                validate_template(&template.value())
+                    .map_err(|message| syn::Error::new(template.span(), message))?;
+                Ok(ParsedAttribute::template(template.value()))
+            }
         }
     }
 }
 
+/// Mirrors `cache_diff::Template::parse` so invalid templates are a compile time error
+pub(crate) fn validate_template(template: &str) -> Result<(), String> {
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+            }
+            '}' => {
+                return Err(
+                    "Template contains a `}` without an opening `{`, escape literal braces as `}}`"
+                        .to_string(),
+                )
+            }
+            '{' => {
+                let mut placeholder = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => placeholder.push(c),
+                        None => return Err("Template contains a `{` without a closing `}`, escape literal braces as `{{`".to_string()),
+                    }
+                }
+                if !["name", "old", "new", "code"].contains(&placeholder.as_str()) {
+                    return Err(format!("Unknown template placeholder `{{{placeholder}}}`. Must be one of `{{name}}`, `{{old}}`, `{{new}}`, `{{code}}`"));
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -213,7 +517,273 @@ mod test {
         };
 
         let container = CacheDiffContainer::from_ast(&input).unwrap();
-        assert!(container.custom.is_some());
+        assert_eq!(container.custom.len(), 1);
+    }
+
+    #[test]
+    fn test_multiple_custom_parallel() {
+        let input: DeriveInput = syn::parse_quote! {
+            #[cache_diff(custom = hash_files, custom = check_version, parallel)]
+            struct Metadata {
+                version: String
+            }
+        };
+
+        let container = CacheDiffContainer::from_ast(&input).unwrap();
+        assert!(container.parallel);
+        assert_eq!(
+            container.custom,
+            vec![
+                syn::parse_str::<syn::Path>("hash_files").unwrap(),
+                syn::parse_str::<syn::Path>("check_version").unwrap()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_style_and_custom_on_container() {
+        let input: DeriveInput = syn::parse_quote! {
+            #[cache_diff(custom = my_function, style = cache_diff::DiffStyle::Plain)]
+            struct Metadata {
+                version: String
+            }
+        };
+
+        let container = CacheDiffContainer::from_ast(&input).unwrap();
+        assert_eq!(container.custom.len(), 1);
+        assert_eq!(
+            container.style,
+            Some(syn::parse_str("cache_diff::DiffStyle::Plain").unwrap())
+        );
+    }
+
+    #[test]
+    fn test_invalid_template() {
+        let input: DeriveInput = syn::parse_quote! {
+            #[cache_diff(template = "{name} {version}")]
+            struct Metadata {
+                version: String
+            }
+        };
+
+        let result = CacheDiffContainer::from_ast(&input);
+        assert!(result.is_err(), "Expected an error, got {result:?}");
+        assert_eq!(
+            format!("{}", result.err().unwrap()),
+            r#"Unknown template placeholder `{version}`. Must be one of `{name}`, `{old}`, `{new}`, `{code}`"#
+        );
+    }
+
+    #[test]
+    fn test_content_hash_with_custom() {
+        let input: DeriveInput = syn::parse_quote! {
+            #[cache_diff(content_hash, custom = my_function)]
+            struct Metadata {
+                version: String
+            }
+        };
+
+        let result = CacheDiffContainer::from_ast(&input);
+        assert!(result.is_err(), "Expected an error, got {result:?}");
+        assert_eq!(
+            format!("{}", result.err().unwrap()),
+            r#"`content_hash` cannot be combined with `custom = <function>` because fields compared by the custom function are not hashed"#
+        );
+    }
+
+    #[test]
+    fn test_errors_are_combined() {
+        let input: DeriveInput = syn::parse_quote! {
+            #[cache_diff(unknown)]
+            struct Metadata {
+                #[cache_diff(renmae = "Version")]
+                version: String,
+                #[cache_diff(ignore = "custom")]
+                distro: String,
+            }
+        };
+
+        let result = CacheDiffContainer::from_ast(&input);
+        assert!(result.is_err(), "Expected an error, got {result:?}");
+        let messages = result
+            .err()
+            .unwrap()
+            .into_iter()
+            .map(|error| error.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(messages.len(), 3, "Expected three errors, got {messages:?}");
+        assert!(messages[0].starts_with("Unknown cache_diff attribute: `unknown`"));
+        assert!(messages[1].starts_with("Unknown cache_diff attribute: `renmae`"));
+        assert!(messages[2].starts_with("field `distro` on Metadata marked ignored as custom"));
+    }
+
+    #[test]
+    fn test_tuple_struct() {
+        let input: DeriveInput = syn::parse_quote! {
+            struct Metadata(String);
+        };
+
+        let result = CacheDiffContainer::from_ast(&input);
+        assert!(result.is_err(), "Expected an error, got {result:?}");
+        assert!(format!("{}", result.err().unwrap())
+            .starts_with("CacheDiff can only be derived for structs with named fields, `Metadata` is a tuple struct"));
+    }
+
+    #[test]
+    fn test_enum() {
+        let input: DeriveInput = syn::parse_quote! {
+            enum Metadata {
+                Version(String),
+            }
+        };
+
+        let result = CacheDiffContainer::from_ast(&input);
+        assert!(result.is_err(), "Expected an error, got {result:?}");
+        assert!(format!("{}", result.err().unwrap())
+            .starts_with("CacheDiff cannot be derived for enums, `Metadata` is an enum"));
+    }
+
+    #[test]
+    fn test_allow_unknown_attributes() {
+        let input: DeriveInput = syn::parse_quote! {
+            #[cache_diff(from_the_future = "value")]
+            struct Metadata {
+                #[cache_diff(compare_with = function)]
+                version: String
+            }
+        };
+        let messages = CacheDiffContainer::from_ast(&input)
+            .unwrap_err()
+            .into_iter()
+            .map(|error| error.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            messages.len(),
+            2,
+            "Expected an error per unknown key, got {messages:?}"
+        );
+        assert!(messages[0].starts_with("Unknown cache_diff attribute: `from_the_future`"));
+        assert!(messages[1].starts_with("Unknown cache_diff attribute: `compare_with`"));
+
+        let input: DeriveInput = syn::parse_quote! {
+            #[cache_diff(from_the_future = "value", allow_unknown_attributes)]
+            struct Metadata {
+                #[cache_diff(compare_with = function, rename = "Ruby version")]
+                version: String
+            }
+        };
+        let container = CacheDiffContainer::from_ast(&input).unwrap();
+        assert!(container.unknown_attributes.is_empty());
+        assert_eq!(container.fields[0].name, "Ruby version");
+
+        let input: DeriveInput = syn::parse_quote! {
+            #[cache_diff(allow_unknown_attributes = "warn", from_the_future)]
+            struct Metadata {
+                #[cache_diff(compare_with(a, b))]
+                version: String
+            }
+        };
+        let container = CacheDiffContainer::from_ast(&input).unwrap();
+        assert_eq!(
+            container
+                .unknown_attributes
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            vec!["from_the_future", "compare_with"]
+        );
+    }
+
+    #[test]
+    fn test_duplicate_attribute() {
+        let input: DeriveInput = syn::parse_quote! {
+            #[cache_diff(template = "{name}", template = "{old}")]
+            struct Metadata {
+                version: String
+            }
+        };
+
+        let result = CacheDiffContainer::from_ast(&input);
+        assert!(result.is_err(), "Expected an error, got {result:?}");
+        let messages = result
+            .err()
+            .unwrap()
+            .into_iter()
+            .map(|error| error.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            messages,
+            vec![
+                "Duplicate cache_diff attribute `template`, it can only be used once".to_string(),
+                "`template` first defined here".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_duplicate_field_names() {
+        let input: DeriveInput = syn::parse_quote! {
+            struct Metadata {
+                #[cache_diff(rename = "os version")]
+                distro_version: String,
+                os_version: String,
+            }
+        };
+
+        let result = CacheDiffContainer::from_ast(&input);
+        assert!(result.is_err(), "Expected an error, got {result:?}");
+        let messages = result
+            .err()
+            .unwrap()
+            .into_iter()
+            .map(|error| error.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            messages,
+            vec![
+                "Field `os_version` is displayed as `os version` which is already used by `distro_version`, differences would be ambiguous\nGive one a unique name with `#[cache_diff(rename = \"...\")]`".to_string(),
+                "`os version` first used here".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_deny_float_eq() {
+        let input: DeriveInput = syn::parse_quote! {
+            struct Metadata {
+                ratio: f64,
+            }
+        };
+        assert!(CacheDiffContainer::from_ast(&input).is_ok());
+
+        let input: DeriveInput = syn::parse_quote! {
+            #[cache_diff(deny_float_eq)]
+            struct Metadata {
+                version: String,
+                ratio: f64,
+                #[cache_diff(delegate = "rounded()")]
+                rounded: Ratio,
+                #[cache_diff(ignore)]
+                ignored: f32,
+                #[cache_diff(max = 0.5)]
+                limit: f64,
+                #[cache_diff(compare = close_enough)]
+                tolerant: f64,
+            }
+        };
+        let result = CacheDiffContainer::from_ast(&input);
+        assert!(result.is_err(), "Expected an error, got {result:?}");
+        let messages = result
+            .err()
+            .unwrap()
+            .into_iter()
+            .map(|error| error.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(messages.len(), 1, "{messages:?}");
+        assert!(
+            messages[0].starts_with("Field `ratio` is an `f64` compared with `!=`"),
+            "{messages:?}"
+        );
     }
 
     #[test]
@@ -225,6 +795,6 @@ mod test {
         };
 
         let container = CacheDiffContainer::from_ast(&input).unwrap();
-        assert!(container.custom.is_none());
+        assert!(container.custom.is_empty());
     }
 }
@@ -26,10 +26,18 @@
 //!
 //! A one or more [ParsedField::Active]-s lives inside of a [CacheDiffContainer].
 
+use crate::{AttributeKey, MaybeKnown, WithSpan};
 use std::str::FromStr;
 use strum::IntoEnumIterator;
-use syn::{punctuated::Punctuated, spanned::Spanned, Field, Ident, PathArguments, Token};
+use syn::{
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    spanned::Spanned,
+    Field, Ident, PathArguments, Token,
+};
 
+// Only built once per field at compile time, boxing would make matching noisier for no gain
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug, PartialEq)]
 pub(crate) enum ParsedField {
     IgnoredCustom,
@@ -44,17 +52,306 @@ pub(crate) struct ActiveField {
     pub(crate) name: String,
     /// The function to use when rendering values on the field
     /// i.e. `age: 42` will be `"42"`
-    pub(crate) display_fn: syn::Path,
+    pub(crate) display_fn: DisplayFn,
     /// The proc-macro identifier for a field i.e. `name: String` would be a programatic
     /// reference to `name` that can be used along with `quote!` to produce code
     pub(crate) field_identifier: Ident,
+    /// An optional heading to group the field under when rendered
+    /// i.e. `#[cache_diff(category = "Runtime")]` will be `Some("Runtime")`
+    pub(crate) category: Option<String>,
+    /// A stable machine readable code for the field, defaults to the field identifier
+    /// i.e. `ruby_version: String` will be `"ruby_version"`
+    pub(crate) code: String,
+    /// Show a line by line diff of the values instead of the full old and new values
+    /// i.e. `#[cache_diff(lines)]` will be `true`
+    pub(crate) lines: bool,
+    /// An optional function that returns a changed copy of the field's value for `sample_changed()`
+    /// i.e. `#[cache_diff(sample = bump_version)]` will be `Some(bump_version)`
+    pub(crate) sample: Option<syn::Path>,
+    /// An optional function that renders the old and new values together
+    /// i.e. `#[cache_diff(display_pair = path_suffix)]` will be `Some(path_suffix)`
+    pub(crate) display_pair: Option<syn::Path>,
+    /// An optional field or accessor on the field's value to compare and display instead
+    /// i.e. `#[cache_diff(delegate = "inner")]` will compare `self.version.inner`
+    pub(crate) delegate: Option<Delegate>,
+    /// An optional function applied to the value before it is compared and displayed
+    /// i.e. `#[cache_diff(project = major_minor)]` will be `Some(major_minor)`
+    pub(crate) project: Option<syn::Path>,
+    /// When true an empty string and `None` compare as equal
+    /// i.e. `#[cache_diff(empty_is_none)]` on `url: Option<String>` keeps `Some("")` and `None` equal
+    pub(crate) empty_is_none: bool,
+    /// The age in seconds after which the stored timestamp invalidates the cache
+    /// i.e. `#[cache_diff(ttl = "30d")]` will be `Some(2592000)`
+    pub(crate) ttl: Option<u64>,
+    /// A numeric limit, the field differs when its new value is larger
+    /// i.e. `#[cache_diff(max = 200)]` will be `Some(200)`
+    pub(crate) max: Option<syn::Lit>,
+    /// The number of reuses after which the cache is invalidated
+    /// i.e. `#[cache_diff(max_uses = 200)]` will be `Some(200)`
+    pub(crate) max_uses: Option<u64>,
+    /// An optional function that chooses which elements of a collection are compared and displayed
+    /// i.e. `#[cache_diff(filter = is_runtime)]` will be `Some(is_runtime)`
+    pub(crate) filter: Option<syn::Path>,
+    /// An optional function that returns `true` when the old and new values are the same, used instead of `!=`
+    /// i.e. `#[cache_diff(compare = same_minor)]` will be `Some(same_minor)` and
+    /// `#[cache_diff(with = versions)]` will be `Some(versions::compare)`
+    pub(crate) compare: Option<syn::Path>,
+    /// Optional words placed before the old and new values
+    /// i.e. `#[cache_diff(labels = "was/now")]` will be `Some(("was", "now"))`
+    pub(crate) labels: Option<(String, String)>,
+    /// When true the field's own CacheDiff implementation is used and its differences are included
+    /// i.e. `#[cache_diff(nested)]` on `ruby: RubyMetadata` reports `ruby.version` changes
+    pub(crate) nested: bool,
+    /// The field's previous key and its current key, used to read metadata written before a rename
+    /// i.e. `#[cache_diff(alias = "ruby_version")]` on `version: String` will be `Some(("ruby_version", "version"))`
+    pub(crate) alias: Option<(String, String)>,
+    /// An optional function that returns the field's messages, used instead of the derived difference
+    /// i.e. `#[cache_diff(custom = diff_version)]` will be `Some(diff_version)`
+    pub(crate) custom: Option<syn::Path>,
+    /// An optional template for the field's difference, replacing the struct's template
+    /// i.e. `#[cache_diff(fmt = "{name} changed from {old} to {new}")]`
+    pub(crate) fmt: Option<String>,
+    /// The most specific part of a version that differs, also sets `compare`
+    /// i.e. `#[cache_diff(semver = "major")]` will be `Some("major")`
+    pub(crate) semver: Option<String>,
+    /// An optional function of the old and new values, or structs, that skips comparing the field when it returns `true`
+    /// i.e. `#[cache_diff(skip_if = url_changed)]` will be `Some(url_changed)`
+    pub(crate) skip_if: Option<syn::Path>,
+    /// The name of a difference shared with other fields, their values are shown together
+    /// i.e. `#[cache_diff(group = "OS")]` will be `Some("OS")`
+    pub(crate) group: Option<String>,
+}
+
+impl ActiveField {
+    /// Whether the field falls back to comparing its values with `!=`
+    ///
+    /// Fields using `ttl`, `max`, `max_uses`, `with`, `nested`, `compare` (which `semver` sets) or `custom` are compared another way.
+    pub(crate) fn compared_with_ne(&self) -> bool {
+        self.ttl.is_none()
+            && self.max.is_none()
+            && self.max_uses.is_none()
+            && self.compare.is_none()
+            && !self.nested
+            && self.custom.is_none()
+    }
+
+    /// How the field's values are compared, for `explain()` i.e. `` `!=` ``
+    pub(crate) fn explain_compare(&self) -> String {
+        let strategy = if self.nested {
+            "its own `CacheDiff`".to_string()
+        } else if let Some(path) = &self.custom {
+            format!("`custom = {}`", path_string(path))
+        } else if let Some(part) = &self.semver {
+            format!("`semver` {part} version")
+        } else if let Some(seconds) = self.ttl {
+            format!("`ttl` of {seconds} seconds")
+        } else if let Some(limit) = &self.max {
+            format!("`max` of {}", quote::quote! { #limit })
+        } else if let Some(limit) = self.max_uses {
+            format!("`max_uses` of {limit}")
+        } else if let Some(path) = &self.compare {
+            format!("`{}`", path_string(path))
+        } else {
+            "`!=`".to_string()
+        };
+        let mut modifiers = Vec::new();
+        if let Some(delegate) = &self.delegate {
+            modifiers.push(format!("delegated to `{}`", delegate.describe()));
+        }
+        if let Some(path) = &self.filter {
+            modifiers.push(format!("filtered by `{}`", path_string(path)));
+        }
+        if let Some(path) = &self.project {
+            modifiers.push(format!("projected by `{}`", path_string(path)));
+        }
+        if self.empty_is_none {
+            modifiers.push("empty is none".to_string());
+        }
+        if let Some(path) = &self.skip_if {
+            modifiers.push(format!("skipped if `{}`", path_string(path)));
+        }
+        if modifiers.is_empty() {
+            strategy
+        } else {
+            format!("{strategy} ({})", modifiers.join(", "))
+        }
+    }
+
+    /// How the field's values are displayed, for `explain()` i.e. `` `Display` ``
+    pub(crate) fn explain_display(&self) -> String {
+        if self.custom.is_some() {
+            return "its `custom` messages".to_string();
+        }
+        let display = match &self.display_pair {
+            Some(path) => format!("`{}` for both values", path_string(path)),
+            None if self.ttl.is_some() || self.max.is_some() || self.max_uses.is_some() => {
+                "a limit message".to_string()
+            }
+            None => match &self.display_fn {
+                DisplayFn::Custom(path) => format!("`{}`", path_string(path)),
+                DisplayFn::PathBuf => "`Path::display`".to_string(),
+                DisplayFn::Debug => "`Debug`".to_string(),
+                DisplayFn::NormalizedPath(None) => "`normalize_path`".to_string(),
+                DisplayFn::NormalizedPath(Some(base)) => {
+                    format!("`normalize_path` relative to `{base}`")
+                }
+                DisplayFn::Identity if self.nested => "its initial message".to_string(),
+                DisplayFn::Identity => "`Display`".to_string(),
+            },
+        };
+        let display = match &self.labels {
+            Some((old, now)) => format!("{display} labeled `{old}` and `{now}`"),
+            None => display,
+        };
+        match &self.group {
+            Some(group) => format!("{display} in group `{group}`"),
+            None => display,
+        }
+    }
+}
+
+/// A path as written without spaces i.e. `versions::compare`
+fn path_string(path: &syn::Path) -> String {
+    let segments = path
+        .segments
+        .iter()
+        .map(|segment| segment.ident.to_string())
+        .collect::<Vec<_>>()
+        .join("::");
+    match path.leading_colon {
+        Some(_) => format!("::{segments}"),
+        None => segments,
+    }
+}
+
+/// A chain of fields and zero argument accessors parsed from `delegate = "..."`
+/// i.e. `"inner"` is `.inner` and `"value().inner"` is `.value().inner`
+#[derive(Debug, PartialEq)]
+pub(crate) struct Delegate(Vec<(Ident, bool)>);
+
+impl Delegate {
+    fn parse(value: &syn::LitStr) -> syn::Result<Self> {
+        value
+            .value()
+            .split('.')
+            .map(|segment| {
+                let (name, call) = match segment.strip_suffix("()") {
+                    Some(name) => (name, true),
+                    None => (segment, false),
+                };
+                syn::parse_str::<Ident>(name)
+                    .map(|ident| (Ident::new(&ident.to_string(), value.span()), call))
+                    .map_err(|_| {
+                        syn::Error::new(
+                            value.span(),
+                            format!("Invalid cache_diff `delegate` value `{segment}`, expected a field name i.e. `inner` or an accessor i.e. `inner()`, separated by `.`"),
+                        )
+                    })
+            })
+            .collect::<syn::Result<Vec<_>>>()
+            .map(Delegate)
+    }
+}
+
+impl Delegate {
+    /// The accessor chain as written i.e. `.value().inner`
+    fn describe(&self) -> String {
+        self.0
+            .iter()
+            .map(|(ident, call)| {
+                if *call {
+                    format!(".{ident}()")
+                } else {
+                    format!(".{ident}")
+                }
+            })
+            .collect()
+    }
+}
+
+impl quote::ToTokens for Delegate {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        for (ident, call) in &self.0 {
+            tokens.extend(if *call {
+                quote::quote! { .#ident() }
+            } else {
+                quote::quote! { .#ident }
+            });
+        }
+    }
+}
+
+/// The function used to display a field's value
+///
+/// Built in functions are emitted as tokens directly so they don't need to be parsed for every field
+#[derive(Debug, PartialEq)]
+pub(crate) enum DisplayFn {
+    /// `std::convert::identity` for values that implement Display
+    Identity,
+    /// `std::path::Path::display` for `PathBuf` fields
+    PathBuf,
+    /// #[cache_diff(display = <function>)]
+    Custom(syn::Path),
+    /// `cache_diff::normalize_path` with an optional base directory from #[cache_diff(normalize_path = "...")]
+    NormalizedPath(Option<String>),
+    /// The value's `Debug` output from #[cache_diff(debug)]
+    Debug,
+}
+
+impl quote::ToTokens for DisplayFn {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        match self {
+            DisplayFn::Identity => tokens.extend(quote::quote! { ::std::convert::identity }),
+            DisplayFn::PathBuf => tokens.extend(quote::quote! { ::std::path::Path::display }),
+            DisplayFn::Custom(path) => path.to_tokens(tokens),
+            DisplayFn::NormalizedPath(_) => {
+                tokens.extend(quote::quote! { ::cache_diff::normalize_path })
+            }
+            DisplayFn::Debug => tokens.extend(quote::quote! { ::cache_diff::field_debug }),
+        }
+    }
 }
 
 impl ParsedField {
-    pub(crate) fn from_field(field: &Field) -> syn::Result<Self> {
+    /// Parse a field and its attributes
+    ///
+    /// When `serde_rename` is true, a `#[serde(rename = "...")]` on the field is used as the
+    /// name unless a `#[cache_diff(rename = "...")]` is also present.
+    ///
+    /// Unknown attribute keys are an error unless `unknown` is given, then they are skipped and
+    /// their keys pushed onto it.
+    pub(crate) fn from_field(
+        field: &Field,
+        serde_rename: bool,
+        mut unknown: Option<&mut Vec<Ident>>,
+    ) -> syn::Result<Self> {
         let mut rename = None;
         let mut display = None;
         let mut ignored = None;
+        let mut category = None;
+        let mut code = None;
+        let mut lines = false;
+        let mut sample = None;
+        let mut display_pair = None;
+        let mut delegate = None;
+        let mut project = None;
+        let mut empty_is_none = false;
+        let mut ttl = None;
+        let mut max = None;
+        let mut max_uses = None;
+        let mut filter = None;
+        let mut with = None;
+        let mut labels = None;
+        let mut nested = false;
+        let mut alias = None;
+        let mut normalize_path = None;
+        let mut compare = None;
+        let mut custom = None;
+        let mut fmt = None;
+        let mut debug = false;
+        let mut semver = None;
+        let mut skip_if = None;
+        let mut group = None;
         let field_identifier = field.ident.clone().ok_or_else(|| {
             syn::Error::new(
                 field.span(),
@@ -62,16 +359,48 @@ impl ParsedField {
             )
         })?;
 
-        if let Some(attributes) = field
+        // Every bad attribute is reported in one compile instead of stopping at the first
+        let mut defined: Vec<(KnownAttribute, proc_macro2::Span)> = Vec::new();
+        let mut errors: Option<syn::Error> = None;
+
+        for attributes in field
             .attrs
             .iter()
-            .find(|&attr| attr.path().is_ident("cache_diff"))
+            .filter(|&attr| attr.path().is_ident("cache_diff"))
         {
             match &attributes.meta {
                 syn::Meta::List(meta_list) => {
-                    for attr in meta_list.parse_args_with(
-                        Punctuated::<ParsedAttribute, Token![,]>::parse_terminated,
-                    )? {
+                    let parsed_attributes = meta_list
+                        .parse_args_with(parse_recovering::<WithSpan<MaybeKnown<ParsedAttribute>>>)
+                        .unwrap_or_else(|error| vec![Err(error)]);
+                    for parsed in parsed_attributes {
+                        let WithSpan(attr, span) = match parsed {
+                            Ok(parsed) => parsed,
+                            Err(error) => {
+                                crate::push_error(&mut errors, error);
+                                continue;
+                            }
+                        };
+                        let attr = match (attr, unknown.as_mut()) {
+                            (MaybeKnown::Known(attr), _) => attr,
+                            (MaybeKnown::Unknown { key, .. }, Some(unknown)) => {
+                                unknown.push(key);
+                                continue;
+                            }
+                            (MaybeKnown::Unknown { error, .. }, None) => {
+                                crate::push_error(&mut errors, error);
+                                continue;
+                            }
+                        };
+                        let key = KnownAttribute::from(&attr);
+                        if let Some((_, first)) = defined.iter().find(|(k, _)| *k == key) {
+                            crate::push_error(
+                                &mut errors,
+                                crate::duplicate_error(key, *first, span),
+                            );
+                            continue;
+                        }
+                        defined.push((key, span));
                         match attr {
                             ParsedAttribute::rename(name) => {
                                 rename = Some(name);
@@ -79,6 +408,78 @@ impl ParsedField {
                             ParsedAttribute::display(path) => {
                                 display = Some(path);
                             }
+                            ParsedAttribute::category(name) => {
+                                category = Some(name);
+                            }
+                            ParsedAttribute::code(value) => {
+                                code = Some(value);
+                            }
+                            ParsedAttribute::lines => {
+                                lines = true;
+                            }
+                            ParsedAttribute::sample(path) => {
+                                sample = Some(path);
+                            }
+                            ParsedAttribute::display_pair(path) => {
+                                display_pair = Some(path);
+                            }
+                            ParsedAttribute::delegate(value) => {
+                                delegate = Some(value);
+                            }
+                            ParsedAttribute::project(path) => {
+                                project = Some(path);
+                            }
+                            ParsedAttribute::empty_is_none => {
+                                empty_is_none = true;
+                            }
+                            ParsedAttribute::ttl(seconds) => {
+                                ttl = Some(seconds);
+                            }
+                            ParsedAttribute::max(limit) => {
+                                max = Some(limit);
+                            }
+                            ParsedAttribute::max_uses(limit) => {
+                                max_uses = Some(limit);
+                            }
+                            ParsedAttribute::filter(path) => {
+                                filter = Some(path);
+                            }
+                            ParsedAttribute::with(path) => {
+                                with = Some(path);
+                            }
+                            ParsedAttribute::labels(value) => {
+                                labels = Some(value);
+                            }
+                            ParsedAttribute::nested => {
+                                nested = true;
+                            }
+                            ParsedAttribute::alias(value) => {
+                                alias = Some(value);
+                            }
+                            ParsedAttribute::normalize_path(base) => {
+                                normalize_path = Some(base);
+                            }
+                            ParsedAttribute::compare(path) => {
+                                compare = Some(path);
+                            }
+                            ParsedAttribute::custom(path) => {
+                                custom = Some(path);
+                            }
+                            ParsedAttribute::fmt(template) => {
+                                fmt = Some(template);
+                            }
+                            ParsedAttribute::debug => {
+                                debug = true;
+                            }
+                            ParsedAttribute::semver(part) => {
+                                semver = Some(part);
+                            }
+                            ParsedAttribute::skip_if(path) => {
+                                skip_if = Some(path);
+                            }
+                            ParsedAttribute::group(name) => {
+                                group = Some(name);
+                            }
                             ParsedAttribute::ignore(field_status) => {
                                 //
                                 match field_status {
@@ -93,39 +494,290 @@ impl ParsedField {
                         }
                     }
                 }
-                _ => {
-                    return Err(syn::Error::new(
-                        attributes.pound_token.span,
-                        "Expected a list of attributes",
-                    ))
-                }
+                _ => crate::push_error(
+                    &mut errors,
+                    syn::Error::new(attributes.pound_token.span, "Expected a list of attributes"),
+                ),
             }
         }
 
-        if let Some(ignored) = ignored {
-            if display.is_some() || rename.is_some() {
-                Err(syn::Error::new(field_identifier.span(), format!("The cache_diff attribute `{}` renders other attributes useless, remove additional attributes", KnownAttribute::ignore)))
-            } else {
-                Ok(ignored)
+        // Each of these decides when the field differs and how it is shown
+        let exclusive = [
+            KnownAttribute::ttl,
+            KnownAttribute::max,
+            KnownAttribute::max_uses,
+            KnownAttribute::display_pair,
+            KnownAttribute::with,
+        ];
+        let mut exclusive = defined.iter().filter(|(key, _)| exclusive.contains(key));
+        if let (Some((first, _)), Some((second, span))) = (exclusive.next(), exclusive.next()) {
+            let error = syn::Error::new(
+                *span,
+                format!("The cache_diff attributes `{first}` and `{second}` cannot be used together, both replace how the field's difference is shown"),
+            );
+            crate::push_error(&mut errors, error);
+        }
+        if let (Some(_), Some((_, span))) = (
+            &with,
+            defined
+                .iter()
+                .find(|(key, _)| *key == KnownAttribute::display),
+        ) {
+            let error = syn::Error::new(
+                *span,
+                format!("The cache_diff attributes `{}` and `{}` cannot be used together, the `with` module provides the `display` function", KnownAttribute::display, KnownAttribute::with),
+            );
+            crate::push_error(&mut errors, error);
+        }
+
+        // Only `!=` compares the normalized value, the others receive the field's own value
+        if empty_is_none {
+            let replaced = [
+                KnownAttribute::ttl,
+                KnownAttribute::max,
+                KnownAttribute::max_uses,
+                KnownAttribute::with,
+                KnownAttribute::compare,
+                KnownAttribute::custom,
+                KnownAttribute::semver,
+            ];
+            for (key, span) in defined.iter().filter(|(key, _)| replaced.contains(key)) {
+                let error = syn::Error::new(
+                    *span,
+                    format!("The cache_diff attributes `{key}` and `{}` cannot be used together, `{key}` receives the field's value before empty strings become `None`", KnownAttribute::empty_is_none),
+                );
+                crate::push_error(&mut errors, error);
+            }
+        }
+
+        if custom.is_some() {
+            let replaced = [
+                KnownAttribute::lines,
+                KnownAttribute::display_pair,
+                KnownAttribute::ttl,
+                KnownAttribute::max,
+                KnownAttribute::max_uses,
+                KnownAttribute::with,
+                KnownAttribute::labels,
+                KnownAttribute::nested,
+                KnownAttribute::compare,
+                KnownAttribute::fmt,
+                KnownAttribute::semver,
+            ];
+            for (key, span) in defined.iter().filter(|(key, _)| replaced.contains(key)) {
+                let error = syn::Error::new(
+                    *span,
+                    format!("The cache_diff attributes `{key}` and `{}` cannot be used together, the `custom` function produces the field's messages", KnownAttribute::custom),
+                );
+                crate::push_error(&mut errors, error);
+            }
+        }
+
+        // Each of these replaces `!=`
+        for (replacement, set) in [
+            (KnownAttribute::compare, compare.is_some()),
+            (KnownAttribute::semver, semver.is_some()),
+        ] {
+            let decides = [
+                KnownAttribute::ttl,
+                KnownAttribute::max,
+                KnownAttribute::max_uses,
+                KnownAttribute::with,
+                KnownAttribute::compare,
+            ];
+            for (key, span) in defined
+                .iter()
+                .filter(|(key, _)| set && *key != replacement && decides.contains(key))
+            {
+                let error = syn::Error::new(
+                    *span,
+                    format!("The cache_diff attributes `{key}` and `{replacement}` cannot be used together, both decide when the field differs"),
+                );
+                crate::push_error(&mut errors, error);
+            }
+        }
+
+        if fmt.is_some() {
+            let layouts = [KnownAttribute::labels, KnownAttribute::lines];
+            for (key, span) in defined.iter().filter(|(key, _)| layouts.contains(key)) {
+                let error = syn::Error::new(
+                    *span,
+                    format!("The cache_diff attributes `{key}` and `{}` cannot be used together, both replace the layout of the field's difference", KnownAttribute::fmt),
+                );
+                crate::push_error(&mut errors, error);
+            }
+        }
+
+        // Each of these sets how the field's values are displayed
+        let displays = [
+            KnownAttribute::display,
+            KnownAttribute::with,
+            KnownAttribute::normalize_path,
+            KnownAttribute::debug,
+        ];
+        let mut displays = defined.iter().filter(|(key, _)| displays.contains(key));
+        if let (Some((first, _)), Some((second, span))) = (displays.next(), displays.next()) {
+            // `display` and `with` have their own message
+            if (*first, *second) != (KnownAttribute::display, KnownAttribute::with)
+                && (*first, *second) != (KnownAttribute::with, KnownAttribute::display)
+            {
+                let error = syn::Error::new(
+                    *span,
+                    format!("The cache_diff attributes `{first}` and `{second}` cannot be used together, both set how the field's values are displayed"),
+                );
+                crate::push_error(&mut errors, error);
+            }
+        }
+
+        // A nested field is compared and shown by its own implementation
+        if nested {
+            let replaced = [
+                KnownAttribute::display,
+                KnownAttribute::display_pair,
+                KnownAttribute::lines,
+                KnownAttribute::empty_is_none,
+                KnownAttribute::ttl,
+                KnownAttribute::max,
+                KnownAttribute::max_uses,
+                KnownAttribute::with,
+                KnownAttribute::labels,
+                KnownAttribute::normalize_path,
+                KnownAttribute::compare,
+                KnownAttribute::fmt,
+                KnownAttribute::debug,
+                KnownAttribute::semver,
+            ];
+            for (key, span) in defined.iter().filter(|(key, _)| replaced.contains(key)) {
+                let error = syn::Error::new(
+                    *span,
+                    format!("The cache_diff attribute `{key}` cannot be used with `{}`, the field's own CacheDiff implementation compares and shows its values", KnownAttribute::nested),
+                );
+                crate::push_error(&mut errors, error);
+            }
+        }
+
+        // A grouped field is shown as part of the group's difference
+        if group.is_some() {
+            let replaced = [
+                KnownAttribute::lines,
+                KnownAttribute::display_pair,
+                KnownAttribute::ttl,
+                KnownAttribute::max,
+                KnownAttribute::max_uses,
+                KnownAttribute::labels,
+                KnownAttribute::nested,
+                KnownAttribute::custom,
+                KnownAttribute::fmt,
+            ];
+            for (key, span) in defined.iter().filter(|(key, _)| replaced.contains(key)) {
+                let error = syn::Error::new(
+                    *span,
+                    format!("The cache_diff attributes `{key}` and `{}` cannot be used together, the group's difference shows the field's value", KnownAttribute::group),
+                );
+                crate::push_error(&mut errors, error);
+            }
+        }
+
+        if let Some(errors) = errors {
+            Err(errors)
+        } else if let Some(ignored) = ignored {
+            match defined.iter().find(|(key, _)| *key != KnownAttribute::ignore) {
+                Some((_, span)) => Err(syn::Error::new(*span, format!("The cache_diff attribute `{}` renders other attributes useless, remove additional attributes", KnownAttribute::ignore))),
+                None => Ok(ignored),
             }
         } else {
             Ok(ParsedField::Active(ActiveField {
-                name: rename.unwrap_or_else(|| field_identifier.to_string().replace("_", " ")),
-                display_fn: display.unwrap_or_else(|| {
-                    if is_pathbuf(&field.ty) {
-                        syn::parse_str("std::path::Path::display")
-                            .expect("PathBuf::display parses as a syn::Path")
-                    } else {
-                        syn::parse_str("std::convert::identity")
-                            .expect("std::convert::identity parses as a syn::Path")
+                name: rename
+                    .or_else(|| serde_rename.then(|| serde_rename_value(field)).flatten())
+                    .unwrap_or_else(|| field_identifier.to_string().replace("_", " ")),
+                display_fn: match (display, &with) {
+                    _ if normalize_path.is_some() => {
+                        DisplayFn::NormalizedPath(normalize_path.flatten())
                     }
+                    (Some(path), _) => DisplayFn::Custom(path),
+                    (None, Some(module)) => DisplayFn::Custom(module_fn(module, "display")),
+                    (None, None) if debug => DisplayFn::Debug,
+                    (None, None)
+                        if delegate.is_none()
+                            && project.is_none()
+                            && filter.is_none()
+                            && is_pathbuf(&field.ty) =>
+                    {
+                        DisplayFn::PathBuf
+                    }
+                    (None, None) => DisplayFn::Identity,
+                },
+                compare: compare
+                    .or_else(|| with.as_ref().map(|module| module_fn(module, "compare")))
+                    .or_else(|| {
+                        let function = quote::format_ident!("same_semver_{}", semver.as_ref()?);
+                        Some(syn::parse_quote! { ::cache_diff::#function })
+                    }),
+                semver,
+                skip_if,
+                group,
+                labels,
+                nested,
+                custom,
+                fmt,
+                alias: alias.map(|alias| {
+                    let key =
+                        serde_rename_value(field).unwrap_or_else(|| field_identifier.to_string());
+                    (alias, key)
                 }),
+                code: code.unwrap_or_else(|| field_identifier.to_string()),
                 field_identifier,
+                category,
+                lines,
+                sample,
+                display_pair,
+                delegate,
+                project,
+                empty_is_none,
+                ttl,
+                max,
+                max_uses,
+                filter,
             }))
         }
     }
 }
 
+/// Parse comma separated attributes, an invalid attribute is returned as an error in its place
+///
+/// Parsing continues after the next comma so every bad attribute on a field is reported, not only the first.
+fn parse_recovering<T: Parse>(input: ParseStream) -> syn::Result<Vec<syn::Result<T>>> {
+    let mut parsed = Vec::new();
+    while !input.is_empty() {
+        match input.parse::<T>() {
+            Ok(attribute) => parsed.push(Ok(attribute)),
+            Err(error) => {
+                parsed.push(Err(error));
+                skip_past_comma(input)?;
+                continue;
+            }
+        }
+        if !input.is_empty() {
+            input.parse::<Token![,]>()?;
+        }
+    }
+    Ok(parsed)
+}
+
+/// Advance the input past the next top level comma, or to the end
+fn skip_past_comma(input: ParseStream) -> syn::Result<()> {
+    input.step(|cursor| {
+        let mut rest = *cursor;
+        while let Some((token, next)) = rest.token_tree() {
+            rest = next;
+            if matches!(&token, proc_macro2::TokenTree::Punct(punct) if punct.as_char() == ',') {
+                break;
+            }
+        }
+        Ok(((), rest))
+    })
+}
+
 /// Holds one macro configuration attribute for a field (i.e. `name: String`)
 ///
 /// Enum variants match configuration attribute keys exactly, this allows us to guarantee our error
@@ -142,6 +794,60 @@ enum ParsedAttribute {
     display(syn::Path), // #[cache_diff(display="...")]
     #[allow(non_camel_case_types)]
     ignore(Ignored), // #[cache_diff(ignore)]
+    #[allow(non_camel_case_types)]
+    category(String), // #[cache_diff(category="...")]
+    #[allow(non_camel_case_types)]
+    code(String), // #[cache_diff(code="...")]
+    #[allow(non_camel_case_types)]
+    lines, // #[cache_diff(lines)]
+    #[allow(non_camel_case_types)]
+    sample(syn::Path), // #[cache_diff(sample = <function>)]
+    #[allow(non_camel_case_types)]
+    display_pair(syn::Path), // #[cache_diff(display_pair = <function>)]
+    #[allow(non_camel_case_types)]
+    delegate(Delegate), // #[cache_diff(delegate = "...")]
+    #[allow(non_camel_case_types)]
+    project(syn::Path), // #[cache_diff(project = <function>)]
+    #[allow(non_camel_case_types)]
+    empty_is_none, // #[cache_diff(empty_is_none)]
+    #[allow(non_camel_case_types)]
+    ttl(u64), // #[cache_diff(ttl = "...")]
+    #[allow(non_camel_case_types)]
+    max(syn::Lit), // #[cache_diff(max = 200)]
+    #[allow(non_camel_case_types)]
+    max_uses(u64), // #[cache_diff(max_uses = 200)]
+    #[allow(non_camel_case_types)]
+    filter(syn::Path), // #[cache_diff(filter = <function>)]
+    #[allow(non_camel_case_types)]
+    with(syn::Path), // #[cache_diff(with = <module>)]
+    #[allow(non_camel_case_types)]
+    labels((String, String)), // #[cache_diff(labels = "was/now")]
+    #[allow(non_camel_case_types)]
+    nested, // #[cache_diff(nested)]
+    #[allow(non_camel_case_types)]
+    alias(String), // #[cache_diff(alias = "...")]
+    #[allow(non_camel_case_types)]
+    normalize_path(Option<String>), // #[cache_diff(normalize_path)] or #[cache_diff(normalize_path = "...")]
+    #[allow(non_camel_case_types)]
+    compare(syn::Path), // #[cache_diff(compare = <function>)]
+    #[allow(non_camel_case_types)]
+    custom(syn::Path), // #[cache_diff(custom = <function>)]
+    #[allow(non_camel_case_types)]
+    fmt(String), // #[cache_diff(fmt = "...")]
+    #[allow(non_camel_case_types)]
+    debug, // #[cache_diff(debug)]
+    #[allow(non_camel_case_types)]
+    semver(String), // #[cache_diff(semver = "major")]
+    #[allow(non_camel_case_types)]
+    skip_if(syn::Path), // #[cache_diff(skip_if = <function>)]
+    #[allow(non_camel_case_types)]
+    group(String), // #[cache_diff(group = "...")]
+}
+
+impl AttributeKey for ParsedAttribute {
+    fn is_known(key: &str) -> bool {
+        KnownAttribute::from_str(key).is_ok()
+    }
 }
 
 /// List all valid attributes for a field, mostly for error messages
@@ -157,10 +863,9 @@ impl syn::parse::Parse for ParsedAttribute {
         let name: Ident = input.parse()?;
         let name_str = name.to_string();
         match KnownAttribute::from_str(&name_str).map_err(|_| {
-            let extra = match name_str.as_ref() {
-                "custom" => "\nThe cache_diff attribute `custom` is available on the struct, not the field",
-                _ => ""
-            };
+            let extra = crate::did_you_mean(&name_str, KnownAttribute::iter().map(|k| k.to_string()))
+                .map(|suggestion| format!("\nDid you mean `{suggestion}`?"))
+                .unwrap_or_default();
 
             syn::Error::new(
                 name.span(),
@@ -178,6 +883,145 @@ impl syn::parse::Parse for ParsedAttribute {
                 input.parse::<syn::Token![=]>()?;
                 Ok(ParsedAttribute::display(input.parse()?))
             }
+            KnownAttribute::category => {
+                input.parse::<syn::Token![=]>()?;
+                Ok(ParsedAttribute::category(
+                    input.parse::<syn::LitStr>()?.value(),
+                ))
+            }
+            KnownAttribute::code => {
+                input.parse::<syn::Token![=]>()?;
+                Ok(ParsedAttribute::code(input.parse::<syn::LitStr>()?.value()))
+            }
+            KnownAttribute::lines => Ok(ParsedAttribute::lines),
+            KnownAttribute::sample => {
+                input.parse::<syn::Token![=]>()?;
+                Ok(ParsedAttribute::sample(input.parse()?))
+            }
+            KnownAttribute::display_pair => {
+                input.parse::<syn::Token![=]>()?;
+                Ok(ParsedAttribute::display_pair(input.parse()?))
+            }
+            KnownAttribute::project => {
+                input.parse::<syn::Token![=]>()?;
+                Ok(ParsedAttribute::project(input.parse()?))
+            }
+            KnownAttribute::empty_is_none => Ok(ParsedAttribute::empty_is_none),
+            KnownAttribute::nested => Ok(ParsedAttribute::nested),
+            KnownAttribute::debug => Ok(ParsedAttribute::debug),
+            KnownAttribute::ttl => {
+                input.parse::<syn::Token![=]>()?;
+                Ok(ParsedAttribute::ttl(parse_duration(
+                    &input.parse::<syn::LitStr>()?,
+                )?))
+            }
+            KnownAttribute::max => {
+                input.parse::<syn::Token![=]>()?;
+                match input.parse::<syn::Lit>()? {
+                    limit @ (syn::Lit::Int(_) | syn::Lit::Float(_)) => {
+                        Ok(ParsedAttribute::max(limit))
+                    }
+                    other => Err(syn::Error::new(
+                        other.span(),
+                        "Invalid cache_diff `max` value, expected a number i.e. `200`",
+                    )),
+                }
+            }
+            KnownAttribute::filter => {
+                input.parse::<syn::Token![=]>()?;
+                Ok(ParsedAttribute::filter(input.parse()?))
+            }
+            KnownAttribute::with => {
+                input.parse::<syn::Token![=]>()?;
+                Ok(ParsedAttribute::with(input.parse()?))
+            }
+            KnownAttribute::skip_if => {
+                input.parse::<syn::Token![=]>()?;
+                Ok(ParsedAttribute::skip_if(input.parse()?))
+            }
+            KnownAttribute::group => {
+                input.parse::<syn::Token![=]>()?;
+                let name = input.parse::<syn::LitStr>()?;
+                if name.value().trim().is_empty() {
+                    return Err(syn::Error::new(
+                        name.span(),
+                        "Invalid cache_diff `group` value, expected a name i.e. `\"OS\"`",
+                    ));
+                }
+                Ok(ParsedAttribute::group(name.value()))
+            }
+            KnownAttribute::semver => {
+                input.parse::<syn::Token![=]>()?;
+                let part = input.parse::<syn::LitStr>()?;
+                match part.value().as_str() {
+                    value @ ("major" | "minor" | "patch") => {
+                        Ok(ParsedAttribute::semver(value.to_string()))
+                    }
+                    value => Err(syn::Error::new(
+                        part.span(),
+                        format!("Invalid cache_diff `semver` value `{value}`, expected `major`, `minor`, or `patch`"),
+                    )),
+                }
+            }
+            KnownAttribute::fmt => {
+                input.parse::<syn::Token![=]>()?;
+                let template = input.parse::<syn::LitStr>()?;
+                crate::cache_diff_container::validate_template(&template.value())
+                    .map_err(|message| syn::Error::new(template.span(), message))?;
+                Ok(ParsedAttribute::fmt(template.value()))
+            }
+            KnownAttribute::custom => {
+                input.parse::<syn::Token![=]>()?;
+                Ok(ParsedAttribute::custom(input.parse()?))
+            }
+            KnownAttribute::compare => {
+                input.parse::<syn::Token![=]>()?;
+                Ok(ParsedAttribute::compare(input.parse()?))
+            }
+            KnownAttribute::normalize_path => {
+                if input.peek(syn::Token![=]) {
+                    input.parse::<syn::Token![=]>()?;
+                    Ok(ParsedAttribute::normalize_path(Some(
+                        input.parse::<syn::LitStr>()?.value(),
+                    )))
+                } else {
+                    Ok(ParsedAttribute::normalize_path(None))
+                }
+            }
+            KnownAttribute::alias => {
+                input.parse::<syn::Token![=]>()?;
+                let value = input.parse::<syn::LitStr>()?;
+                match value.value().trim() {
+                    "" => Err(syn::Error::new(
+                        value.span(),
+                        "Invalid cache_diff `alias` value, expected the field's previous name i.e. `ruby_version`",
+                    )),
+                    alias => Ok(ParsedAttribute::alias(alias.to_string())),
+                }
+            }
+            KnownAttribute::labels => {
+                input.parse::<syn::Token![=]>()?;
+                Ok(ParsedAttribute::labels(parse_labels(
+                    &input.parse::<syn::LitStr>()?,
+                )?))
+            }
+            KnownAttribute::max_uses => {
+                input.parse::<syn::Token![=]>()?;
+                let limit = input.parse::<syn::LitInt>()?;
+                match limit.base10_parse::<u64>()? {
+                    0 => Err(syn::Error::new(
+                        limit.span(),
+                        "Invalid cache_diff `max_uses` value, the cache would never be kept. Use a count of at least 1",
+                    )),
+                    count => Ok(ParsedAttribute::max_uses(count)),
+                }
+            }
+            KnownAttribute::delegate => {
+                input.parse::<syn::Token![=]>()?;
+                Ok(ParsedAttribute::delegate(Delegate::parse(
+                    &input.parse::<syn::LitStr>()?,
+                )?))
+            }
             KnownAttribute::ignore => {
                 if input.peek(syn::Token![=]) {
                     input.parse::<syn::Token![=]>()?;
@@ -205,6 +1049,90 @@ pub(crate) enum Ignored {
     IgnoreOther,
 }
 
+/// The value of a `#[serde(rename = "...")]` attribute on a field if there is one
+fn serde_rename_value(field: &Field) -> Option<String> {
+    field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("serde"))
+        .filter_map(|attr| {
+            attr.parse_args_with(Punctuated::<syn::Meta, Token![,]>::parse_terminated)
+                .ok()
+        })
+        .flatten()
+        .find_map(|meta| match meta {
+            syn::Meta::NameValue(syn::MetaNameValue {
+                path,
+                value:
+                    syn::Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Str(value),
+                        ..
+                    }),
+                ..
+            }) if path.is_ident("rename") => Some(value.value()),
+            _ => None,
+        })
+}
+
+/// A function inside of a `with = <module>` module i.e. `versions::compare`
+///
+/// Spanned at the module so errors about a missing or mismatched function point at the attribute
+fn module_fn(module: &syn::Path, function: &str) -> syn::Path {
+    let mut path = module.clone();
+    path.segments
+        .push(Ident::new(function, module.span()).into());
+    path
+}
+
+/// The old and new labels from `labels = "was/now"`, two non-empty words separated by `/`
+fn parse_labels(value: &syn::LitStr) -> syn::Result<(String, String)> {
+    let text = value.value();
+    match text.split_once('/') {
+        Some((old, now))
+            if !old.trim().is_empty() && !now.trim().is_empty() && !now.contains('/') =>
+        {
+            Ok((old.trim().to_string(), now.trim().to_string()))
+        }
+        _ => Err(syn::Error::new(
+            value.span(),
+            format!("Invalid cache_diff `labels` value `{text}`, expected an old and new label separated by `/` i.e. `was/now`"),
+        )),
+    }
+}
+
+/// Seconds in a duration such as `30d`, a whole number followed by `s`, `m`, `h`, `d` or `w`
+fn parse_duration(value: &syn::LitStr) -> syn::Result<u64> {
+    let text = value.value();
+    let (count, unit) = text.split_at(text.trim_end_matches(char::is_alphabetic).len());
+    let seconds = match unit {
+        "s" => Some(1),
+        "m" => Some(60),
+        "h" => Some(60 * 60),
+        "d" => Some(24 * 60 * 60),
+        "w" => Some(7 * 24 * 60 * 60),
+        _ => None,
+    };
+    seconds
+        .zip(count.parse::<u64>().ok())
+        .and_then(|(seconds, count)| count.checked_mul(seconds))
+        .filter(|seconds| *seconds > 0)
+        .ok_or_else(|| {
+            syn::Error::new(
+                value.span(),
+                format!("Invalid cache_diff `ttl` value `{text}`, expected a whole number followed by a unit i.e. `30d`. Units are `s`, `m`, `h`, `d` and `w`"),
+            )
+        })
+}
+
+/// `Some("f64")` for `f32` and `f64` fields, which are error prone to compare exactly
+pub(crate) fn float_type(ty: &syn::Type) -> Option<&'static str> {
+    match ty {
+        syn::Type::Path(type_path) if type_path.path.is_ident("f32") => Some("f32"),
+        syn::Type::Path(type_path) if type_path.path.is_ident("f64") => Some("f64"),
+        _ => None,
+    }
+}
+
 fn is_pathbuf(ty: &syn::Type) -> bool {
     if let syn::Type::Path(type_path) = ty {
         if let Some(segment) = type_path.path.segments.last() {
@@ -217,7 +1145,6 @@ fn is_pathbuf(ty: &syn::Type) -> bool {
 #[cfg(test)]
 mod test {
     use super::*;
-    use indoc::formatdoc;
     use pretty_assertions::assert_eq;
     use syn::Attribute;
 
@@ -239,10 +1166,34 @@ mod test {
         );
         let expected = ParsedField::Active(ActiveField {
             name: "Ruby version".to_string(),
-            display_fn: syn::parse_str("std::convert::identity").unwrap(),
+            display_fn: DisplayFn::Identity,
             field_identifier: input.ident.to_owned().unwrap(),
+            category: None,
+            code: "version".to_string(),
+            lines: false,
+            sample: None,
+            display_pair: None,
+            delegate: None,
+            project: None,
+            empty_is_none: false,
+            ttl: None,
+            max: None,
+            max_uses: None,
+            filter: None,
+            compare: None,
+            labels: None,
+            nested: false,
+            alias: None,
+            custom: None,
+            fmt: None,
+            semver: None,
+            skip_if: None,
+            group: None,
         });
-        assert_eq!(expected, ParsedField::from_field(&input).unwrap());
+        assert_eq!(
+            expected,
+            ParsedField::from_field(&input, false, None).unwrap()
+        );
     }
 
     #[test]
@@ -257,101 +1208,906 @@ mod test {
         );
         let expected = ParsedField::Active(ActiveField {
             name: "version".to_string(),
-            display_fn: syn::parse_str("my_function").unwrap(),
+            display_fn: DisplayFn::Custom(syn::parse_str("my_function").unwrap()),
             field_identifier: input.ident.to_owned().unwrap(),
+            category: None,
+            code: "version".to_string(),
+            lines: false,
+            sample: None,
+            display_pair: None,
+            delegate: None,
+            project: None,
+            empty_is_none: false,
+            ttl: None,
+            max: None,
+            max_uses: None,
+            filter: None,
+            compare: None,
+            labels: None,
+            nested: false,
+            alias: None,
+            custom: None,
+            fmt: None,
+            semver: None,
+            skip_if: None,
+            group: None,
         });
-        assert_eq!(expected, ParsedField::from_field(&input).unwrap());
+        assert_eq!(
+            expected,
+            ParsedField::from_field(&input, false, None).unwrap()
+        );
     }
 
     #[test]
-    fn test_ignore_with_value() {
+    fn test_parse_display_pair() {
         let input = attribute_on_field(
             syn::parse_quote! {
-                #[cache_diff(ignore = "value")]
+                #[cache_diff(display_pair = changed_suffix)]
             },
             syn::parse_quote! {
-                version: String
+                install_dir: PathBuf
             },
         );
+        let expected = ParsedField::Active(ActiveField {
+            name: "install dir".to_string(),
+            display_fn: DisplayFn::PathBuf,
+            field_identifier: input.ident.to_owned().unwrap(),
+            category: None,
+            code: "install_dir".to_string(),
+            lines: false,
+            sample: None,
+            display_pair: Some(syn::parse_str("changed_suffix").unwrap()),
+            delegate: None,
+            project: None,
+            empty_is_none: false,
+            ttl: None,
+            max: None,
+            max_uses: None,
+            filter: None,
+            compare: None,
+            labels: None,
+            nested: false,
+            alias: None,
+            custom: None,
+            fmt: None,
+            semver: None,
+            skip_if: None,
+            group: None,
+        });
         assert_eq!(
-            ParsedField::IgnoredOther,
-            ParsedField::from_field(&input).unwrap()
+            expected,
+            ParsedField::from_field(&input, false, None).unwrap()
         );
     }
 
     #[test]
-    fn test_parse_all_ignore_no_value() {
+    fn test_parse_delegate() {
         let input = attribute_on_field(
             syn::parse_quote! {
-                #[cache_diff(ignore)]
+                #[cache_diff(delegate = "value().inner")]
             },
             syn::parse_quote! {
-                version: String
+                version: Versioned<String>
             },
         );
+        let ParsedField::Active(active) = ParsedField::from_field(&input, false, None).unwrap()
+        else {
+            panic!("Expected an active field");
+        };
+        let delegate = active.delegate.unwrap();
         assert_eq!(
-            ParsedField::IgnoredOther,
-            ParsedField::from_field(&input).unwrap()
+            quote::quote! { #delegate }.to_string(),
+            quote::quote! { .value().inner }.to_string()
         );
-    }
 
-    #[test]
-    fn test_parse_all_ignore_custom() {
         let input = attribute_on_field(
             syn::parse_quote! {
-                #[cache_diff(ignore = "custom")]
+                #[cache_diff(delegate = "inner(x)")]
             },
             syn::parse_quote! {
-                version: String
+                version: Versioned<String>
             },
         );
         assert_eq!(
-            ParsedField::IgnoredCustom,
-            ParsedField::from_field(&input).unwrap()
+            ParsedField::from_field(&input, false, None).unwrap_err().to_string(),
+            "Invalid cache_diff `delegate` value `inner(x)`, expected a field name i.e. `inner` or an accessor i.e. `inner()`, separated by `.`"
         );
     }
 
     #[test]
-    fn test_parse_accidental_custom() {
+    fn test_parse_category() {
         let input = attribute_on_field(
             syn::parse_quote! {
-                #[cache_diff(custom = "IDK")]
+                #[cache_diff(category = "Runtime")]
             },
             syn::parse_quote! {
                 version: String
             },
         );
-
-        let result = ParsedField::from_field(&input);
-        assert!(result.is_err(), "Expected an error, got {result:?}");
+        let expected = ParsedField::Active(ActiveField {
+            name: "version".to_string(),
+            display_fn: DisplayFn::Identity,
+            field_identifier: input.ident.to_owned().unwrap(),
+            category: Some("Runtime".to_string()),
+            code: "version".to_string(),
+            lines: false,
+            sample: None,
+            display_pair: None,
+            delegate: None,
+            project: None,
+            empty_is_none: false,
+            ttl: None,
+            max: None,
+            max_uses: None,
+            filter: None,
+            compare: None,
+            labels: None,
+            nested: false,
+            alias: None,
+            custom: None,
+            fmt: None,
+            semver: None,
+            skip_if: None,
+            group: None,
+        });
         assert_eq!(
-            format!("{}", result.err().unwrap()).trim(),
-            formatdoc! {"
-                Unknown cache_diff attribute: `custom`. Must be one of `rename`, `display`, `ignore`
-                The cache_diff attribute `custom` is available on the struct, not the field
-            "}
-            .trim()
+            expected,
+            ParsedField::from_field(&input, false, None).unwrap()
         );
     }
 
     #[test]
-    fn test_parse_all_unknown() {
+    fn test_parse_lines() {
         let input = attribute_on_field(
             syn::parse_quote! {
-                #[cache_diff(unknown = "IDK")]
+                #[cache_diff(lines)]
             },
             syn::parse_quote! {
-                version: String
+                gemfile_lock: String
             },
         );
-        let result = ParsedField::from_field(&input);
-        assert!(result.is_err(), "Expected an error, got {result:?}");
+        let expected = ParsedField::Active(ActiveField {
+            name: "gemfile lock".to_string(),
+            display_fn: DisplayFn::Identity,
+            field_identifier: input.ident.to_owned().unwrap(),
+            category: None,
+            code: "gemfile_lock".to_string(),
+            lines: true,
+            sample: None,
+            display_pair: None,
+            delegate: None,
+            project: None,
+            empty_is_none: false,
+            ttl: None,
+            max: None,
+            max_uses: None,
+            filter: None,
+            compare: None,
+            labels: None,
+            nested: false,
+            alias: None,
+            custom: None,
+            fmt: None,
+            semver: None,
+            skip_if: None,
+            group: None,
+        });
         assert_eq!(
-            format!("{}", result.err().unwrap()),
-            r#"Unknown cache_diff attribute: `unknown`. Must be one of `rename`, `display`, `ignore`"#
+            expected,
+            ParsedField::from_field(&input, false, None).unwrap()
         );
     }
 
+    #[test]
+    fn test_parse_empty_is_none() {
+        let input = attribute_on_field(
+            syn::parse_quote! {
+                #[cache_diff(empty_is_none)]
+            },
+            syn::parse_quote! {
+                mirror: Option<String>
+            },
+        );
+        let ParsedField::Active(active) = ParsedField::from_field(&input, false, None).unwrap()
+        else {
+            panic!("Expected an active field");
+        };
+        assert!(active.empty_is_none);
+    }
+
+    #[test]
+    fn test_parse_ttl() {
+        let input = attribute_on_field(
+            syn::parse_quote! {
+                #[cache_diff(ttl = "30d")]
+            },
+            syn::parse_quote! {
+                created_at: SystemTime
+            },
+        );
+        let ParsedField::Active(active) = ParsedField::from_field(&input, false, None).unwrap()
+        else {
+            panic!("Expected an active field");
+        };
+        assert_eq!(active.ttl, Some(30 * 24 * 60 * 60));
+
+        for invalid in ["30", "d", "0h", "1.5d", "30 days"] {
+            let input = attribute_on_field(
+                syn::parse_quote! {
+                    #[cache_diff(ttl = #invalid)]
+                },
+                syn::parse_quote! {
+                    created_at: SystemTime
+                },
+            );
+            assert_eq!(
+                ParsedField::from_field(&input, false, None).unwrap_err().to_string(),
+                format!("Invalid cache_diff `ttl` value `{invalid}`, expected a whole number followed by a unit i.e. `30d`. Units are `s`, `m`, `h`, `d` and `w`")
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_max() {
+        let input = attribute_on_field(
+            syn::parse_quote! {
+                #[cache_diff(max = 200)]
+            },
+            syn::parse_quote! {
+                cache_usage_count: usize
+            },
+        );
+        let ParsedField::Active(active) = ParsedField::from_field(&input, false, None).unwrap()
+        else {
+            panic!("Expected an active field");
+        };
+        assert_eq!(active.max, Some(syn::parse_quote! { 200 }));
+
+        let input = attribute_on_field(
+            syn::parse_quote! {
+                #[cache_diff(max = 200, ttl = "1d")]
+            },
+            syn::parse_quote! {
+                cache_usage_count: usize
+            },
+        );
+        assert_eq!(
+            ParsedField::from_field(&input, false, None).unwrap_err().to_string(),
+            "The cache_diff attributes `max` and `ttl` cannot be used together, both replace how the field's difference is shown"
+        );
+    }
+
+    #[test]
+    fn test_parse_max_uses() {
+        let input = attribute_on_field(
+            syn::parse_quote! {
+                #[cache_diff(max_uses = 200)]
+            },
+            syn::parse_quote! {
+                cache_usage_count: usize
+            },
+        );
+        let ParsedField::Active(active) = ParsedField::from_field(&input, false, None).unwrap()
+        else {
+            panic!("Expected an active field");
+        };
+        assert_eq!(active.max_uses, Some(200));
+
+        let input = attribute_on_field(
+            syn::parse_quote! {
+                #[cache_diff(max_uses = 0)]
+            },
+            syn::parse_quote! {
+                cache_usage_count: usize
+            },
+        );
+        assert_eq!(
+            ParsedField::from_field(&input, false, None).unwrap_err().to_string(),
+            "Invalid cache_diff `max_uses` value, the cache would never be kept. Use a count of at least 1"
+        );
+    }
+
+    #[test]
+    fn test_parse_with() {
+        let input = attribute_on_field(
+            syn::parse_quote! {
+                #[cache_diff(with = versions)]
+            },
+            syn::parse_quote! {
+                version: String
+            },
+        );
+        let ParsedField::Active(active) = ParsedField::from_field(&input, false, None).unwrap()
+        else {
+            panic!("Expected an active field");
+        };
+        assert_eq!(
+            active.display_fn,
+            DisplayFn::Custom(syn::parse_str("versions::display").unwrap())
+        );
+        assert_eq!(
+            active.compare,
+            Some(syn::parse_str("versions::compare").unwrap())
+        );
+
+        let input = attribute_on_field(
+            syn::parse_quote! {
+                #[cache_diff(with = versions, display = my_function)]
+            },
+            syn::parse_quote! {
+                version: String
+            },
+        );
+        assert_eq!(
+            ParsedField::from_field(&input, false, None).unwrap_err().to_string(),
+            "The cache_diff attributes `display` and `with` cannot be used together, the `with` module provides the `display` function"
+        );
+    }
+
+    #[test]
+    fn test_parse_compare() {
+        let input = attribute_on_field(
+            syn::parse_quote! {
+                #[cache_diff(compare = same_minor)]
+            },
+            syn::parse_quote! {
+                version: String
+            },
+        );
+        let ParsedField::Active(active) = ParsedField::from_field(&input, false, None).unwrap()
+        else {
+            panic!("Expected an active field");
+        };
+        assert_eq!(active.display_fn, DisplayFn::Identity);
+        assert_eq!(active.compare, Some(syn::parse_str("same_minor").unwrap()));
+        assert_eq!(active.explain_compare(), "`same_minor`");
+
+        let input = attribute_on_field(
+            syn::parse_quote! {
+                #[cache_diff(compare = same_minor, with = versions)]
+            },
+            syn::parse_quote! {
+                version: String
+            },
+        );
+        assert_eq!(
+            ParsedField::from_field(&input, false, None).unwrap_err().to_string(),
+            "The cache_diff attributes `with` and `compare` cannot be used together, both decide when the field differs"
+        );
+    }
+
+    #[test]
+    fn test_parse_labels() {
+        let input = attribute_on_field(
+            syn::parse_quote! {
+                #[cache_diff(labels = "was/now")]
+            },
+            syn::parse_quote! {
+                stack: String
+            },
+        );
+        let ParsedField::Active(active) = ParsedField::from_field(&input, false, None).unwrap()
+        else {
+            panic!("Expected an active field");
+        };
+        assert_eq!(active.labels, Some(("was".to_string(), "now".to_string())));
+
+        for invalid in ["was", "was/", "/now", "was/now/then"] {
+            let input = attribute_on_field(
+                syn::parse_quote! {
+                    #[cache_diff(labels = #invalid)]
+                },
+                syn::parse_quote! {
+                    stack: String
+                },
+            );
+            assert_eq!(
+                ParsedField::from_field(&input, false, None).unwrap_err().to_string(),
+                format!("Invalid cache_diff `labels` value `{invalid}`, expected an old and new label separated by `/` i.e. `was/now`")
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_nested() {
+        let input = attribute_on_field(
+            syn::parse_quote! {
+                #[cache_diff(nested, rename = "Ruby")]
+            },
+            syn::parse_quote! {
+                ruby: RubyMetadata
+            },
+        );
+        let ParsedField::Active(active) = ParsedField::from_field(&input, false, None).unwrap()
+        else {
+            panic!("Expected an active field");
+        };
+        assert!(active.nested);
+
+        let input = attribute_on_field(
+            syn::parse_quote! {
+                #[cache_diff(nested, display = my_function)]
+            },
+            syn::parse_quote! {
+                ruby: RubyMetadata
+            },
+        );
+        assert_eq!(
+            ParsedField::from_field(&input, false, None).unwrap_err().to_string(),
+            "The cache_diff attribute `display` cannot be used with `nested`, the field's own CacheDiff implementation compares and shows its values"
+        );
+    }
+
+    #[test]
+    fn test_parse_normalize_path() {
+        let input = attribute_on_field(
+            syn::parse_quote! {
+                #[cache_diff(normalize_path = "/layers")]
+            },
+            syn::parse_quote! {
+                install_dir: PathBuf
+            },
+        );
+        let ParsedField::Active(active) = ParsedField::from_field(&input, false, None).unwrap()
+        else {
+            panic!("Expected an active field");
+        };
+        assert_eq!(
+            active.display_fn,
+            DisplayFn::NormalizedPath(Some("/layers".to_string()))
+        );
+        assert_eq!(
+            active.explain_display(),
+            "`normalize_path` relative to `/layers`"
+        );
+
+        let input = attribute_on_field(
+            syn::parse_quote! {
+                #[cache_diff(normalize_path, display = my_function)]
+            },
+            syn::parse_quote! {
+                install_dir: PathBuf
+            },
+        );
+        assert_eq!(
+            ParsedField::from_field(&input, false, None).unwrap_err().to_string(),
+            "The cache_diff attributes `normalize_path` and `display` cannot be used together, both set how the field's values are displayed"
+        );
+    }
+
+    #[test]
+    fn test_parse_alias() {
+        let input = attribute_on_field(
+            syn::parse_quote! {
+                #[cache_diff(alias = "ruby_version")]
+            },
+            syn::parse_quote! {
+                version: String
+            },
+        );
+        let ParsedField::Active(active) = ParsedField::from_field(&input, false, None).unwrap()
+        else {
+            panic!("Expected an active field");
+        };
+        assert_eq!(
+            active.alias,
+            Some(("ruby_version".to_string(), "version".to_string()))
+        );
+
+        let mut input = attribute_on_field(
+            syn::parse_quote! {
+                #[cache_diff(alias = "ruby_version")]
+            },
+            syn::parse_quote! {
+                version: String
+            },
+        );
+        input
+            .attrs
+            .push(syn::parse_quote! { #[serde(rename = "ruby")] });
+        let ParsedField::Active(active) = ParsedField::from_field(&input, false, None).unwrap()
+        else {
+            panic!("Expected an active field");
+        };
+        assert_eq!(
+            active.alias,
+            Some(("ruby_version".to_string(), "ruby".to_string()))
+        );
+
+        let input = attribute_on_field(
+            syn::parse_quote! {
+                #[cache_diff(alias = " ")]
+            },
+            syn::parse_quote! {
+                version: String
+            },
+        );
+        assert_eq!(
+            ParsedField::from_field(&input, false, None).unwrap_err().to_string(),
+            "Invalid cache_diff `alias` value, expected the field's previous name i.e. `ruby_version`"
+        );
+    }
+
+    #[test]
+    fn test_explain() {
+        let explain = |attribute: Attribute| {
+            let input = attribute_on_field(
+                attribute,
+                syn::parse_quote! {
+                    version: String
+                },
+            );
+            let ParsedField::Active(active) = ParsedField::from_field(&input, false, None).unwrap()
+            else {
+                panic!("Expected an active field");
+            };
+            (active.explain_compare(), active.explain_display())
+        };
+        assert_eq!(
+            explain(syn::parse_quote! { #[cache_diff(rename = "Version")] }),
+            ("`!=`".to_string(), "`Display`".to_string())
+        );
+        assert_eq!(
+            explain(
+                syn::parse_quote! { #[cache_diff(delegate = "value().inner", project = semver::major, labels = "was/now")] }
+            ),
+            (
+                "`!=` (delegated to `.value().inner`, projected by `semver::major`)".to_string(),
+                "`Display` labeled `was` and `now`".to_string()
+            )
+        );
+        assert_eq!(
+            explain(syn::parse_quote! { #[cache_diff(with = versions)] }),
+            (
+                "`versions::compare`".to_string(),
+                "`versions::display`".to_string()
+            )
+        );
+        assert_eq!(
+            explain(syn::parse_quote! { #[cache_diff(max = 200.5)] }),
+            ("`max` of 200.5".to_string(), "a limit message".to_string())
+        );
+    }
+
+    #[test]
+    fn test_serde_rename() {
+        let mut input: Field = syn::parse_quote! {
+            #[serde(default, rename = "ruby_version")]
+            version: String
+        };
+        let ParsedField::Active(active) = ParsedField::from_field(&input, false, None).unwrap()
+        else {
+            panic!("Expected an active field");
+        };
+        assert_eq!(active.name, "version");
+
+        let ParsedField::Active(active) = ParsedField::from_field(&input, true, None).unwrap()
+        else {
+            panic!("Expected an active field");
+        };
+        assert_eq!(active.name, "ruby_version");
+
+        input.attrs.push(syn::parse_quote! {
+            #[cache_diff(rename = "Ruby version")]
+        });
+        let ParsedField::Active(active) = ParsedField::from_field(&input, true, None).unwrap()
+        else {
+            panic!("Expected an active field");
+        };
+        assert_eq!(active.name, "Ruby version");
+    }
+
+    #[test]
+    fn test_ignore_with_value() {
+        let input = attribute_on_field(
+            syn::parse_quote! {
+                #[cache_diff(ignore = "value")]
+            },
+            syn::parse_quote! {
+                version: String
+            },
+        );
+        assert_eq!(
+            ParsedField::IgnoredOther,
+            ParsedField::from_field(&input, false, None).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_all_ignore_no_value() {
+        let input = attribute_on_field(
+            syn::parse_quote! {
+                #[cache_diff(ignore)]
+            },
+            syn::parse_quote! {
+                version: String
+            },
+        );
+        assert_eq!(
+            ParsedField::IgnoredOther,
+            ParsedField::from_field(&input, false, None).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_all_ignore_custom() {
+        let input = attribute_on_field(
+            syn::parse_quote! {
+                #[cache_diff(ignore = "custom")]
+            },
+            syn::parse_quote! {
+                version: String
+            },
+        );
+        assert_eq!(
+            ParsedField::IgnoredCustom,
+            ParsedField::from_field(&input, false, None).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_skip_if() {
+        let input = attribute_on_field(
+            syn::parse_quote! {
+                #[cache_diff(skip_if = url_changed)]
+            },
+            syn::parse_quote! {
+                checksum: String
+            },
+        );
+        let ParsedField::Active(active) = ParsedField::from_field(&input, false, None).unwrap()
+        else {
+            panic!("Expected an active field");
+        };
+        assert_eq!(active.skip_if, Some(syn::parse_str("url_changed").unwrap()));
+        assert_eq!(active.explain_compare(), "`!=` (skipped if `url_changed`)");
+    }
+
+    #[test]
+    fn test_parse_group() {
+        let input = attribute_on_field(
+            syn::parse_quote! {
+                #[cache_diff(group = "OS")]
+            },
+            syn::parse_quote! {
+                os_version: String
+            },
+        );
+        let ParsedField::Active(active) = ParsedField::from_field(&input, false, None).unwrap()
+        else {
+            panic!("Expected an active field");
+        };
+        assert_eq!(active.group, Some("OS".to_string()));
+        assert_eq!(active.explain_display(), "`Display` in group `OS`");
+
+        let input = attribute_on_field(
+            syn::parse_quote! {
+                #[cache_diff(group = "OS", labels = "was/now")]
+            },
+            syn::parse_quote! {
+                os_version: String
+            },
+        );
+        assert_eq!(
+            ParsedField::from_field(&input, false, None).unwrap_err().to_string(),
+            "The cache_diff attributes `labels` and `group` cannot be used together, the group's difference shows the field's value"
+        );
+
+        let input = attribute_on_field(
+            syn::parse_quote! {
+                #[cache_diff(group = " ")]
+            },
+            syn::parse_quote! {
+                os_version: String
+            },
+        );
+        assert_eq!(
+            ParsedField::from_field(&input, false, None)
+                .unwrap_err()
+                .to_string(),
+            "Invalid cache_diff `group` value, expected a name i.e. `\"OS\"`"
+        );
+    }
+
+    #[test]
+    fn test_parse_semver() {
+        let input = attribute_on_field(
+            syn::parse_quote! {
+                #[cache_diff(semver = "major")]
+            },
+            syn::parse_quote! {
+                node_version: String
+            },
+        );
+        let ParsedField::Active(active) = ParsedField::from_field(&input, false, None).unwrap()
+        else {
+            panic!("Expected an active field");
+        };
+        assert_eq!(
+            active.compare,
+            Some(syn::parse_quote! { ::cache_diff::same_semver_major })
+        );
+        assert_eq!(active.explain_compare(), "`semver` major version");
+
+        let input = attribute_on_field(
+            syn::parse_quote! {
+                #[cache_diff(semver = "build")]
+            },
+            syn::parse_quote! {
+                node_version: String
+            },
+        );
+        assert_eq!(
+            ParsedField::from_field(&input, false, None)
+                .unwrap_err()
+                .to_string(),
+            "Invalid cache_diff `semver` value `build`, expected `major`, `minor`, or `patch`"
+        );
+
+        let input = attribute_on_field(
+            syn::parse_quote! {
+                #[cache_diff(semver = "major", compare = same_minor)]
+            },
+            syn::parse_quote! {
+                node_version: String
+            },
+        );
+        assert_eq!(
+            ParsedField::from_field(&input, false, None).unwrap_err().to_string(),
+            "The cache_diff attributes `compare` and `semver` cannot be used together, both decide when the field differs"
+        );
+    }
+
+    #[test]
+    fn test_parse_debug() {
+        let input = attribute_on_field(
+            syn::parse_quote! {
+                #[cache_diff(debug)]
+            },
+            syn::parse_quote! {
+                arch: Arch
+            },
+        );
+        let ParsedField::Active(active) = ParsedField::from_field(&input, false, None).unwrap()
+        else {
+            panic!("Expected an active field");
+        };
+        assert_eq!(active.display_fn, DisplayFn::Debug);
+        assert_eq!(active.explain_display(), "`Debug`");
+
+        let input = attribute_on_field(
+            syn::parse_quote! {
+                #[cache_diff(display = my_function, debug)]
+            },
+            syn::parse_quote! {
+                arch: Arch
+            },
+        );
+        assert_eq!(
+            ParsedField::from_field(&input, false, None).unwrap_err().to_string(),
+            "The cache_diff attributes `display` and `debug` cannot be used together, both set how the field's values are displayed"
+        );
+    }
+
+    #[test]
+    fn test_parse_fmt() {
+        let input = attribute_on_field(
+            syn::parse_quote! {
+                #[cache_diff(fmt = "{name} changed from {old} to {new}")]
+            },
+            syn::parse_quote! {
+                version: String
+            },
+        );
+        let ParsedField::Active(active) = ParsedField::from_field(&input, false, None).unwrap()
+        else {
+            panic!("Expected an active field");
+        };
+        assert_eq!(
+            active.fmt,
+            Some("{name} changed from {old} to {new}".to_string())
+        );
+
+        let input = attribute_on_field(
+            syn::parse_quote! {
+                #[cache_diff(fmt = "{name} is now {version}")]
+            },
+            syn::parse_quote! {
+                version: String
+            },
+        );
+        assert_eq!(
+            ParsedField::from_field(&input, false, None).unwrap_err().to_string(),
+            "Unknown template placeholder `{version}`. Must be one of `{name}`, `{old}`, `{new}`, `{code}`"
+        );
+
+        let input = attribute_on_field(
+            syn::parse_quote! {
+                #[cache_diff(fmt = "{name}: {old} => {new}", labels = "was/now")]
+            },
+            syn::parse_quote! {
+                version: String
+            },
+        );
+        assert_eq!(
+            ParsedField::from_field(&input, false, None).unwrap_err().to_string(),
+            "The cache_diff attributes `labels` and `fmt` cannot be used together, both replace the layout of the field's difference"
+        );
+    }
+
+    #[test]
+    fn test_parse_custom() {
+        let input = attribute_on_field(
+            syn::parse_quote! {
+                #[cache_diff(custom = diff_version)]
+            },
+            syn::parse_quote! {
+                version: String
+            },
+        );
+        let ParsedField::Active(active) = ParsedField::from_field(&input, false, None).unwrap()
+        else {
+            panic!("Expected an active field");
+        };
+        assert_eq!(active.custom, Some(syn::parse_str("diff_version").unwrap()));
+        assert_eq!(active.explain_compare(), "`custom = diff_version`");
+
+        let input = attribute_on_field(
+            syn::parse_quote! {
+                #[cache_diff(custom = diff_version, labels = "was/now")]
+            },
+            syn::parse_quote! {
+                version: String
+            },
+        );
+        assert_eq!(
+            ParsedField::from_field(&input, false, None).unwrap_err().to_string(),
+            "The cache_diff attributes `labels` and `custom` cannot be used together, the `custom` function produces the field's messages"
+        );
+    }
+
+    #[test]
+    fn test_parse_all_unknown() {
+        let input = attribute_on_field(
+            syn::parse_quote! {
+                #[cache_diff(unknown = "IDK")]
+            },
+            syn::parse_quote! {
+                version: String
+            },
+        );
+        let result = ParsedField::from_field(&input, false, None);
+        assert!(result.is_err(), "Expected an error, got {result:?}");
+        assert_eq!(
+            format!("{}", result.err().unwrap()),
+            r#"Unknown cache_diff attribute: `unknown`. Must be one of `rename`, `display`, `ignore`, `category`, `code`, `lines`, `sample`, `display_pair`, `delegate`, `project`, `empty_is_none`, `ttl`, `max`, `max_uses`, `filter`, `with`, `labels`, `nested`, `alias`, `normalize_path`, `compare`, `custom`, `fmt`, `debug`, `semver`, `skip_if`, `group`"#
+        );
+    }
+
+    #[test]
+    fn test_parse_all_errors_on_one_field() {
+        let input = attribute_on_field(
+            syn::parse_quote! {
+                #[cache_diff(renmae = "Version", code = 1, categroy = "Runtime", lines)]
+            },
+            syn::parse_quote! {
+                version: String
+            },
+        );
+        let result = ParsedField::from_field(&input, false, None);
+        assert!(result.is_err(), "Expected an error, got {result:?}");
+        let messages = result
+            .err()
+            .unwrap()
+            .into_iter()
+            .map(|error| error.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(messages.len(), 3, "Expected three errors, got {messages:?}");
+        assert!(messages[0].starts_with("Unknown cache_diff attribute: `renmae`"));
+        assert_eq!(messages[1], "expected string literal");
+        assert!(messages[2].starts_with("Unknown cache_diff attribute: `categroy`"));
+    }
+
     #[test]
     fn test_ignored_other_attributes() {
         let input = attribute_on_field(
@@ -362,7 +2118,7 @@ mod test {
                 version: String
             },
         );
-        let result = ParsedField::from_field(&input);
+        let result = ParsedField::from_field(&input, false, None);
         assert!(result.is_err(), "Expected an error, got {result:?}");
         assert_eq!(
             format!("{}", result.err().unwrap()),
@@ -377,7 +2133,7 @@ mod test {
                 version: String
             },
         );
-        let result = ParsedField::from_field(&input);
+        let result = ParsedField::from_field(&input, false, None);
         assert!(result.is_err(), "Expected an error, got {result:?}");
         assert_eq!(
             format!("{}", result.err().unwrap()),
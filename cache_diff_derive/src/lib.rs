@@ -1,11 +1,64 @@
 use cache_diff_container::CacheDiffContainer;
-use cache_diff_field::ActiveField;
+use cache_diff_field::{ActiveField, DisplayFn};
 use proc_macro::TokenStream;
 use syn::DeriveInput;
 
 mod cache_diff_container;
 mod cache_diff_field;
 
+/// Implement `cache_diff::CacheDiff` for a named struct
+///
+/// The expansion is a single trait impl, plus an inherent impl with `remote = "..."`, and never adds free items (helper functions, constants, or
+/// modules) to the user's scope, so several derives in one module cannot collide. Names that only
+/// exist inside generated function bodies (i.e. `custom_diff_0`) are derived from attribute order,
+/// so the same input always produces the same expansion. For example `cargo expand` on:
+///
+/// ```text
+/// #[derive(CacheDiff)]
+/// struct Metadata {
+///     version: String,
+/// }
+/// ```
+///
+/// Produces (abbreviated):
+///
+/// ```text
+/// impl ::cache_diff::CacheDiff for Metadata {
+///     const FIELDS: &'static [::cache_diff::FieldInfo] = &[::cache_diff::FieldInfo {
+///         identifier: "version", name: "version", category: None, code: "version",
+///     }];
+///     fn diff(&self, old: &Self) -> Vec<String> {
+///         ::cache_diff::render_differences(self, &self.diff_structured(old))
+///     }
+///     fn diff_structured(&self, old: &Self) -> Vec<::cache_diff::Difference> {
+///         self.diff_structured_filtered(old, &::cache_diff::IgnoreSet::new())
+///     }
+///     fn diff_structured_filtered(&self, old: &Self, ignore: &::cache_diff::IgnoreSet) -> Vec<::cache_diff::Difference> {
+///         let mut differences = Vec::with_capacity(0);
+///         self.visit_field_diffs(&mut |fields| {
+///             ::cache_diff::diff_fields(self, old, Self::FIELDS, fields, ignore, &mut differences);
+///         });
+///         differences
+///     }
+///     fn visit_field_diffs(&self, visit: &mut dyn FnMut(&[::cache_diff::FieldDiff<Self>])) {
+///         visit(&[::cache_diff::FieldDiff {
+///             differs: |now: &Self, old: &Self| ::cache_diff::field_ne(&now.version, &old.version),
+///             display: |cache: &Self, f: &mut ::cache_diff::DisplayCallback| f(::cache_diff::field_display(&cache.version)),
+///             lines: None,
+///             display_pair: None,
+///             labels: None,
+///             nested: None,
+///             custom: None,
+///             template: None,
+///             skip: None,
+///             group: None,
+///         }]);
+///     }
+///     fn differs(&self, old: &Self) -> bool {
+///         ::cache_diff::field_ne(&self.version, &old.version)
+///     }
+/// }
+/// ```
 #[proc_macro_derive(CacheDiff, attributes(cache_diff))]
 pub fn cache_diff(item: TokenStream) -> TokenStream {
     create_cache_diff(item.into())
@@ -13,50 +66,746 @@ pub fn cache_diff(item: TokenStream) -> TokenStream {
         .into()
 }
 
+/// A parsed attribute along with the span of its value, or its key when it has no value
+///
+/// Used to point errors at the exact token i.e. the `"oops"` in a second `rename = "oops"`
+pub(crate) struct WithSpan<T>(pub(crate) T, pub(crate) proc_macro2::Span);
+
+impl<T: syn::parse::Parse> syn::parse::Parse for WithSpan<T> {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let fork = input.fork();
+        let span = match fork.parse::<syn::Ident>() {
+            Ok(_) if fork.peek(syn::Token![=]) => {
+                fork.parse::<syn::Token![=]>()?;
+                fork.span()
+            }
+            _ => input.span(),
+        };
+        Ok(WithSpan(input.parse()?, span))
+    }
+}
+
+/// An attribute key with its value, or a key this version of the derive doesn't know
+///
+/// Unknown keys and their values are skipped so parsing continues, the error they would have
+/// raised is kept so it can be reported unless `allow_unknown_attributes` is set.
+pub(crate) enum MaybeKnown<T> {
+    Known(T),
+    Unknown { key: syn::Ident, error: syn::Error },
+}
+
+/// Attributes that can tell whether a key is known before parsing it
+pub(crate) trait AttributeKey {
+    fn is_known(key: &str) -> bool;
+}
+
+impl<T: syn::parse::Parse + AttributeKey> syn::parse::Parse for MaybeKnown<T> {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        match input.fork().parse::<syn::Ident>() {
+            Ok(key) if !T::is_known(&key.to_string()) => {
+                let error = match T::parse(&input.fork()) {
+                    Ok(_) => unreachable!("unknown attribute `{key}` parsed successfully"),
+                    Err(error) => error,
+                };
+                input.parse::<syn::Ident>()?;
+                while !input.is_empty() && !input.peek(syn::Token![,]) {
+                    input.parse::<proc_macro2::TokenTree>()?;
+                }
+                Ok(MaybeKnown::Unknown { key, error })
+            }
+            _ => Ok(MaybeKnown::Known(input.parse()?)),
+        }
+    }
+}
+
+/// Add an error to the ones already found, so they are all reported in one compile
+pub(crate) fn push_error(errors: &mut Option<syn::Error>, error: syn::Error) {
+    match errors.as_mut() {
+        Some(errors) => errors.combine(error),
+        None => *errors = Some(error),
+    }
+}
+
+/// An error on a repeated attribute's value, combined with one on its first definition
+pub(crate) fn duplicate_error(
+    name: impl std::fmt::Display,
+    first: proc_macro2::Span,
+    duplicate: proc_macro2::Span,
+) -> syn::Error {
+    let mut error = syn::Error::new(
+        duplicate,
+        format!("Duplicate cache_diff attribute `{name}`, it can only be used once"),
+    );
+    error.combine(syn::Error::new(
+        first,
+        format!("`{name}` first defined here"),
+    ));
+    error
+}
+
+/// The closest known attribute to a misspelled one, if it's close enough to be a typo
+fn did_you_mean(unknown: &str, known: impl Iterator<Item = String>) -> Option<String> {
+    known
+        .map(|candidate| (edit_distance(unknown, &candidate), candidate))
+        .filter(|(distance, candidate)| *distance <= (candidate.len() / 3).max(1))
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// A span at the tokens' location with call site hygiene
+///
+/// Errors point at user provided tokens such as a `display = <function>` path, while identifiers
+/// generated alongside them still resolve when the struct is defined in a `macro_rules!`.
+fn located_at(tokens: &impl syn::spanned::Spanned) -> proc_macro2::Span {
+    proc_macro2::Span::call_site().located_at(tokens.span())
+}
+
+/// Levenshtein distance between two strings
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<char>>();
+    let mut previous = (0..=b.len()).collect::<Vec<usize>>();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
 fn create_cache_diff(item: proc_macro2::TokenStream) -> syn::Result<proc_macro2::TokenStream> {
     let ast: DeriveInput = syn::parse2(item).unwrap();
     let container = CacheDiffContainer::from_ast(&ast)?;
     let ident = &container.identifier;
 
-    let custom_diff = if let Some(ref custom_fn) = container.custom {
-        quote::quote! {
-            let custom_diff = #custom_fn(old, self);
-            for diff in &custom_diff {
-                differences.push(diff.to_string())
+    // Field capacity is reserved by `diff_fields` on the first difference so an unchanged struct never allocates
+    let custom_results = (0..container.custom.len())
+        .map(|index| quote::format_ident!("custom_diff_{index}"))
+        .collect::<Vec<_>>();
+    // Check each custom function's signature so a mismatch is reported on the attribute
+    let custom_fns = (0..container.custom.len())
+        .map(|index| quote::format_ident!("custom_fn_{index}"))
+        .collect::<Vec<_>>();
+    let custom_signatures = container
+        .custom
+        .iter()
+        .zip(&custom_fns)
+        .map(|(path, custom_fn)| {
+            quote::quote_spanned! { syn::spanned::Spanned::span(path) =>
+                let #custom_fn = |old: &Self, now: &Self| ::cache_diff::CustomDiff::<Self, _>::call_custom(&#path, old, now);
             }
+        })
+        .collect::<Vec<_>>();
+    let custom_calls = if container.parallel && !custom_fns.is_empty() {
+        // Results are joined in attribute order so output is deterministic
+        quote::quote! {
+            let (#(#custom_results,)*) = ::std::thread::scope(|scope| {
+                let handles = (#(scope.spawn(|| #custom_fns(old, self)),)*);
+                let (#(#custom_results,)*) = handles;
+                (#(#custom_results.join().unwrap_or_else(|panic| ::std::panic::resume_unwind(panic)),)*)
+            });
         }
     } else {
-        quote::quote! {}
+        quote::quote! {
+            #(let #custom_results = #custom_fns(old, self);)*
+        }
     };
+    let custom_diff = quote::quote! {
+        #(#custom_signatures)*
+        #custom_calls
+        let mut differences = ::std::vec::Vec::with_capacity(0 #(+ #custom_results.len())*);
+        #(
+            differences.extend(#custom_results.into_iter().map(::cache_diff::Difference::message));
+        )*
+    };
+
+    let style = container.style.as_ref().map(|style| {
+        quote::quote! {
+            fn style(&self) -> ::cache_diff::DiffStyle {
+                #style
+            }
+        }
+    });
+
+    let template = container.template.as_ref().map(|template| {
+        quote::quote! {
+            fn template(&self) -> ::cache_diff::Template {
+                ::cache_diff::Template::parse(#template)
+                    .expect("template is validated by the CacheDiff derive macro")
+            }
+        }
+    });
+
+    let description = container.description.as_ref().map(|description| {
+        quote::quote! {
+            fn description(&self) -> ::std::option::Option<&str> {
+                ::std::option::Option::Some(#description)
+            }
+        }
+    });
 
-    let mut comparisons = Vec::new();
+    // Every field in declaration order, including ignored ones
+    let explanations = container.all_fields.iter().map(|identifier| {
+        let status = match container
+            .fields
+            .iter()
+            .find(|f| &&f.field_identifier == identifier)
+        {
+            Some(f) => {
+                let name = &f.name;
+                let compare = f.explain_compare();
+                let display = f.explain_display();
+                quote::quote! {
+                    ::cache_diff::FieldStatus::Compared {
+                        name: #name,
+                        compare: #compare,
+                        display: #display,
+                    }
+                }
+            }
+            None if container.custom_fields.contains(identifier) => {
+                quote::quote! { ::cache_diff::FieldStatus::Custom }
+            }
+            None => quote::quote! { ::cache_diff::FieldStatus::Ignored },
+        };
+        let identifier = identifier.to_string();
+        quote::quote! {
+            ::cache_diff::FieldExplanation {
+                identifier: #identifier,
+                status: #status,
+            }
+        }
+    });
+
+    let custom_differs = custom_fns.iter().map(|custom_fn| {
+        quote::quote! {
+            !#custom_fn(old, self).is_empty() ||
+        }
+    });
+
+    let content_hash = container.content_hash.then(|| {
+        // `ttl`, `max` and `max_uses` values change every build, they are checked against their limit instead of hashed
+        let values = container
+            .fields
+            .iter()
+            .filter(|f| f.ttl.is_none() && f.max.is_none() && f.max_uses.is_none())
+            .map(|f| {
+                let field_identifier = &f.field_identifier;
+                let delegate = &f.delegate;
+                let value = match &f.filter {
+                    Some(path) => quote::quote! {
+                        &::cache_diff::filter_field(&self.#field_identifier #delegate, #path)
+                    },
+                    None => quote::quote! { &self.#field_identifier #delegate },
+                };
+                let value = match &f.project {
+                    Some(path) => quote::quote! { &#path(#value) },
+                    None => value,
+                };
+                if f.empty_is_none {
+                    quote::quote! { &::cache_diff::empty_is_none(#value) }
+                } else {
+                    value
+                }
+            });
+        quote::quote! {
+            fn content_hash(&self) -> ::std::option::Option<u64> {
+                let mut hasher = ::std::hash::DefaultHasher::new();
+                #(::std::hash::Hash::hash(#values, &mut hasher);)*
+                ::std::option::Option::Some(::std::hash::Hasher::finish(&hasher))
+            }
+        }
+    });
+
+    let sample_changed = container.sample_changed.then(|| {
+        let arms = container.fields.iter().map(|f| {
+            let identifier = f.field_identifier.to_string();
+            let field_identifier = &f.field_identifier;
+            let value = match &f.sample {
+                Some(sample_fn) => quote::quote! { #sample_fn(&self.#field_identifier) },
+                None => quote::quote! { ::std::default::Default::default() },
+            };
+            quote::quote! {
+                #identifier => sample.#field_identifier = #value,
+            }
+        });
+        quote::quote! {
+            fn sample_changed(&self, identifier: &str) -> ::std::option::Option<Self> {
+                let mut sample = ::std::clone::Clone::clone(self);
+                match identifier {
+                    #(#arms)*
+                    _ => return ::std::option::Option::None,
+                }
+                ::std::option::Option::Some(sample)
+            }
+        }
+    });
+
+    // Proc macros can't emit warnings on stable, using a deprecated item at the key's span shows one there
+    let unknown_attributes = container.unknown_attributes.iter().map(|key| {
+        let note = format!(
+            "unknown cache_diff attribute `{key}` is ignored by `allow_unknown_attributes`"
+        );
+        quote::quote_spanned! { key.span() =>
+            {
+                #[deprecated(note = #note)]
+                #[allow(non_upper_case_globals)]
+                const unknown_cache_diff_attribute: () = ();
+                unknown_cache_diff_attribute
+            };
+        }
+    });
+
+    let counters = container
+        .fields
+        .iter()
+        .filter(|f| f.max_uses.is_some())
+        .map(|f| {
+            let field_identifier = &f.field_identifier;
+            quote::quote_spanned! { located_at(field_identifier) =>
+                ::cache_diff::increment_uses(&mut self.#field_identifier, &old.#field_identifier);
+            }
+        })
+        .collect::<Vec<_>>();
+    let record_reuse = (!counters.is_empty()).then(|| {
+        quote::quote! {
+            fn record_reuse(&mut self, old: &Self) {
+                #(#counters)*
+            }
+        }
+    });
+
+    let aliases = container
+        .fields
+        .iter()
+        .filter_map(|f| f.alias.as_ref())
+        .map(|(alias, key)| quote::quote! { (#alias, #key) })
+        .collect::<Vec<_>>();
+    let field_aliases = (!aliases.is_empty()).then(|| {
+        quote::quote! {
+            const FIELD_ALIASES: &'static [(&'static str, &'static str)] = &[#(#aliases),*];
+        }
+    });
+
+    let mut field_diffs = Vec::new();
+    let mut inequalities = Vec::new();
+    let mut limits = Vec::new();
+    let mut field_infos = Vec::new();
     for f in container.fields.iter() {
         let ActiveField {
             name,
             display_fn,
             field_identifier,
+            category,
+            code,
+            lines,
+            sample: _,
+            display_pair,
+            delegate,
+            project,
+            empty_is_none,
+            ttl,
+            max,
+            max_uses,
+            filter,
+            compare,
+            labels,
+            nested,
+            alias: _,
+            custom,
+            fmt,
+            semver: _,
+            skip_if,
+            group,
         } = f;
-        comparisons.push(quote::quote! {
-            if self.#field_identifier != old.#field_identifier {
-                differences.push(
-                    format!("{name} ({old} to {new})",
-                        name = #name,
-                        old = self.fmt_value(&#display_fn(&old.#field_identifier)),
-                        new = self.fmt_value(&#display_fn(&self.#field_identifier))
+        // The compared and displayed value, i.e. `version` or `version.inner` when delegated
+        let value = quote::quote! { #field_identifier #delegate };
+        // A reference to the value on the receiver, filtered when `filter = <function>` is given then
+        // projected when `project = <function>` is given.
+        // Located where errors about the value should be reported.
+        let read = |receiver: &str, span: proc_macro2::Span| {
+            let receiver = syn::Ident::new(receiver, span);
+            let filtered = match filter {
+                Some(path) => quote::quote_spanned! { span =>
+                    &::cache_diff::filter_field(&#receiver.#value, #path)
+                },
+                None => quote::quote_spanned! { span => &#receiver.#value },
+            };
+            match project {
+                Some(path) => quote::quote_spanned! { span => &#path(#filtered) },
+                None => filtered,
+            }
+        };
+        let identifier = field_identifier.to_string();
+        let category_info = match category {
+            Some(category) => quote::quote! { ::std::option::Option::Some(#category) },
+            None => quote::quote! { ::std::option::Option::None },
+        };
+        field_infos.push(quote::quote! {
+            ::cache_diff::FieldInfo {
+                identifier: #identifier,
+                name: #name,
+                category: #category_info,
+                code: #code,
+            }
+        });
+        let lines = if *lines {
+            quote::quote! { ::std::option::Option::Some(::cache_diff::text::unified) }
+        } else {
+            quote::quote! { ::std::option::Option::None }
+        };
+        // Located at the field so a missing PartialEq or Display is reported on it, while keeping
+        // call site hygiene so fields generated by `macro_rules!` still resolve `self`, `old`, etc.
+        let field_span = located_at(field_identifier);
+        let [read_self, read_old, read_now, read_cache] =
+            ["self", "old", "now", "cache"].map(|receiver| read(receiver, field_span));
+        // The value that is compared, empty strings become `None` with `empty_is_none`
+        let [compare_self, compare_old, compare_now] = ["self", "old", "now"].map(|receiver| {
+            let value = read(receiver, field_span);
+            if *empty_is_none {
+                quote::quote_spanned! { field_span => &::cache_diff::empty_is_none(#value) }
+            } else {
+                value
+            }
+        });
+        // Located at the literal so a limit of the wrong type is reported on it
+        let max = max
+            .as_ref()
+            .map(|limit| quote::quote_spanned! { located_at(limit) => &#limit });
+        // A `ttl` field only differs when the old timestamp expired, the new one is always recent.
+        // A `max` field only differs when the new value is over the limit, it changes every build.
+        // A `max_uses` field only differs when the old count reached the limit.
+        let (inequality, differs) = if let Some(path) = custom {
+            let span = located_at(path);
+            (
+                quote::quote_spanned! { span =>
+                    !::cache_diff::field_custom(#path(#read_old, #read_self)).is_empty()
+                },
+                quote::quote_spanned! { span =>
+                    !::cache_diff::field_custom(#path(#read_old, #read_now)).is_empty()
+                },
+            )
+        } else if *nested {
+            (
+                quote::quote_spanned! { field_span =>
+                    ::cache_diff::nested_differs(#read_self, #read_old)
+                },
+                quote::quote_spanned! { field_span =>
+                    ::cache_diff::nested_differs(#read_now, #read_old)
+                },
+            )
+        } else {
+            match (ttl, &max, max_uses, compare) {
+                (Some(seconds), _, _, _) => {
+                    let expired = quote::quote_spanned! { field_span =>
+                        ::cache_diff::ttl_expired(#read_old, #seconds)
+                    };
+                    (expired.clone(), expired)
+                }
+                (None, Some(limit), _, _) => (
+                    quote::quote_spanned! { field_span =>
+                        ::cache_diff::exceeds_max(#read_self, #limit)
+                    },
+                    quote::quote_spanned! { field_span =>
+                        ::cache_diff::exceeds_max(#read_now, #limit)
+                    },
+                ),
+                (None, None, Some(limit), _) => {
+                    let reached = quote::quote_spanned! { field_span =>
+                        ::cache_diff::uses_reached(#read_old, #limit)
+                    };
+                    (reached.clone(), reached)
+                }
+                // Located at the function so a mismatched signature is reported on the attribute
+                (None, None, None, Some(compare)) => {
+                    let span = located_at(compare);
+                    (
+                        quote::quote_spanned! { span => !#compare(#read_old, #read_self) },
+                        quote::quote_spanned! { span => !#compare(#read_old, #read_now) },
                     )
-                );
+                }
+                (None, None, None, None) => (
+                    quote::quote_spanned! { field_span =>
+                        ::cache_diff::field_ne(#compare_self, #compare_old)
+                    },
+                    quote::quote_spanned! { field_span =>
+                        ::cache_diff::field_ne(#compare_now, #compare_old)
+                    },
+                ),
+            }
+        };
+        // A skipped field never differs, checked before the comparison
+        // The function takes either the field's values or the structs
+        let (inequality, skip) = match skip_if {
+            Some(path) => (
+                quote::quote_spanned! { located_at(path) =>
+                    (!::cache_diff::SkipIf::<Self, _, _>::should_skip(&#path, old, self, #read_old, #read_self) && #inequality)
+                },
+                quote::quote_spanned! { located_at(path) =>
+                    ::std::option::Option::Some(|now: &Self, old: &Self| {
+                        ::cache_diff::SkipIf::<Self, _, _>::should_skip(&#path, old, now, #read_old, #read_now)
+                    })
+                },
+            ),
+            None => (inequality, quote::quote! { ::std::option::Option::None }),
+        };
+        if ttl.is_some() || max.is_some() || max_uses.is_some() {
+            limits.push(inequality.clone());
+        }
+        inequalities.push(inequality);
+        let display = match display_fn {
+            // Nested metadata usually doesn't implement Display, show its initial message instead
+            DisplayFn::Identity if *nested => quote::quote_spanned! { field_span =>
+                |cache: &Self, f: &mut ::cache_diff::DisplayCallback| f(&::cache_diff::nested_display(#read_cache))
+            },
+            // A custom function is checked with errors pointing at the attribute, the check function's
+            // name tells the user which field's `display = <function>` must return a Display value
+            DisplayFn::Custom(path) => {
+                let check =
+                    quote::format_ident!("display_for_{}_must_return_display", field_identifier);
+                quote::quote_spanned! { located_at(path) =>
+                    |cache: &Self, f: &mut ::cache_diff::DisplayCallback| {
+                        fn #check<T: ::std::fmt::Display>(value: &T) -> &dyn ::std::fmt::Display {
+                            value
+                        }
+                        f(#check(&#path(#read_cache)))
+                    }
+                }
+            }
+            // Timestamps such as `SystemTime` don't implement Display
+            DisplayFn::Identity if ttl.is_some() => quote::quote_spanned! { field_span =>
+                |cache: &Self, f: &mut ::cache_diff::DisplayCallback| f(&::cache_diff::ttl_display(#read_cache))
+            },
+            DisplayFn::Identity => quote::quote_spanned! { field_span =>
+                |cache: &Self, f: &mut ::cache_diff::DisplayCallback| f(::cache_diff::field_display(#read_cache))
+            },
+            DisplayFn::PathBuf => quote::quote! {
+                |cache: &Self, f: &mut ::cache_diff::DisplayCallback| f(&#display_fn(#read_cache))
+            },
+            DisplayFn::Debug => quote::quote_spanned! { field_span =>
+                |cache: &Self, f: &mut ::cache_diff::DisplayCallback| f(&#display_fn(#read_cache))
+            },
+            DisplayFn::NormalizedPath(base) => {
+                let base = match base {
+                    Some(base) => quote::quote! { ::std::option::Option::Some(#base) },
+                    None => quote::quote! { ::std::option::Option::None },
+                };
+                quote::quote_spanned! { field_span =>
+                    |cache: &Self, f: &mut ::cache_diff::DisplayCallback| {
+                        f(&#display_fn(::std::convert::AsRef::<::std::path::Path>::as_ref(#read_cache), #base))
+                    }
+                }
+            }
+        };
+        let display_pair = match (display_pair, ttl, &max, max_uses) {
+            (_, Some(seconds), _, _) => quote::quote_spanned! { field_span =>
+                ::std::option::Option::Some(|old: &Self, _: &Self| {
+                    ::cache_diff::IntoPairDisplay::into_pair_display(::cache_diff::ttl_message(#name, #read_old, #seconds))
+                })
+            },
+            (_, None, Some(limit), _) => quote::quote_spanned! { field_span =>
+                ::std::option::Option::Some(|_: &Self, now: &Self| {
+                    ::cache_diff::IntoPairDisplay::into_pair_display(::cache_diff::max_message(#name, #read_now, #limit))
+                })
+            },
+            (_, None, None, Some(limit)) => quote::quote_spanned! { field_span =>
+                ::std::option::Option::Some(|old: &Self, _: &Self| {
+                    ::cache_diff::IntoPairDisplay::into_pair_display(::cache_diff::uses_message(#name, #read_old, #limit))
+                })
+            },
+            (Some(path), None, None, None) => quote::quote_spanned! { located_at(path) =>
+                ::std::option::Option::Some(|old: &Self, now: &Self| {
+                    ::cache_diff::IntoPairDisplay::into_pair_display(#path(#read_old, #read_now))
+                })
+            },
+            (None, None, None, None) => quote::quote! { ::std::option::Option::None },
+        };
+        let labels = match labels {
+            Some((old, now)) => quote::quote! { ::std::option::Option::Some((#old, #now)) },
+            None => quote::quote! { ::std::option::Option::None },
+        };
+        let nested_diff = if *nested {
+            quote::quote_spanned! { field_span =>
+                ::std::option::Option::Some(|now: &Self, old: &Self| {
+                    ::cache_diff::nested_diff(#read_now, #read_old)
+                })
+            }
+        } else {
+            quote::quote! { ::std::option::Option::None }
+        };
+        let custom_diff = match custom {
+            Some(path) => quote::quote_spanned! { located_at(path) =>
+                ::std::option::Option::Some(|now: &Self, old: &Self| {
+                    ::cache_diff::field_custom(#path(#read_old, #read_now))
+                })
+            },
+            None => quote::quote! { ::std::option::Option::None },
+        };
+        let template = match fmt {
+            Some(template) => quote::quote! { ::std::option::Option::Some(#template) },
+            None => quote::quote! { ::std::option::Option::None },
+        };
+        let group = match group {
+            Some(group) => quote::quote! { ::std::option::Option::Some(#group) },
+            None => quote::quote! { ::std::option::Option::None },
+        };
+        field_diffs.push(quote::quote! {
+            ::cache_diff::FieldDiff {
+                differs: |now: &Self, old: &Self| #differs,
+                display: #display,
+                lines: #lines,
+                display_pair: #display_pair,
+                labels: #labels,
+                nested: #nested_diff,
+                custom: #custom_diff,
+                template: #template,
+                skip: #skip,
+                group: #group,
             }
         });
     }
     let (impl_generics, type_generics, where_clause) = container.generics.split_for_impl();
+    // Fields are copied from the remote type, a missing or mismatched field is a compile error
+    let remote = container.remote.as_ref().map(|remote| {
+        let fields = container.all_fields.iter().map(|field| {
+            quote::quote_spanned! { located_at(field) =>
+                #field: ::std::clone::Clone::clone(&remote.#field)
+            }
+        });
+        quote::quote! {
+            impl #impl_generics #ident #type_generics #where_clause {
+                /// Copy the compared values out of the remote type
+                pub fn from_remote(remote: &#remote) -> Self {
+                    Self {
+                        #(#fields,)*
+                    }
+                }
+
+                /// Like `CacheDiff::diff` for two values of the remote type
+                pub fn diff_remote(now: &#remote, old: &#remote) -> ::std::vec::Vec<String> {
+                    ::cache_diff::CacheDiff::diff(&Self::from_remote(now), &Self::from_remote(old))
+                }
+
+                /// Like `CacheDiff::diff_structured` for two values of the remote type
+                pub fn diff_structured_remote(
+                    now: &#remote,
+                    old: &#remote,
+                ) -> ::std::vec::Vec<::cache_diff::Difference> {
+                    ::cache_diff::CacheDiff::diff_structured(&Self::from_remote(now), &Self::from_remote(old))
+                }
+            }
+        }
+    });
+    // Limits aren't part of the hash, a cache that expired or reached a limit differs even when the hashes match
+    let differs_from_hash = (container.content_hash && !limits.is_empty()).then(|| {
+        quote::quote! {
+            fn differs_from_hash(&self, old: &Self, old_hash: ::std::option::Option<u64>) -> bool {
+                match (self.content_hash(), old_hash) {
+                    (::std::option::Option::Some(now_hash), ::std::option::Option::Some(old_hash))
+                        if now_hash == old_hash => #(#limits)||*,
+                    _ => self.differs(old),
+                }
+            }
+        }
+    });
+
     Ok(quote::quote! {
+        #remote
+
         impl #impl_generics ::cache_diff::CacheDiff for #ident #type_generics #where_clause {
+            const FIELDS: &'static [::cache_diff::FieldInfo] = &[#(#field_infos),*];
+            #field_aliases
+
             fn diff(&self, old: &Self) -> ::std::vec::Vec<String> {
-                let mut differences = ::std::vec::Vec::new();
+                #(#unknown_attributes)*
+                ::cache_diff::render_differences(self, &self.diff_structured(old))
+            }
+
+            fn diff_structured(&self, old: &Self) -> ::std::vec::Vec<::cache_diff::Difference> {
+                self.diff_structured_filtered(old, &::cache_diff::IgnoreSet::new())
+            }
+
+            fn diff_structured_filtered(
+                &self,
+                old: &Self,
+                ignore: &::cache_diff::IgnoreSet,
+            ) -> ::std::vec::Vec<::cache_diff::Difference> {
                 #custom_diff
-                #(#comparisons)*
+                self.visit_field_diffs(&mut |fields| {
+                    ::cache_diff::diff_fields(self, old, Self::FIELDS, fields, ignore, &mut differences);
+                });
                 differences
             }
+
+            fn visit_field_diffs(&self, visit: &mut dyn FnMut(&[::cache_diff::FieldDiff<Self>])) {
+                visit(&[#(#field_diffs),*]);
+            }
+
+            fn differs(&self, old: &Self) -> bool {
+                #(#custom_signatures)*
+                #(#custom_differs)* #(#inequalities)||*
+            }
+
+            fn explain(&self) -> &'static [::cache_diff::FieldExplanation] {
+                &[#(#explanations),*]
+            }
+
+            #style
+            #template
+            #description
+            #content_hash
+            #differs_from_hash
+            #sample_changed
+            #record_reuse
         }
     })
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_did_you_mean() {
+        let known = || {
+            ["rename", "display", "ignore", "category", "code"]
+                .map(String::from)
+                .into_iter()
+        };
+        assert_eq!(did_you_mean("renmae", known()), Some("rename".to_string()));
+        assert_eq!(
+            did_you_mean("catgory", known()),
+            Some("category".to_string())
+        );
+        assert_eq!(did_you_mean("cod", known()), Some("code".to_string()));
+        assert_eq!(did_you_mean("unknown", known()), None);
+    }
+
+    #[test]
+    fn test_expansion_is_one_deterministic_impl() {
+        let input = quote::quote! {
+            #[cache_diff(custom = diff_os, custom = diff_usage)]
+            struct Metadata {
+                version: String,
+                #[cache_diff(ignore = "custom")]
+                distro: String,
+            }
+        };
+        let first = create_cache_diff(input.clone()).unwrap().to_string();
+        let second = create_cache_diff(input).unwrap().to_string();
+
+        assert_eq!(first, second);
+        assert!(
+            first.starts_with("impl :: cache_diff :: CacheDiff for Metadata"),
+            "{first}"
+        );
+        assert_eq!(
+            first.matches("impl ").count(),
+            1,
+            "Expected a single impl and no free items: {first}"
+        );
+    }
+}